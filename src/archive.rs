@@ -0,0 +1,127 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::BufReader,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::json::JsonSerde;
+use crate::model::{Object, ObjectOrLink};
+
+/**
+ * Reader of an extracted Mastodon account export.
+ *
+ * Reference: https://docs.joinmastodon.org/user/moving/#export
+ */
+pub struct MastodonArchive {
+    root: PathBuf,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MediaEntry {
+    pub url: String,
+    pub media_type: Option<String>,
+    pub path: PathBuf,
+}
+
+impl MastodonArchive {
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self, Box<dyn Error>> {
+        let root = root.as_ref().to_path_buf();
+        if !root.join("actor.json").is_file() {
+            return Err(format!("{} is not a Mastodon archive", root.display()).into());
+        }
+        Ok(Self { root })
+    }
+
+    pub fn actor(&self) -> Result<Object, Box<dyn Error>> {
+        self.read_object("actor.json")
+    }
+
+    pub fn outbox(&self) -> Result<Object, Box<dyn Error>> {
+        self.read_object("outbox.json")
+    }
+
+    pub fn likes(&self) -> Result<Object, Box<dyn Error>> {
+        self.read_object("likes.json")
+    }
+
+    pub fn bookmarks(&self) -> Result<Object, Box<dyn Error>> {
+        self.read_object("bookmarks.json")
+    }
+
+    pub fn activities(&self) -> Result<Vec<ObjectOrLink>, Box<dyn Error>> {
+        let outbox = self.outbox()?;
+        let mut dest = outbox.ordered_collection_items.ordered_items;
+        dest.extend(outbox.collection_items.items);
+        Ok(dest)
+    }
+
+    /**
+     * Media files referenced by the actor and the posted objects. Files which
+     * are not contained in the archive are skipped, as are URLs whose path
+     * would leave the archive root.
+     */
+    pub fn media(&self) -> Result<Vec<MediaEntry>, Box<dyn Error>> {
+        let mut attachments = vec![];
+        let actor = self.actor()?;
+        attachments.extend(actor.object_items.icon.iter());
+        attachments.extend(actor.object_items.image.iter());
+
+        let activities = self.activities()?;
+        for activity in activities.iter() {
+            if let ObjectOrLink::Object(activity) = activity {
+                for object in activity.activity_items.object.iter() {
+                    if let ObjectOrLink::Object(object) = object {
+                        attachments.extend(object.object_items.attachment.iter());
+                    }
+                }
+            }
+        }
+
+        let mut dest = vec![];
+        for attachment in attachments {
+            let (url, media_type) = match attachment {
+                ObjectOrLink::Link(link) => (&link.href, link.media_type.first()),
+                ObjectOrLink::Object(object) => match &object.object_items.url {
+                    None => continue,
+                    Some(link) => (
+                        &link.href,
+                        object
+                            .object_items
+                            .media_type
+                            .first()
+                            .or(link.media_type.first()),
+                    ),
+                },
+            };
+            let path = match self.media_path(url) {
+                None => continue,
+                Some(path) => path,
+            };
+            if path.is_file() {
+                dest.push(MediaEntry {
+                    url: url.clone(),
+                    media_type: media_type.cloned(),
+                    path,
+                });
+            }
+        }
+        Ok(dest)
+    }
+
+    fn media_path(&self, url: &str) -> Option<PathBuf> {
+        let relative = Path::new(url.trim_start_matches('/'));
+        for component in relative.components() {
+            match component {
+                Component::Normal(_) | Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+            }
+        }
+        Some(self.root.join(relative))
+    }
+
+    fn read_object(&self, name: &str) -> Result<Object, Box<dyn Error>> {
+        let file = File::open(self.root.join(name))?;
+        Object::io_read_json(BufReader::new(file))
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::model::{Object, ObjectOrLink};
+
+/**
+ * A way in which the attachments of an object break an `AttachmentPolicy`.
+ * Attachments are identified by their index in `attachment`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Violation {
+    TooMany {
+        count: usize,
+        max: usize,
+    },
+    MediaTypeNotAllowed {
+        index: usize,
+        media_type: String,
+    },
+    // Declared dimensions over the limit.
+    TooLarge {
+        index: usize,
+        width: u64,
+        height: u64,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooMany { count, max } => {
+                write!(f, "{} attachments exceed the limit of {}", count, max)
+            }
+            Self::MediaTypeNotAllowed { index, media_type } => write!(
+                f,
+                "attachment {} has media type {}, which is not allowed",
+                index, media_type
+            ),
+            Self::TooLarge {
+                index,
+                width,
+                height,
+            } => write!(
+                f,
+                "attachment {} is {}x{}, which is too large",
+                index, width, height
+            ),
+        }
+    }
+}
+
+/**
+ * Limits on the attachments of objects, for servers which do not accept
+ * arbitrary media. Attachments without a declared media type or
+ * dimensions are not checked for them.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct AttachmentPolicy {
+    pub max_count: usize,
+    // Essences of media types, where `image/*` matches all subtypes. An
+    // empty list allows any.
+    pub allowed_media_types: Vec<String>,
+    pub max_width: u64,
+    pub max_height: u64,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        Self {
+            max_count: 16,
+            allowed_media_types: vec![
+                "image/*".to_string(),
+                "video/*".to_string(),
+                "audio/*".to_string(),
+            ],
+            max_width: 16384,
+            max_height: 16384,
+        }
+    }
+}
+
+impl AttachmentPolicy {
+    /**
+     * The violations of the attachments of the object, for validating it.
+     */
+    pub fn check(&self, object: &Object) -> Vec<Violation> {
+        let attachment = &object.object_items.attachment;
+        let mut dest: Vec<Violation> = attachment
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| self.check_item(index, item))
+            .collect();
+        if attachment.len() > self.max_count {
+            dest.insert(
+                0,
+                Violation::TooMany {
+                    count: attachment.len(),
+                    max: self.max_count,
+                },
+            );
+        }
+        dest
+    }
+
+    /**
+     * Drops the attachments which violate the policy and the ones over the
+     * limit, for accepting the object from an inbox without them, as
+     * Mastodon does. Returns the violations found before dropping.
+     */
+    pub fn enforce(&self, object: &mut Object) -> Vec<Violation> {
+        let violations = self.check(object);
+        let mut index = 0;
+        object.object_items.attachment.retain(|_| {
+            let keep = !violations.iter().any(|violation| match violation {
+                Violation::TooMany { .. } => false,
+                Violation::MediaTypeNotAllowed { index: at, .. }
+                | Violation::TooLarge { index: at, .. } => *at == index,
+            });
+            index += 1;
+            keep
+        });
+        object.object_items.attachment.truncate(self.max_count);
+        violations
+    }
+
+    fn check_item(&self, index: usize, item: &ObjectOrLink) -> Option<Violation> {
+        let (media_type, width, height) = declared(item);
+        if let Some(media_type) = media_type {
+            if !self.allows_media_type(media_type) {
+                return Some(Violation::MediaTypeNotAllowed {
+                    index,
+                    media_type: media_type.to_string(),
+                });
+            }
+        }
+        let (width, height) = (width.unwrap_or_default(), height.unwrap_or_default());
+        (width > self.max_width || height > self.max_height).then_some(Violation::TooLarge {
+            index,
+            width,
+            height,
+        })
+    }
+
+    pub fn allows_media_type(&self, media_type: &str) -> bool {
+        let essence = media_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        self.allowed_media_types.is_empty()
+            || self.allowed_media_types.iter().any(|allowed| {
+                let allowed = allowed.to_ascii_lowercase();
+                match allowed.strip_suffix("/*") {
+                    Some(top) => essence.split_once('/').is_some_and(|(t, _)| t == top),
+                    None => essence == allowed,
+                }
+            })
+    }
+}
+
+// The media type and dimensions declared by a link, or by a document or its
+// `url`. Documents carry dimensions as properties of Mastodon.
+fn declared(item: &ObjectOrLink) -> (Option<&str>, Option<u64>, Option<u64>) {
+    match item {
+        ObjectOrLink::Link(link) => (
+            link.media_type.first().map(String::as_str),
+            link.width.map(|width| width as u64),
+            link.height.map(|height| height as u64),
+        ),
+        ObjectOrLink::Object(object) => {
+            let url = object.object_items.url.as_ref();
+            let dimension = |name: &str, of_url: Option<usize>| match object
+                .json_items
+                .unknown_properties
+                .get(name)
+            {
+                Some(Value::Number(value)) => value.as_u64(),
+                _ => of_url.map(|value| value as u64),
+            };
+            (
+                object
+                    .object_items
+                    .media_type
+                    .first()
+                    .or_else(|| url.and_then(|url| url.media_type.first()))
+                    .map(String::as_str),
+                dimension("width", url.and_then(|url| url.width)),
+                dimension("height", url.and_then(|url| url.height)),
+            )
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use url::Origin;
+
+use crate::model::{Object, ObjectOrLink};
+use crate::origin::origin_of;
+use crate::visit::{walk, Visitor};
+
+/**
+ * A suspicious pattern in a document. None of them makes the document
+ * invalid, but they are worth logging or rejecting on a server which
+ * receives documents from anyone.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Finding {
+    // An object id whose scheme is not `https`.
+    NonHttpsId(String),
+    // An actor or author on another origin than the object.
+    OriginMismatch {
+        id: String,
+        actor: String,
+    },
+    LargeCollection {
+        id: Option<String>,
+        total_items: u64,
+    },
+    // A `data:` URL, with its media type and parameters.
+    DataUrl(String),
+    // An embedded object on another origin than the root object, which
+    // its origin did not vouch for.
+    ForeignEmbedded(String),
+}
+
+/**
+ * Limits of the audit, over which collections are reported.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Audit {
+    pub max_total_items: u64,
+    // Items embedded in one collection.
+    pub max_items: usize,
+}
+
+impl Default for Audit {
+    fn default() -> Self {
+        Self {
+            max_total_items: 100_000_000,
+            max_items: 10_000,
+        }
+    }
+}
+
+impl Audit {
+    /**
+     * The findings in the object and the ones embedded in it, in the order
+     * they are walked.
+     */
+    pub fn audit(&self, object: &Object) -> Vec<Finding> {
+        let mut auditor = Auditor {
+            audit: self,
+            root: None,
+            findings: vec![],
+        };
+        walk(object, &mut auditor);
+        auditor.findings
+    }
+}
+
+struct Auditor<'a> {
+    audit: &'a Audit,
+    // The origin of the root object, once it is visited.
+    root: Option<Option<Origin>>,
+    findings: Vec<Finding>,
+}
+
+impl Visitor for Auditor<'_> {
+    fn visit_object(&mut self, object: &Object) {
+        let origin = object.id.as_deref().and_then(origin_of);
+        match &self.root {
+            None => self.root = Some(origin.clone()),
+            Some(root) => {
+                if let (Some(id), Some(_)) = (&object.id, &origin) {
+                    if root.is_some() && origin != *root {
+                        self.findings.push(Finding::ForeignEmbedded(id.clone()));
+                    }
+                }
+            }
+        }
+
+        if let Some(id) = &object.id {
+            if !id.starts_with("https://") && !id.starts_with("_:") {
+                self.findings.push(Finding::NonHttpsId(id.clone()));
+            }
+            for actor in object
+                .activity_items
+                .actor
+                .iter()
+                .chain(&object.object_items.attributed_to)
+                .filter_map(ObjectOrLink::id)
+            {
+                if origin.is_some() && origin_of(actor) != origin {
+                    self.findings.push(Finding::OriginMismatch {
+                        id: id.clone(),
+                        actor: actor.to_string(),
+                    });
+                }
+            }
+        }
+
+        let items = object.collection_items.items.len()
+            + object.ordered_collection_items.ordered_items.len();
+        let total_items = object.collection_items.total_items.unwrap_or_default();
+        if total_items > self.audit.max_total_items || items > self.audit.max_items {
+            self.findings.push(Finding::LargeCollection {
+                id: object.id.clone(),
+                total_items: total_items.max(items as u64),
+            });
+        }
+    }
+
+    fn visit_iri(&mut self, iri: &str) {
+        if iri
+            .get(..5)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        {
+            let header = iri[5..].split(',').next().unwrap_or_default();
+            let finding = Finding::DataUrl(header.to_string());
+            if !self.findings.contains(&finding) {
+                self.findings.push(finding);
+            }
+        }
+    }
+}
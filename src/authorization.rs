@@ -0,0 +1,89 @@
+use std::error::Error;
+
+use crate::model::{Object, ObjectOrLink};
+use crate::origin::same_origin;
+use crate::routes::Locality;
+
+/**
+ * Checks that the actor of a `Delete`, `Update` or `Undo` may apply it to
+ * `target`, the stored version of its object. The actor must be the target
+ * itself or its author, or be on the same origin as a target which names no
+ * author. Otherwise it must be a moderator of a group the target is
+ * addressed to, which `is_moderator` decides from the actor and group IRIs.
+ */
+pub fn authorize(
+    activity: &Object,
+    target: &Object,
+    is_moderator: impl Fn(&str, &str) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    authorize_with(activity, target, same_origin, is_moderator)
+}
+
+/**
+ * `authorize` on a server hosting several tenants, which may share an
+ * origin. An actor is on the same side as a local target which names no
+ * author only if they are of the same tenant.
+ */
+pub fn authorize_local<L: Locality + ?Sized>(
+    activity: &Object,
+    target: &Object,
+    local: &L,
+    is_moderator: impl Fn(&str, &str) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    authorize_with(
+        activity,
+        target,
+        |target, actor| match local.base_of(target) {
+            Some(base) => local.base_of(actor) == Some(base),
+            None => same_origin(target, actor),
+        },
+        is_moderator,
+    )
+}
+
+fn authorize_with(
+    activity: &Object,
+    target: &Object,
+    same_origin: impl Fn(&str, &str) -> bool,
+    is_moderator: impl Fn(&str, &str) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    let actor = match activity.activity_items.actor.as_slice() {
+        [actor] => actor.id().ok_or("actor has no id")?,
+        _ => return Err("activity must have a single actor".into()),
+    };
+    let target_id = target.id.as_deref().ok_or("target has no id")?;
+    if !activity
+        .activity_items
+        .object
+        .iter()
+        .any(|object| object.id() == Some(target_id))
+    {
+        return Err(format!("{} is not the object of the activity", target_id).into());
+    }
+
+    let owners = target
+        .object_items
+        .attributed_to
+        .iter()
+        .chain(&target.activity_items.actor)
+        .filter_map(ObjectOrLink::id);
+    if target_id == actor
+        || owners.clone().any(|owner| owner == actor)
+        || (owners.count() == 0 && same_origin(target_id, actor))
+    {
+        return Ok(());
+    }
+
+    let items = &target.object_items;
+    if items
+        .audience
+        .iter()
+        .chain(&items.to)
+        .chain(&items.cc)
+        .filter_map(ObjectOrLink::id)
+        .any(|group| is_moderator(actor, group))
+    {
+        return Ok(());
+    }
+    Err(format!("{} is not allowed to modify {}", actor, target_id).into())
+}
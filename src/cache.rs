@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::clock::{Clock, SystemClock};
+use crate::model::{Map, Object};
+use crate::observe::{NoopObserver, Observer};
+use crate::resolver::{Resolver, Revalidated, Validators};
+
+/**
+ * How long resolved objects are cached. Actors, which rarely change and are
+ * looked up for every activity they send, are kept longer than other
+ * objects; `by_type` overrides both for objects of a type, e.g. `Question`
+ * whose votes change often.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CacheTtls {
+    pub actor: Duration,
+    pub object: Duration,
+    pub by_type: Map<String, Duration>,
+}
+
+impl Default for CacheTtls {
+    fn default() -> Self {
+        Self {
+            actor: Duration::days(1),
+            object: Duration::minutes(10),
+            by_type: Map::new(),
+        }
+    }
+}
+
+impl CacheTtls {
+    /**
+     * How long the object is cached, by the first of its types with a TTL
+     * in `by_type`.
+     */
+    pub fn ttl_of(&self, object: &Object) -> Duration {
+        if let Some(ttl) = object.typ.iter().find_map(|typ| self.by_type.get(typ)) {
+            return *ttl;
+        }
+        if object.actor_items.is_some() {
+            self.actor
+        } else {
+            self.object
+        }
+    }
+}
+
+/**
+ * A resolver caching the objects of another, e.g. one fetching them. An
+ * `Update` or `Delete` received for a cached object is to be passed to
+ * `invalidate_for`, so that the cache does not serve it until it expires.
+ * Expired objects are kept until evicted, and revalidated with their
+ * validators if the resolver gave any, so that an unchanged object is not
+ * fetched again.
+ */
+pub struct CachingResolver<R> {
+    pub resolver: R,
+    pub observer: Box<dyn Observer>,
+    // The time objects resolved by `resolve` are cached at.
+    pub clock: Box<dyn Clock>,
+    pub ttls: CacheTtls,
+    // Objects kept at most. Beyond it, expired ones are dropped, then the
+    // ones expiring first.
+    pub max_entries: usize,
+    entries: RwLock<Map<String, Entry>>,
+}
+
+struct Entry {
+    object: Object,
+    validators: Validators,
+    expires_at: DateTime<Utc>,
+}
+
+impl<R: Resolver> CachingResolver<R> {
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver,
+            observer: Box::new(NoopObserver),
+            clock: Box::new(SystemClock),
+            ttls: CacheTtls::default(),
+            max_entries: 10_000,
+            entries: RwLock::new(Map::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().map_or(0, |entries| entries.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * The object of the IRI, from the cache unless expired at `now`. An
+     * expired object which the resolver finds not modified is kept for
+     * another TTL. Failures are not cached.
+     */
+    pub fn resolve_at(&self, iri: &str, now: DateTime<Utc>) -> Result<Object, Box<dyn Error>> {
+        let validators = {
+            let entries = self.entries.read().map_err(|err| err.to_string())?;
+            match entries.get(iri) {
+                Some(entry) if entry.expires_at > now => {
+                    self.observer.fetch_cache_hit(iri);
+                    return Ok(entry.object.clone());
+                }
+                Some(entry) => entry.validators.clone(),
+                None => Validators::default(),
+            }
+        };
+        self.observer.fetch_cache_miss(iri);
+        let (object, validators) = match self.resolver.resolve_if_modified(iri, &validators)? {
+            Revalidated::Modified(object, validators) => (object, validators),
+            Revalidated::NotModified => {
+                let mut entries = self.entries.write().map_err(|err| err.to_string())?;
+                if let Some(entry) = entries.get_mut(iri) {
+                    entry.expires_at = now + self.ttls.ttl_of(&entry.object);
+                    return Ok(entry.object.clone());
+                }
+                // Evicted meanwhile.
+                (self.resolver.resolve(iri)?, Validators::default())
+            }
+        };
+        let mut entries = self.entries.write().map_err(|err| err.to_string())?;
+        if entries.len() >= self.max_entries && !entries.contains_key(iri) {
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+        if entries.len() >= self.max_entries && !entries.contains_key(iri) {
+            let first = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(key, _)| key.clone());
+            if let Some(first) = first {
+                remove(&mut entries, &first);
+            }
+        }
+        entries.insert(
+            iri.to_string(),
+            Entry {
+                object: object.clone(),
+                validators,
+                expires_at: now + self.ttls.ttl_of(&object),
+            },
+        );
+        Ok(object)
+    }
+
+    /**
+     * Drops the object from the cache, whether cached by its id or another
+     * IRI resolving to it. Returns whether it was cached.
+     */
+    pub fn invalidate(&self, id: &str) -> bool {
+        !self
+            .invalidate_where(|key, object| key == id || object.id.as_deref() == Some(id))
+            .is_empty()
+    }
+
+    /**
+     * Drops the objects which an incoming `Update` or `Delete` changes, and
+     * the objects by an actor deleting itself. Returns the IRIs dropped.
+     * Other activities change nothing.
+     */
+    pub fn invalidate_for(&self, activity: &Object) -> Vec<String> {
+        let items = &activity.activity_items;
+        let has_type = |name: &str| activity.typ.iter().any(|typ| typ == name);
+        if !has_type("Update") && !has_type("Delete") {
+            return vec![];
+        }
+        let ids: Vec<&str> = items
+            .object
+            .iter()
+            .filter_map(|object| object.id())
+            .collect();
+        let deleted_actors: Vec<&str> = items
+            .actor
+            .iter()
+            .filter_map(|actor| actor.id())
+            .filter(|actor| has_type("Delete") && ids.contains(actor))
+            .collect();
+        self.invalidate_where(|key, object| {
+            let is_by_deleted = object
+                .object_items
+                .attributed_to
+                .iter()
+                .chain(&object.activity_items.actor)
+                .any(|actor| {
+                    actor
+                        .id()
+                        .is_some_and(|actor| deleted_actors.contains(&actor))
+                });
+            ids.iter()
+                .any(|id| key == *id || object.id.as_deref() == Some(id))
+                || is_by_deleted
+        })
+    }
+
+    fn invalidate_where<F: Fn(&str, &Object) -> bool>(&self, matches: F) -> Vec<String> {
+        let Ok(mut entries) = self.entries.write() else {
+            return vec![];
+        };
+        let keys: Vec<String> = entries
+            .iter()
+            .filter(|(key, entry)| matches(key, &entry.object))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &keys {
+            remove(&mut entries, key);
+        }
+        keys
+    }
+}
+
+impl<R: Resolver> Resolver for CachingResolver<R> {
+    fn resolve(&self, iri: &str) -> Result<Object, Box<dyn Error>> {
+        self.resolve_at(iri, self.clock.now())
+    }
+}
+
+fn remove(entries: &mut Map<String, Entry>, key: &str) {
+    entries.shift_remove(key);
+}
@@ -0,0 +1,80 @@
+use crate::mention::acct_at;
+
+// Length of any URL, as Mastodon counts it.
+pub const URL_LENGTH: usize = 23;
+
+// The default limit of Mastodon.
+pub const MAX_CHARS: usize = 500;
+
+/**
+ * The length of plain text as Mastodon counts it for the limit of a status:
+ * an http(s) URL counts as `URL_LENGTH`, and a mention as `@user` without
+ * its host. Other text counts by grapheme clusters with the `graphemes`
+ * feature, and by chars without it, which differ only for combined
+ * characters such as emoji sequences.
+ *
+ * Reference: https://github.com/mastodon/mastodon/blob/main/app/validators/status_length_validator.rb
+ */
+pub fn char_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut plain = 0;
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let entity = if let Some(end) = url_at(text, pos) {
+            Some((URL_LENGTH, end))
+        } else if rest.starts_with('@') {
+            acct_at(text, pos).map(|(acct, end)| (1 + acct.user.chars().count(), end))
+        } else {
+            None
+        };
+        match entity {
+            Some((len, end)) => {
+                count += text_len(&text[plain..pos]) + len;
+                pos = end;
+                plain = end;
+            }
+            None => pos += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    count + text_len(&text[plain..])
+}
+
+/**
+ * The length of a status with a content warning, which counts toward the
+ * same limit.
+ */
+pub fn status_char_count(summary: Option<&str>, text: &str) -> usize {
+    summary.map_or(0, char_count) + char_count(text)
+}
+
+// The end of an http(s) URL starting at `start`, without trailing
+// punctuation.
+fn url_at(text: &str, start: usize) -> Option<usize> {
+    let rest = &text[start..];
+    let scheme = ["https://", "http://"].into_iter().find(|scheme| {
+        rest.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    })?;
+    if text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '/' || c == '@')
+    {
+        return None;
+    }
+    let len: usize = rest
+        .chars()
+        .take_while(|c| !c.is_whitespace() && !matches!(c, '<' | '>' | '"'))
+        .map(char::len_utf8)
+        .sum();
+    let url = rest[..len].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'']);
+    (url.len() > scheme.len()).then_some(start + url.len())
+}
+
+fn text_len(text: &str) -> usize {
+    #[cfg(feature = "graphemes")]
+    return unicode_segmentation::UnicodeSegmentation::graphemes(text, true).count();
+    #[cfg(not(feature = "graphemes"))]
+    text.chars().count()
+}
@@ -0,0 +1,76 @@
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+/**
+ * The source of the current time for what the crate stamps by itself, so
+ * that tests and replays produce the same documents every time. Functions
+ * taking `now` leave the clock to the caller instead.
+ */
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/**
+ * The time of the system.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/**
+ * A time standing still.
+ */
+impl Clock for DateTime<Utc> {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+    }
+}
+
+impl<F: Fn() -> DateTime<Utc> + Send + Sync> Clock for F {
+    fn now(&self) -> DateTime<Utc> {
+        self()
+    }
+}
+
+/**
+ * A clock which moves only when told, e.g. to step through retries in
+ * tests.
+ */
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        if let Ok(mut current) = self.now.lock() {
+            *current = now;
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        if let Ok(mut current) = self.now.lock() {
+            *current += duration;
+        }
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now
+            .lock()
+            .map_or_else(|err| **err.get_ref(), |now| *now)
+    }
+}
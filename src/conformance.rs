@@ -0,0 +1,108 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::json::JsonSerde;
+use crate::model::Object;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum FixtureOutcome {
+    Passed,
+    ParseFailed(String),
+    // The object differs after it is serialized and parsed again. Holds the
+    // JSON Pointer of the first difference, or the error.
+    RoundTripFailed(String),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct FixtureReport {
+    pub path: PathBuf,
+    pub outcome: FixtureOutcome,
+}
+
+impl FixtureReport {
+    pub fn is_passed(&self) -> bool {
+        self.outcome == FixtureOutcome::Passed
+    }
+}
+
+/**
+ * Parses each `.json` file in `dir` as an object and round trips it, e.g. to
+ * check documents of other servers before upgrading. Reports are sorted by
+ * path.
+ */
+pub fn check_fixtures<P: AsRef<Path>>(dir: P) -> Result<Vec<FixtureReport>, Box<dyn Error>> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut dest = Vec::with_capacity(paths.len());
+    for path in paths {
+        let outcome = check_fixture(&fs::read(&path)?);
+        dest.push(FixtureReport { path, outcome });
+    }
+    Ok(dest)
+}
+
+/**
+ * Parses a document as an object and round trips it: the object written,
+ * parsed again and written must be written the same. Dates are written in
+ * whole seconds, so their fractions are not taken as a difference.
+ */
+pub fn check_fixture(bytes: &[u8]) -> FixtureOutcome {
+    let object = match Object::from_json_bytes(bytes) {
+        Ok(object) => object,
+        Err(err) => return FixtureOutcome::ParseFailed(err.to_string()),
+    };
+    let written = object.to_value().and_then(|expected| {
+        let actual = Object::from_value(&expected)?.to_value()?;
+        Ok((expected, actual))
+    });
+    match written {
+        Ok((expected, actual)) if expected == actual => FixtureOutcome::Passed,
+        Ok((expected, actual)) => FixtureOutcome::RoundTripFailed(format!(
+            "differs at {}",
+            find_difference(&expected, &actual, "")
+                .as_deref()
+                .unwrap_or("(unknown)")
+        )),
+        Err(err) => FixtureOutcome::RoundTripFailed(err.to_string()),
+    }
+}
+
+fn find_difference(expected: &Value, actual: &Value, path: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<_> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter().find_map(|key| {
+                let path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => find_difference(expected, actual, &path),
+                    _ => Some(path),
+                }
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .find_map(|(index, (expected, actual))| {
+                    find_difference(expected, actual, &format!("{}/{}", path, index))
+                })
+        }
+        _ if expected == actual => None,
+        _ => Some(path.to_string()),
+    }
+}
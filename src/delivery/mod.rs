@@ -0,0 +1,408 @@
+use std::error::Error;
+
+use crate::federation::{FederationPolicy, Verdict};
+use crate::model::{Object, ObjectOrLink};
+use crate::origin::{host_of, origin_of};
+use crate::resolver::Resolver;
+use crate::routes::Locality;
+use crate::store::ObjectStore;
+use crate::visit::{walk_mut, VisitorMut};
+use indexmap::{IndexMap, IndexSet};
+
+pub mod queue;
+
+// The special collection addressing everyone, which has no inbox.
+pub const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+// Pages of a collection read to find recipients.
+const MAX_COLLECTION_PAGES: usize = 100;
+
+// Layers of objects looked through for one owned by the server to decide
+// forwarding.
+const MAX_FORWARDING_DEPTH: usize = 3;
+
+/**
+ * Whether the IRI is the public collection, including its compact forms.
+ */
+pub fn is_public(iri: &str) -> bool {
+    matches!(iri, PUBLIC | "as:Public" | "Public")
+}
+
+impl Object {
+    /**
+     * The activity to transmit, without `bto` and `bcc` in it and the objects
+     * embedded in it. The original keeps them to compute the recipients.
+     *
+     * Reference: https://www.w3.org/TR/activitypub/#client-to-server-interactions
+     */
+    pub fn prepare_for_delivery(&self) -> Object {
+        let mut dest = self.clone();
+        walk_mut(&mut dest, &mut BlindRecipientStripper);
+        dest
+    }
+}
+
+struct BlindRecipientStripper;
+
+impl VisitorMut for BlindRecipientStripper {
+    fn visit_object(&mut self, object: &mut Object) {
+        object.object_items.bto.clear();
+        object.object_items.bcc.clear();
+    }
+}
+
+/**
+ * How the recipients of an activity are turned into inboxes.
+ * `collection_depth` is the number of layers of remote collections
+ * dereferenced to find recipients, which the specification requires to be
+ * limited. Collections owned by the server, e.g. a list of followers
+ * collections, are expanded up to `local_collection_depth` layers.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Targeting {
+    pub use_shared_inbox: bool,
+    pub collection_depth: usize,
+    pub local_collection_depth: usize,
+}
+
+impl Default for Targeting {
+    fn default() -> Self {
+        Self {
+            use_shared_inbox: true,
+            collection_depth: 1,
+            local_collection_depth: 3,
+        }
+    }
+}
+
+/**
+ * The inboxes to deliver the activity to, without duplicates. Recipients in
+ * `to`, `bto`, `cc`, `bcc` and `audience` are resolved, and collections are
+ * expanded to their items. Objects which are local by `local` are read from
+ * `store`, and the others from `resolver`. The actor of the activity is
+ * excluded, and so are local inboxes, which get it locally; other tenants
+ * sharing the origin are delivered to. Recipients which fail to resolve are
+ * skipped, and recipients and inboxes rejected by `policy` are neither
+ * resolved nor delivered to.
+ *
+ * Reference: https://www.w3.org/TR/activitypub/#delivery
+ */
+pub fn delivery_targets<L, S, R>(
+    activity: &Object,
+    local: &L,
+    store: &S,
+    resolver: &R,
+    policy: &dyn FederationPolicy,
+    targeting: &Targeting,
+) -> Vec<String>
+where
+    L: Locality + ?Sized,
+    S: ObjectStore + ?Sized,
+    R: Resolver + ?Sized,
+{
+    let is_local = |iri: &str| local.is_local(iri);
+    let mut targets = Targets {
+        store,
+        resolver,
+        policy,
+        targeting,
+        is_local: &is_local,
+        senders: activity
+            .activity_items
+            .actor
+            .iter()
+            .filter_map(ObjectOrLink::id)
+            .collect(),
+        inboxes: vec![],
+    };
+    let items = &activity.object_items;
+    for recipient in items
+        .to
+        .iter()
+        .chain(&items.bto)
+        .chain(&items.cc)
+        .chain(&items.bcc)
+        .chain(&items.audience)
+    {
+        targets.add(recipient, 0);
+    }
+
+    let dest: IndexSet<String> = targets
+        .inboxes
+        .into_iter()
+        .filter(|inbox| !is_local(inbox))
+        .collect();
+    dest.into_iter().collect()
+}
+
+/**
+ * The inboxes to forward a received activity to. They are empty unless the
+ * activity is addressed to a collection owned by the server, e.g.
+ * the followers of a local actor, and its `inReplyTo`, `object`, `target` or
+ * `tag` is an object owned by the server, directly or through up to
+ * `MAX_FORWARDING_DEPTH` layers of such objects. The addressed local
+ * collections are expanded from `store`, and inboxes on the server and the
+ * origin of the activity are excluded, as are the ones rejected by `policy`.
+ *
+ * The caller forwards the activity unmodified, so that its signature still
+ * verifies, and only the first time it is received.
+ *
+ * Reference: https://www.w3.org/TR/activitypub/#inbox-forwarding
+ */
+pub fn forwarding_targets<L, S, R>(
+    activity: &Object,
+    local: &L,
+    store: &S,
+    resolver: &R,
+    policy: &dyn FederationPolicy,
+    targeting: &Targeting,
+) -> Vec<String>
+where
+    L: Locality + ?Sized,
+    S: ObjectStore + ?Sized,
+    R: Resolver + ?Sized,
+{
+    let is_local = |iri: &str| local.is_local(iri);
+    let items = &activity.object_items;
+    let collections: Vec<Object> = items
+        .to
+        .iter()
+        .chain(&items.cc)
+        .chain(&items.audience)
+        .filter_map(ObjectOrLink::id)
+        .filter(|id| is_local(id))
+        .filter_map(|id| store.get(id).ok().flatten())
+        .filter(is_collection)
+        .collect();
+    if collections.is_empty() || !references_local(activity, &is_local, resolver, 0) {
+        return vec![];
+    }
+
+    let mut targets = Targets {
+        store,
+        resolver,
+        policy,
+        targeting,
+        is_local: &is_local,
+        senders: activity
+            .activity_items
+            .actor
+            .iter()
+            .filter_map(ObjectOrLink::id)
+            .collect(),
+        inboxes: vec![],
+    };
+    for collection in &collections {
+        targets.add_object(collection, 0);
+    }
+    let sender = activity.id.as_deref().and_then(origin_of);
+    let dest: IndexSet<String> = targets
+        .inboxes
+        .into_iter()
+        .filter(|inbox| !is_local(inbox) && origin_of(inbox) != sender)
+        .collect();
+    dest.into_iter().collect()
+}
+
+fn references_local<R: Resolver + ?Sized>(
+    object: &Object,
+    is_local: &impl Fn(&str) -> bool,
+    resolver: &R,
+    depth: usize,
+) -> bool {
+    if depth >= MAX_FORWARDING_DEPTH {
+        return false;
+    }
+    object
+        .object_items
+        .in_reply_to
+        .iter()
+        .chain(&object.activity_items.object)
+        .chain(&object.activity_items.target)
+        .chain(&object.object_items.tag)
+        .any(|value| {
+            if value.id().is_some_and(is_local) {
+                return true;
+            }
+            match value {
+                ObjectOrLink::Object(object) if !is_reference(object) => {
+                    references_local(object, is_local, resolver, depth + 1)
+                }
+                value => value
+                    .id()
+                    .and_then(|id| resolver.resolve(id).ok())
+                    .is_some_and(|object| references_local(&object, is_local, resolver, depth + 1)),
+            }
+        })
+}
+
+struct Targets<'a, S: ?Sized, R: ?Sized> {
+    store: &'a S,
+    resolver: &'a R,
+    policy: &'a dyn FederationPolicy,
+    targeting: &'a Targeting,
+    // Whether an object is owned by the server, and read from `store`.
+    is_local: &'a dyn Fn(&str) -> bool,
+    senders: Vec<&'a str>,
+    inboxes: Vec<String>,
+}
+
+impl<S: ObjectStore + ?Sized, R: Resolver + ?Sized> Targets<'_, S, R> {
+    fn resolve(&self, id: &str) -> Result<Object, Box<dyn Error>> {
+        if (self.is_local)(id) {
+            self.store
+                .get(id)?
+                .ok_or_else(|| format!("{} is not found", id).into())
+        } else if self.policy.actor_verdict(id) == Verdict::Reject {
+            Err(format!("{} is rejected by the federation policy", id).into())
+        } else {
+            self.resolver.resolve(id)
+        }
+    }
+
+    fn add(&mut self, recipient: &ObjectOrLink, depth: usize) {
+        let id = recipient.id();
+        if id.is_some_and(|id| is_public(id) || self.senders.contains(&id)) {
+            return;
+        }
+        let object = match recipient {
+            ObjectOrLink::Object(object) if !is_reference(object) => object.clone(),
+            _ => match id.map(|id| self.resolve(id)) {
+                Some(Ok(object)) => object,
+                Some(Err(_err)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(recipient = id, error = %_err, "failed to resolve recipient");
+                    return;
+                }
+                None => return,
+            },
+        };
+        self.add_object(&object, depth);
+    }
+
+    fn add_object(&mut self, object: &Object, depth: usize) {
+        if let Some(actor_items) = &object.actor_items {
+            let shared_inbox = actor_items
+                .endpoints
+                .get("sharedInbox")
+                .filter(|_| self.targeting.use_shared_inbox);
+            let inbox = shared_inbox.unwrap_or(&actor_items.inbox);
+            if self.policy.actor_verdict(inbox) != Verdict::Reject {
+                self.inboxes.push(inbox.clone());
+            }
+            return;
+        }
+        let is_local = object.id.as_deref().is_some_and(self.is_local);
+        let max_depth = if is_local {
+            self.targeting.local_collection_depth
+        } else {
+            self.targeting.collection_depth
+        };
+        if !is_collection(object) || depth >= max_depth {
+            return;
+        }
+        let mut page = Some(object.clone());
+        let mut pages = 0;
+        while let Some(current) = page.take() {
+            for item in current
+                .collection_items
+                .items
+                .iter()
+                .chain(&current.ordered_collection_items.ordered_items)
+            {
+                self.add(item, depth + 1);
+            }
+            pages += 1;
+            if pages >= MAX_COLLECTION_PAGES {
+                break;
+            }
+            let next = current
+                .collection_items
+                .first
+                .as_deref()
+                .filter(|_| pages == 1)
+                .or(current.collection_page_items.next.as_deref());
+            page = next.and_then(|next| match next {
+                ObjectOrLink::Object(next) if !is_reference(next) => Some(next.clone()),
+                next => next.id().and_then(|id| self.resolve(id).ok()),
+            });
+        }
+    }
+}
+
+// Objects with only an id, which need to be resolved.
+fn is_reference(object: &Object) -> bool {
+    object.typ.is_empty() && object.actor_items.is_none()
+}
+
+fn is_collection(object: &Object) -> bool {
+    object.typ.iter().any(|typ| {
+        matches!(
+            typ.as_str(),
+            "Collection" | "OrderedCollection" | "CollectionPage" | "OrderedCollectionPage"
+        )
+    })
+}
+
+/**
+ * The inboxes of the recipients on one host.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct HostInboxes {
+    pub host: String,
+    pub inboxes: Vec<String>,
+}
+
+/**
+ * Whether the shared inboxes of the recipients can be used. They are for
+ * public activities and the ones addressed to collections, e.g. followers,
+ * whose members the receiving server finds by itself. Activities addressed
+ * only to the `recipients` are delivered to their own inboxes.
+ */
+pub fn uses_shared_inbox(activity: &Object, recipients: &[Object]) -> bool {
+    let items = &activity.object_items;
+    items
+        .to
+        .iter()
+        .chain(&items.bto)
+        .chain(&items.cc)
+        .chain(&items.bcc)
+        .chain(&items.audience)
+        .filter_map(ObjectOrLink::id)
+        .any(|id| {
+            is_public(id)
+                || !recipients
+                    .iter()
+                    .any(|recipient| recipient.id.as_deref() == Some(id))
+        })
+}
+
+/**
+ * Groups the inboxes of resolved recipient actors by host, in the order the
+ * hosts appear, using `endpoints.sharedInbox` if `uses_shared_inbox` allows.
+ */
+pub fn inboxes_by_host(activity: &Object, recipients: &[Object]) -> Vec<HostInboxes> {
+    let shared = uses_shared_inbox(activity, recipients);
+    let mut dest: IndexMap<String, IndexSet<String>> = IndexMap::new();
+    for actor_items in recipients
+        .iter()
+        .filter_map(|actor| actor.actor_items.as_ref())
+    {
+        let inbox = actor_items
+            .endpoints
+            .get("sharedInbox")
+            .filter(|_| shared)
+            .unwrap_or(&actor_items.inbox);
+        let host = match host_of(inbox) {
+            None => continue,
+            Some(host) => host,
+        };
+        dest.entry(host).or_default().insert(inbox.clone());
+    }
+    dest.into_iter()
+        .map(|(host, inboxes)| HostInboxes {
+            host,
+            inboxes: inboxes.into_iter().collect(),
+        })
+        .collect()
+}
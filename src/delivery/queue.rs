@@ -0,0 +1,504 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::federation::{AcceptAll, FederationPolicy, Verdict};
+use crate::model::{Map, Object};
+use crate::observe::{NoopObserver, Observer};
+use crate::origin::host_of;
+use chrono::{DateTime, Duration, Utc};
+
+pub type JobId = u64;
+
+// Longest wait between retries.
+const MAX_RETRY_DELAY_SECONDS: i64 = 24 * 60 * 60;
+
+/**
+ * Lanes of the queue, so that a flood of deletions does not delay follows
+ * and replies. Higher priorities are ordered first.
+ */
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash)]
+pub enum Priority {
+    High,
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /**
+     * Interactions awaited by a user are high and deletions are low.
+     */
+    pub fn of(activity: &Object) -> Self {
+        let is = |names: &[&str]| activity.typ.iter().any(|typ| names.contains(&typ.as_str()));
+        if is(&["Accept", "Reject", "Follow", "Undo", "Block"]) {
+            Priority::High
+        } else if is(&["Delete"]) {
+            Priority::Low
+        } else {
+            Priority::Normal
+        }
+    }
+}
+
+/**
+ * An activity to deliver to one inbox.
+ */
+#[derive(PartialEq, Debug, Clone)]
+pub struct DeliveryJob {
+    pub inbox: String,
+    pub activity: Object,
+    pub priority: Priority,
+    // Failed attempts so far.
+    pub attempts: u32,
+    // The job is not leased before this time.
+    pub not_before: DateTime<Utc>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct LeasedJob {
+    pub id: JobId,
+    pub job: DeliveryJob,
+}
+
+/**
+ * Storage of delivery jobs, so that retries survive restarts of the process.
+ * Implementations backed by a database must keep this contract:
+ *
+ * - `enqueue` stores the job durably before returning an id which is unique
+ *   in the store.
+ * - `lease` returns up to `limit` jobs whose `not_before` is not after `now`
+ *   and which are not leased, highest `priority` first and then earliest
+ *   `not_before` first, and leases them until `now + duration`. A job whose
+ *   lease expires without `ack` or `fail`, e.g. as the process crashed, can
+ *   be leased again, so a job may be delivered more than once.
+ * - `release` returns a leased job to the store without counting an attempt,
+ *   not to be leased before `not_before`.
+ * - `ack` removes a delivered job.
+ * - `fail` records a failed attempt. With `retry_at`, the job is released
+ *   with `attempts` incremented and `not_before` set to it; without, the job
+ *   is removed as undeliverable.
+ * - `pending` counts the jobs in the store, leased or not.
+ * - `release`, `ack` and `fail` of an unknown id are errors.
+ */
+pub trait JobStore: Send + Sync {
+    fn enqueue(&self, job: DeliveryJob) -> Result<JobId, Box<dyn Error>>;
+
+    fn lease(
+        &self,
+        now: DateTime<Utc>,
+        duration: Duration,
+        limit: usize,
+    ) -> Result<Vec<LeasedJob>, Box<dyn Error>>;
+
+    fn release(&self, id: JobId, not_before: DateTime<Utc>) -> Result<(), Box<dyn Error>>;
+
+    fn ack(&self, id: JobId) -> Result<(), Box<dyn Error>>;
+
+    fn fail(&self, id: JobId, retry_at: Option<DateTime<Utc>>) -> Result<(), Box<dyn Error>>;
+
+    fn pending(&self) -> Result<usize, Box<dyn Error>>;
+}
+
+/**
+ * A job store in memory, whose jobs are lost on restart.
+ */
+#[derive(Debug, Default)]
+pub struct MemoryJobStore {
+    state: Mutex<MemoryJobState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryJobState {
+    next_id: JobId,
+    jobs: BTreeMap<JobId, StoredJob>,
+}
+
+#[derive(Debug)]
+struct StoredJob {
+    job: DeliveryJob,
+    leased_until: Option<DateTime<Utc>>,
+}
+
+impl MemoryJobStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().map_or(0, |state| state.jobs.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl JobStore for MemoryJobStore {
+    fn enqueue(&self, job: DeliveryJob) -> Result<JobId, Box<dyn Error>> {
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        let id = state.next_id;
+        state.next_id += 1;
+        state.jobs.insert(
+            id,
+            StoredJob {
+                job,
+                leased_until: None,
+            },
+        );
+        Ok(id)
+    }
+
+    fn lease(
+        &self,
+        now: DateTime<Utc>,
+        duration: Duration,
+        limit: usize,
+    ) -> Result<Vec<LeasedJob>, Box<dyn Error>> {
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        let mut due: Vec<(&JobId, &mut StoredJob)> = state
+            .jobs
+            .iter_mut()
+            .filter(|(_, stored)| {
+                stored.job.not_before <= now
+                    && stored
+                        .leased_until
+                        .is_none_or(|leased_until| leased_until <= now)
+            })
+            .collect();
+        due.sort_by_key(|(id, stored)| (stored.job.priority, stored.job.not_before, **id));
+        Ok(due
+            .into_iter()
+            .take(limit)
+            .map(|(id, stored)| {
+                stored.leased_until = Some(now + duration);
+                LeasedJob {
+                    id: *id,
+                    job: stored.job.clone(),
+                }
+            })
+            .collect())
+    }
+
+    fn release(&self, id: JobId, not_before: DateTime<Utc>) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        let stored = state
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| format!("job {} is not found", id))?;
+        stored.job.not_before = not_before;
+        stored.leased_until = None;
+        Ok(())
+    }
+
+    fn ack(&self, id: JobId) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        state
+            .jobs
+            .remove(&id)
+            .map(|_| ())
+            .ok_or_else(|| format!("job {} is not found", id).into())
+    }
+
+    fn fail(&self, id: JobId, retry_at: Option<DateTime<Utc>>) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state.lock().map_err(|err| err.to_string())?;
+        let retry_at = match retry_at {
+            None => {
+                return state
+                    .jobs
+                    .remove(&id)
+                    .map(|_| ())
+                    .ok_or_else(|| format!("job {} is not found", id).into());
+            }
+            Some(retry_at) => retry_at,
+        };
+        let stored = state
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| format!("job {} is not found", id))?;
+        stored.job.attempts += 1;
+        stored.job.not_before = retry_at;
+        stored.leased_until = None;
+        Ok(())
+    }
+
+    fn pending(&self) -> Result<usize, Box<dyn Error>> {
+        let state = self.state.lock().map_err(|err| err.to_string())?;
+        Ok(state.jobs.len())
+    }
+}
+
+/**
+ * The wait before retrying a job which failed `attempts` times, doubling
+ * from a minute up to a day.
+ */
+pub fn retry_delay(attempts: u32) -> Duration {
+    let seconds = 60i64.saturating_mul(1 << attempts.min(20));
+    Duration::seconds(seconds.min(MAX_RETRY_DELAY_SECONDS))
+}
+
+/**
+ * A delivery refused by the peer, returned by the `send` of
+ * `DeliveryQueue::run_once` with the `Retry-After` header of the response,
+ * so that the retry is scheduled as the peer asks.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DeliveryError {
+    pub status: u16,
+    pub retry_after: Option<String>,
+}
+
+impl DeliveryError {
+    /**
+     * When the peer asks to retry, from `Retry-After` of a `429` or `503`
+     * response, at most a day after `now`.
+     */
+    pub fn retry_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if !matches!(self.status, 429 | 503) {
+            return None;
+        }
+        let retry_at = parse_retry_after(self.retry_after.as_deref()?, now)?;
+        Some(retry_at.clamp(now, now + Duration::seconds(MAX_RETRY_DELAY_SECONDS)))
+    }
+}
+
+impl fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "delivery failed with status {}", self.status)?;
+        if let Some(retry_after) = &self.retry_after {
+            write!(f, ", retry after {}", retry_after)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for DeliveryError {}
+
+/**
+ * The time a `Retry-After` header tells, either in seconds after `now` or
+ * as an HTTP date.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc9110#section-10.2.3
+ */
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u32>() {
+        return Some(now + Duration::seconds(seconds.into()));
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/**
+ * Limits of deliveries to one host.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct HostLimits {
+    // Deliveries in flight at the same time, across the workers of a queue.
+    pub max_concurrent: usize,
+    // The least time between starting two deliveries.
+    pub min_interval: Duration,
+}
+
+impl Default for HostLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 4,
+            min_interval: Duration::zero(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    in_flight: usize,
+    last_started: Option<DateTime<Utc>>,
+}
+
+/**
+ * Delivers activities through a job store, retrying failures with
+ * `retry_delay` up to `max_attempts` attempts, or when the peer asks with a
+ * `DeliveryError`. Sending is left to the caller, e.g. a signed POST with
+ * an HTTP client.
+ *
+ * Jobs to a host over its `HostLimits` are put back to the store without
+ * counting an attempt, and `enqueue` fails once `max_pending` jobs are
+ * pending, so that callers can push back on their own clients.
+ *
+ * Inboxes on hosts rejected by `policy` are not enqueued, and jobs to hosts
+ * rejected since they were enqueued are dropped without being sent.
+ */
+pub struct DeliveryQueue<S> {
+    pub store: S,
+    pub observer: Box<dyn Observer>,
+    pub policy: Box<dyn FederationPolicy>,
+    pub max_attempts: u32,
+    // How long a job is leased for a delivery attempt.
+    pub lease_duration: Duration,
+    pub host_limits: HostLimits,
+    // Limits of particular hosts, overriding `host_limits`.
+    pub host_overrides: Map<String, HostLimits>,
+    pub max_pending: usize,
+    hosts: Mutex<Map<String, HostState>>,
+}
+
+impl<S: JobStore> DeliveryQueue<S> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            observer: Box::new(NoopObserver),
+            policy: Box::new(AcceptAll),
+            max_attempts: 10,
+            lease_duration: Duration::minutes(5),
+            host_limits: HostLimits::default(),
+            host_overrides: Map::new(),
+            max_pending: 100_000,
+            hosts: Mutex::new(Map::new()),
+        }
+    }
+
+    fn limits_of(&self, host: &str) -> HostLimits {
+        self.host_overrides
+            .get(host)
+            .copied()
+            .unwrap_or(self.host_limits)
+    }
+
+    /**
+     * Whether no more jobs can be enqueued.
+     */
+    pub fn is_saturated(&self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.store.pending()? >= self.max_pending)
+    }
+
+    /**
+     * Starts a delivery to the host if its limits allow, or returns the time
+     * to try again.
+     */
+    fn start(
+        &self,
+        host: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+        let limits = self.limits_of(host);
+        let mut hosts = self.hosts.lock().map_err(|err| err.to_string())?;
+        let state = hosts.entry(host.to_string()).or_default();
+        if let Some(next) = state.last_started.map(|last| last + limits.min_interval) {
+            if next > now {
+                return Ok(Some(next));
+            }
+        }
+        if state.in_flight >= limits.max_concurrent {
+            return Ok(Some(now));
+        }
+        state.in_flight += 1;
+        state.last_started = Some(now);
+        Ok(None)
+    }
+
+    fn finish(&self, host: &str) -> Result<(), Box<dyn Error>> {
+        let mut hosts = self.hosts.lock().map_err(|err| err.to_string())?;
+        if let Some(state) = hosts.get_mut(host) {
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    fn rejects(&self, host: &str) -> bool {
+        self.policy.domain_verdict(host) == Verdict::Reject
+    }
+
+    /**
+     * Enqueues the activity for each inbox not rejected by the policy,
+     * without `bto` and `bcc`, in the lane of `Priority::of`.
+     */
+    pub fn enqueue(
+        &self,
+        activity: &Object,
+        inboxes: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<JobId>, Box<dyn Error>> {
+        if self.store.pending()? + inboxes.len() > self.max_pending {
+            return Err("the delivery queue is saturated".into());
+        }
+        let activity = activity.prepare_for_delivery();
+        let priority = Priority::of(&activity);
+        inboxes
+            .iter()
+            .filter(|inbox| !self.rejects(&host_or_inbox(inbox)))
+            .map(|inbox| {
+                self.store.enqueue(DeliveryJob {
+                    inbox: inbox.clone(),
+                    activity: activity.clone(),
+                    priority,
+                    attempts: 0,
+                    not_before: now,
+                })
+            })
+            .collect()
+    }
+
+    /**
+     * Attempts up to `limit` due jobs with `send`, and returns the number of
+     * jobs attempted. Jobs deferred by `HostLimits` or dropped by the policy
+     * are not counted.
+     */
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(limit), err)
+    )]
+    pub fn run_once<F>(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+        mut send: F,
+    ) -> Result<usize, Box<dyn Error>>
+    where
+        F: FnMut(&DeliveryJob) -> Result<(), Box<dyn Error>>,
+    {
+        let leased = self.store.lease(now, self.lease_duration, limit)?;
+        let mut attempted = 0;
+        for LeasedJob { id, job } in &leased {
+            let host = host_or_inbox(&job.inbox);
+            if self.rejects(&host) {
+                self.store.fail(*id, None)?;
+                continue;
+            }
+            if let Some(not_before) = self.start(&host, now)? {
+                self.store.release(*id, not_before.max(job.not_before))?;
+                continue;
+            }
+            attempted += 1;
+            self.observer.delivery_attempted(&job.inbox);
+            let result = send(job);
+            self.finish(&host)?;
+            match result {
+                Ok(()) => {
+                    self.observer.delivery_succeeded(&job.inbox);
+                    self.store.ack(*id)?;
+                }
+                Err(err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(inbox = job.inbox, attempts = job.attempts, error = %err, "delivery failed");
+                    self.observer.delivery_failed(&job.inbox, &err.to_string());
+                    let requested = err
+                        .downcast_ref::<DeliveryError>()
+                        .and_then(|err| err.retry_at(now));
+                    if let Some(requested) = requested {
+                        self.observer
+                            .delivery_retry_after(&job.inbox, (requested - now).num_seconds());
+                    }
+                    let retry_at = (job.attempts + 1 < self.max_attempts)
+                        .then(|| requested.unwrap_or_else(|| now + retry_delay(job.attempts)));
+                    self.store.fail(*id, retry_at)?;
+                }
+            }
+        }
+        Ok(attempted)
+    }
+}
+
+fn host_or_inbox(inbox: &str) -> String {
+    host_of(inbox).unwrap_or_else(|| inbox.to_string())
+}
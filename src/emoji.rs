@@ -0,0 +1,150 @@
+use crate::model::{Link, Object, ObjectOrLink};
+
+/**
+ * A custom emoji, which Mastodon sends as an `Emoji` tag named by its
+ * shortcode in colons.
+ *
+ * Reference: https://docs.joinmastodon.org/spec/activitypub/#Emoji
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CustomEmoji {
+    // Without colons.
+    pub shortcode: String,
+    pub id: Option<String>,
+    pub icon_url: String,
+    pub media_type: Option<String>,
+}
+
+impl CustomEmoji {
+    pub fn from_tag(tag: &Object) -> Option<Self> {
+        if !tag.typ.iter().any(|typ| typ == "Emoji") {
+            return None;
+        }
+        let shortcode = tag.object_items.name.first()?.trim_matches(':').to_string();
+        let (icon_url, media_type) = match tag.object_items.icon.first()? {
+            ObjectOrLink::Link(link) => (link.href.clone(), link.media_type.first().cloned()),
+            ObjectOrLink::Object(icon) => (
+                icon.object_items.url.as_ref()?.href.clone(),
+                icon.object_items.media_type.first().cloned(),
+            ),
+        };
+        Some(Self {
+            shortcode,
+            id: tag.id.clone(),
+            icon_url,
+            media_type,
+        })
+    }
+
+    pub fn to_tag(&self) -> Object {
+        let mut icon = Object::new(None, vec!["Image".to_string()]);
+        icon.schema_context = None;
+        icon.object_items.url = Some(Link::from(self.icon_url.as_str()));
+        icon.object_items.media_type = self.media_type.iter().cloned().collect();
+        let mut tag = Object::new(self.id.clone(), vec!["Emoji".to_string()]);
+        tag.schema_context = None;
+        tag.object_items.name = vec![format!(":{}:", self.shortcode)];
+        tag.object_items.icon = vec![ObjectOrLink::Object(icon)];
+        tag
+    }
+}
+
+/**
+ * The shortcodes of `:shortcode:` patterns in the text, without colons or
+ * duplicates. As in Mastodon, a shortcode has two or more ASCII letters,
+ * digits or `_`, and is not adjacent to other letters, digits or colons.
+ */
+pub fn shortcodes(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let is_code = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let is_boundary = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric() && c != ':');
+    let mut dest: Vec<&str> = vec![];
+    let mut start = 0;
+    while let Some(offset) = text[start..].find(':') {
+        let open = start + offset;
+        let len = bytes[open + 1..]
+            .iter()
+            .take_while(|b| is_code(**b))
+            .count();
+        let close = open + 1 + len;
+        if len >= 2
+            && bytes.get(close) == Some(&b':')
+            && is_boundary(text[..open].chars().next_back())
+            && is_boundary(text[close + 1..].chars().next())
+        {
+            let shortcode = &text[open + 1..close];
+            if !dest.contains(&shortcode) {
+                dest.push(shortcode);
+            }
+            start = close + 1;
+        } else {
+            start = open + 1;
+        }
+    }
+    dest
+}
+
+// Texts of an object which may contain shortcodes.
+fn texts(object: &Object) -> impl Iterator<Item = &String> {
+    let items = &object.object_items;
+    items
+        .name
+        .iter()
+        .chain(&items.summary)
+        .chain(&items.content)
+        .chain(items.name_map.values())
+        .chain(items.summary_map.values())
+        .chain(items.content_map.values())
+}
+
+/**
+ * The custom emojis used in the `name`, `summary` and `content` of the
+ * object which have an `Emoji` tag, in the order they appear.
+ */
+pub fn used_emojis(object: &Object) -> Vec<CustomEmoji> {
+    let tags: Vec<CustomEmoji> = object
+        .object_items
+        .tag
+        .iter()
+        .filter_map(|tag| match tag {
+            ObjectOrLink::Object(tag) => CustomEmoji::from_tag(tag),
+            ObjectOrLink::Link(_) => None,
+        })
+        .collect();
+    let mut dest: Vec<CustomEmoji> = vec![];
+    for shortcode in texts(object).flat_map(|text| shortcodes(text)) {
+        if dest.iter().any(|emoji| emoji.shortcode == shortcode) {
+            continue;
+        }
+        if let Some(emoji) = tags.iter().find(|emoji| emoji.shortcode == shortcode) {
+            dest.push(emoji.clone());
+        }
+    }
+    dest
+}
+
+/**
+ * Adds `Emoji` tags for the shortcodes in the texts of a composed object,
+ * which `lookup` finds among the custom emojis of the server. Shortcodes
+ * already tagged or unknown to `lookup` are left as they are.
+ */
+pub fn add_emoji_tags(object: &mut Object, lookup: impl Fn(&str) -> Option<CustomEmoji>) {
+    let tagged: Vec<String> = used_emojis(object)
+        .into_iter()
+        .map(|emoji| emoji.shortcode)
+        .collect();
+    let mut found: Vec<CustomEmoji> = vec![];
+    for shortcode in texts(object).flat_map(|text| shortcodes(text)) {
+        if tagged.iter().any(|tagged| tagged == shortcode)
+            || found.iter().any(|emoji| emoji.shortcode == shortcode)
+        {
+            continue;
+        }
+        found.extend(lookup(shortcode));
+    }
+    object.object_items.tag.extend(
+        found
+            .iter()
+            .map(|emoji| ObjectOrLink::Object(emoji.to_tag())),
+    );
+}
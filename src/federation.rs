@@ -0,0 +1,140 @@
+use std::error::Error;
+
+use crate::model::{Object, ObjectOrLink};
+use crate::origin::host_of;
+use crate::resolver::{Resolver, Revalidated, Validators};
+
+/**
+ * What to do with a remote domain or actor. `Silence` accepts activities
+ * but keeps them out of public timelines. Verdicts are ordered by
+ * strictness.
+ */
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+pub enum Verdict {
+    #[default]
+    Accept,
+    Silence,
+    Reject,
+}
+
+/**
+ * Instance level decisions on remote domains and actors, to be consulted by
+ * everything which talks to other servers so that blocklists apply
+ * consistently.
+ */
+pub trait FederationPolicy: Send + Sync {
+    fn domain_verdict(&self, _domain: &str) -> Verdict {
+        Verdict::Accept
+    }
+
+    /**
+     * The verdict on the domain of the actor by default.
+     */
+    fn actor_verdict(&self, actor: &str) -> Verdict {
+        match host_of(actor) {
+            None => Verdict::Accept,
+            Some(domain) => self.domain_verdict(&domain),
+        }
+    }
+
+    /**
+     * The strictest verdict on the actors and the origin of the activity.
+     */
+    fn activity_verdict(&self, activity: &Object) -> Verdict {
+        let actors = activity
+            .activity_items
+            .actor
+            .iter()
+            .chain(&activity.object_items.attributed_to)
+            .filter_map(ObjectOrLink::id)
+            .map(|actor| self.actor_verdict(actor));
+        let origin = activity
+            .id
+            .as_deref()
+            .and_then(host_of)
+            .map(|domain| self.domain_verdict(&domain));
+        actors.chain(origin).max().unwrap_or_default()
+    }
+}
+
+/**
+ * A policy which accepts everything.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct AcceptAll;
+
+impl FederationPolicy for AcceptAll {}
+
+/**
+ * Rejects or silences the listed domains and their subdomains, and the
+ * listed actors.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct DomainList {
+    pub rejected: Vec<String>,
+    pub silenced: Vec<String>,
+    pub rejected_actors: Vec<String>,
+}
+
+impl FederationPolicy for DomainList {
+    fn domain_verdict(&self, domain: &str) -> Verdict {
+        let domain = domain.to_ascii_lowercase();
+        let matches = |listed: &String| {
+            let listed = listed.to_ascii_lowercase();
+            domain == listed
+                || domain
+                    .strip_suffix(&listed)
+                    .is_some_and(|sub| sub.ends_with('.'))
+        };
+        if self.rejected.iter().any(matches) {
+            Verdict::Reject
+        } else if self.silenced.iter().any(matches) {
+            Verdict::Silence
+        } else {
+            Verdict::Accept
+        }
+    }
+
+    fn actor_verdict(&self, actor: &str) -> Verdict {
+        if self
+            .rejected_actors
+            .iter()
+            .any(|rejected| rejected == actor)
+        {
+            return Verdict::Reject;
+        }
+        match host_of(actor) {
+            None => Verdict::Accept,
+            Some(domain) => self.domain_verdict(&domain),
+        }
+    }
+}
+
+/**
+ * A resolver which refuses to resolve IRIs rejected by the policy.
+ */
+#[derive(Debug, Clone)]
+pub struct PolicyResolver<P, R> {
+    pub policy: P,
+    pub resolver: R,
+}
+
+impl<P: FederationPolicy, R: Resolver> Resolver for PolicyResolver<P, R> {
+    fn resolve(&self, iri: &str) -> Result<Object, Box<dyn Error>> {
+        if self.policy.actor_verdict(iri) == Verdict::Reject {
+            return Err(format!("{} is rejected by the federation policy", iri).into());
+        }
+        self.resolver.resolve(iri)
+    }
+
+    fn resolve_if_modified(
+        &self,
+        iri: &str,
+        validators: &Validators,
+    ) -> Result<Revalidated, Box<dyn Error>> {
+        if self.policy.actor_verdict(iri) == Verdict::Reject {
+            return Err(format!("{} is rejected by the federation policy", iri).into());
+        }
+        self.resolver.resolve_if_modified(iri, validators)
+    }
+}
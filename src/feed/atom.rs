@@ -0,0 +1,149 @@
+use std::error::Error;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use url::Url;
+
+use super::{author_name, content_of, hashtags, link_of, posts, title_of};
+use crate::html::escape;
+use crate::model::Object;
+
+/**
+ * Renders the Notes and Articles created in an outbox as an Atom feed of the
+ * actor. The feed is identified by the first absolute IRI of the outbox id,
+ * the actor id and the actor link, and updated at the newest date of the
+ * posts or else of the actor; it fails without either. Posts without an
+ * absolute IRI for id or link are skipped, and posts without dates take the
+ * date of the feed.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc4287
+ */
+pub fn to_atom(actor: &Object, outbox: &Object) -> Result<String, Box<dyn Error>> {
+    let feed_id = [outbox.id.as_ref(), actor.id.as_ref(), link_of(actor)]
+        .into_iter()
+        .flatten()
+        .find(|iri| is_absolute(iri))
+        .ok_or("the feed has no absolute IRI to be identified by")?;
+    let posts: Vec<(&Object, &String)> = posts(outbox)
+        .into_iter()
+        .filter_map(|post| {
+            let id = [post.id.as_ref(), link_of(post)]
+                .into_iter()
+                .flatten()
+                .find(|iri| is_absolute(iri))?;
+            Some((post, id))
+        })
+        .collect();
+    let updated = posts
+        .iter()
+        .filter_map(|(post, _)| post.object_items.updated.or(post.object_items.published))
+        .max()
+        .or(actor.object_items.updated)
+        .or(actor.object_items.published)
+        .ok_or("the feed has no date to be updated at")?;
+
+    let mut dest = String::new();
+    dest.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    dest.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    push_element(&mut dest, 1, "id", feed_id);
+    push_element(
+        &mut dest,
+        1,
+        "title",
+        author_name(actor)
+            .or(actor.id.as_ref())
+            .map_or("", |name| name),
+    );
+    push_element(&mut dest, 1, "updated", &format_date(&updated));
+    if let Some(summary) = actor.object_items.summary.first() {
+        push_text_construct(&mut dest, 1, "subtitle", summary);
+    }
+    if let Some(link) = link_of(actor) {
+        push_link(&mut dest, 1, "alternate", link);
+    }
+    if let Some(id) = outbox.id.as_ref().filter(|id| is_absolute(id)) {
+        push_link(&mut dest, 1, "via", id);
+    }
+    push_author(&mut dest, 1, actor);
+
+    for (post, id) in posts {
+        dest.push_str("  <entry>\n");
+        push_element(&mut dest, 2, "id", id);
+        push_element(&mut dest, 2, "title", &title_of(post));
+        let published = post.object_items.published;
+        let updated = post.object_items.updated.or(published).unwrap_or(updated);
+        push_element(&mut dest, 2, "updated", &format_date(&updated));
+        if let Some(published) = published {
+            push_element(&mut dest, 2, "published", &format_date(&published));
+        }
+        if let Some(link) = link_of(post) {
+            push_link(&mut dest, 2, "alternate", link);
+        }
+        if let Some(summary) = post.object_items.summary.first() {
+            push_text_construct(&mut dest, 2, "summary", summary);
+        }
+        if let Some(content) = content_of(post) {
+            push_text_construct(&mut dest, 2, "content", content);
+        }
+        for tag in hashtags(post) {
+            dest.push_str(&format!("    <category term=\"{}\"/>\n", escape(tag)));
+        }
+        dest.push_str("  </entry>\n");
+    }
+
+    dest.push_str("</feed>\n");
+    Ok(dest)
+}
+
+fn is_absolute(iri: &str) -> bool {
+    Url::parse(iri).is_ok()
+}
+
+fn push_author(dest: &mut String, depth: usize, actor: &Object) {
+    let indent = "  ".repeat(depth);
+    dest.push_str(&format!("{}<author>\n", indent));
+    push_element(
+        dest,
+        depth + 1,
+        "name",
+        author_name(actor)
+            .or(actor.id.as_ref())
+            .map_or("", |name| name),
+    );
+    if let Some(link) = link_of(actor) {
+        push_element(dest, depth + 1, "uri", link);
+    }
+    dest.push_str(&format!("{}</author>\n", indent));
+}
+
+fn push_element(dest: &mut String, depth: usize, name: &str, text: &str) {
+    dest.push_str(&format!(
+        "{}<{}>{}</{}>\n",
+        "  ".repeat(depth),
+        name,
+        escape(text),
+        name
+    ));
+}
+
+fn push_text_construct(dest: &mut String, depth: usize, name: &str, html: &str) {
+    dest.push_str(&format!(
+        "{}<{} type=\"html\">{}</{}>\n",
+        "  ".repeat(depth),
+        name,
+        escape(html),
+        name
+    ));
+}
+
+fn push_link(dest: &mut String, depth: usize, rel: &str, href: &str) {
+    dest.push_str(&format!(
+        "{}<link rel=\"{}\" href=\"{}\"/>\n",
+        "  ".repeat(depth),
+        rel,
+        escape(href)
+    ));
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
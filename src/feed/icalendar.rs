@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+
+use super::{content_of, link_of, title_of};
+use crate::html::strip_tags;
+use crate::model::{Object, ObjectOrLink};
+
+// Lines longer than this in octets, without the line break, are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/**
+ * Events in a collection, such as an outbox or the events of a group on
+ * Mobilizon: embedded `Event` items, and `Event` objects of embedded
+ * activities. References by IRI are skipped.
+ */
+pub fn events(collection: &Object) -> Vec<&Object> {
+    let mut dest = vec![];
+    let items = collection
+        .ordered_collection_items
+        .ordered_items
+        .iter()
+        .chain(collection.collection_items.items.iter());
+    for item in items {
+        let ObjectOrLink::Object(item) = item else {
+            continue;
+        };
+        let objects = std::iter::once(item).chain(
+            item.activity_items
+                .object
+                .iter()
+                .filter_map(ObjectOrLink::as_object),
+        );
+        for object in objects {
+            let is_new = !dest
+                .iter()
+                .any(|other: &&Object| object.id.is_some() && other.id == object.id);
+            if object.typ.iter().any(|typ| typ == "Event") && is_new {
+                dest.push(object);
+            }
+        }
+    }
+    dest
+}
+
+/**
+ * Renders events as an iCalendar object of `VEVENT`s. Events without an
+ * `id` or a `startTime` are skipped, as the `UID` and `DTSTART` of an
+ * event are required.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc5545
+ */
+pub fn to_icalendar(events: &[&Object]) -> String {
+    let mut dest = String::new();
+    push_line(&mut dest, "BEGIN:VCALENDAR");
+    push_line(&mut dest, "VERSION:2.0");
+    push_line(&mut dest, "PRODID:-//activitist//NONSGML activitist//EN");
+    for event in events {
+        if let Some(vevent) = to_vevent(event) {
+            dest.push_str(&vevent);
+        }
+    }
+    push_line(&mut dest, "END:VCALENDAR");
+    dest
+}
+
+/**
+ * Renders an event as a `VEVENT`, with lines ended by CRLF. `None` if the
+ * event has no `id` or `startTime`.
+ */
+pub fn to_vevent(event: &Object) -> Option<String> {
+    let items = &event.object_items;
+    let uid = event.id.as_ref()?;
+    let start = items.start_time?;
+
+    let mut dest = String::new();
+    push_line(&mut dest, "BEGIN:VEVENT");
+    push_property(&mut dest, "UID", uid);
+    let stamp = items.updated.or(items.published).unwrap_or(start);
+    push_line(&mut dest, &format!("DTSTAMP:{}", format_date(&stamp)));
+    push_line(&mut dest, &format!("DTSTART:{}", format_date(&start)));
+    if let Some(end) = items.end_time {
+        push_line(&mut dest, &format!("DTEND:{}", format_date(&end)));
+    }
+    push_property(&mut dest, "SUMMARY", &title_of(event));
+    if let Some(content) = content_of(event) {
+        push_property(&mut dest, "DESCRIPTION", strip_tags(content).trim());
+    }
+    if let Some(place) = items.location.iter().find_map(ObjectOrLink::as_object) {
+        if let Some(name) = place.object_items.name.first() {
+            push_property(&mut dest, "LOCATION", name);
+        }
+        #[cfg(feature = "place")]
+        if let (Some(latitude), Some(longitude)) =
+            (place.place_items.latitude, place.place_items.longitude)
+        {
+            push_line(&mut dest, &format!("GEO:{};{}", latitude, longitude));
+        }
+    }
+    if let Some(link) = link_of(event) {
+        push_line(&mut dest, &format!("URL:{}", link));
+    }
+    push_line(&mut dest, "END:VEVENT");
+    Some(dest)
+}
+
+fn push_property(dest: &mut String, name: &str, text: &str) {
+    push_line(dest, &format!("{}:{}", name, escape_text(text)));
+}
+
+// Folds the line into ones of at most `MAX_LINE_OCTETS`, without splitting
+// characters, each following one starting with a space.
+fn push_line(dest: &mut String, line: &str) {
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > MAX_LINE_OCTETS {
+            dest.push_str("\r\n ");
+            len = 1;
+        }
+        dest.push(c);
+        len += c.len_utf8();
+    }
+    dest.push_str("\r\n");
+}
+
+// Escapes a value of type TEXT.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use super::{author_name, content_of, hashtags, link_of, posts};
+use crate::html::strip_tags;
+use crate::model::{Object, ObjectOrLink};
+
+pub const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/**
+ * Can be read and written with `json::SerdeJsonValue`.
+ *
+ * Reference: https://www.jsonfeed.org/version/1.1/
+ */
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFeed {
+    pub version: String,
+    pub title: String,
+    pub home_page_url: Option<String>,
+    pub feed_url: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<JsonFeedAuthor>,
+    pub items: Vec<JsonFeedItem>,
+}
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFeedAuthor {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFeedItem {
+    pub id: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub content_html: Option<String>,
+    pub content_text: Option<String>,
+    pub summary: Option<String>,
+    pub date_published: Option<DateTime<Utc>>,
+    pub date_modified: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<JsonFeedAttachment>,
+}
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFeedAttachment {
+    pub url: String,
+    pub mime_type: String,
+    pub title: Option<String>,
+}
+
+/**
+ * Exports the Notes and Articles created in an outbox as a JSON Feed of the
+ * actor. Posts without an `id` are skipped, since items require one.
+ */
+pub fn to_json_feed(actor: &Object, outbox: &Object) -> JsonFeed {
+    let items = posts(outbox)
+        .into_iter()
+        .filter_map(|post| {
+            let id = post.id.clone()?;
+            let object_items = &post.object_items;
+            Some(JsonFeedItem {
+                id,
+                url: link_of(post).cloned(),
+                title: object_items.name.first().cloned(),
+                content_html: content_of(post).cloned(),
+                content_text: None,
+                summary: object_items
+                    .summary
+                    .first()
+                    .map(|summary| strip_tags(summary)),
+                date_published: object_items.published,
+                date_modified: object_items.updated,
+                tags: hashtags(post).into_iter().map(str::to_string).collect(),
+                attachments: object_items
+                    .attachment
+                    .iter()
+                    .filter_map(attachment_of)
+                    .collect(),
+            })
+        })
+        .collect();
+
+    JsonFeed {
+        version: JSON_FEED_VERSION.to_string(),
+        title: author_name(actor)
+            .or(actor.id.as_ref())
+            .cloned()
+            .unwrap_or_default(),
+        home_page_url: link_of(actor).cloned(),
+        feed_url: None,
+        description: actor
+            .object_items
+            .summary
+            .first()
+            .map(|summary| strip_tags(summary)),
+        icon: actor.object_items.icon.first().and_then(url_of),
+        authors: vec![JsonFeedAuthor {
+            name: author_name(actor).cloned(),
+            url: link_of(actor).cloned(),
+            avatar: actor.object_items.icon.first().and_then(url_of),
+        }],
+        items,
+    }
+}
+
+fn attachment_of(attachment: &ObjectOrLink) -> Option<JsonFeedAttachment> {
+    let (media_type, title) = match attachment {
+        ObjectOrLink::Link(link) => (link.media_type.first(), None),
+        ObjectOrLink::Object(object) => (
+            object.object_items.media_type.first(),
+            object.object_items.name.first(),
+        ),
+    };
+    Some(JsonFeedAttachment {
+        url: url_of(attachment)?,
+        mime_type: media_type
+            .cloned()
+            .unwrap_or("application/octet-stream".to_string()),
+        title: title.cloned(),
+    })
+}
+
+fn url_of(value: &ObjectOrLink) -> Option<String> {
+    match value {
+        ObjectOrLink::Link(link) => Some(link.href.clone()),
+        ObjectOrLink::Object(object) => {
+            object.object_items.url.as_ref().map(|url| url.href.clone())
+        }
+    }
+}
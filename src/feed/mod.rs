@@ -0,0 +1,89 @@
+use crate::html::strip_tags;
+use crate::model::{Object, ObjectOrLink};
+
+pub mod atom;
+pub mod icalendar;
+pub mod json_feed;
+
+const POST_TYPES: &[&str] = &["Note", "Article"];
+
+/**
+ * Notes and Articles created by the activities of an outbox, in the order of
+ * the collection. Only embedded activities and objects are taken; references
+ * by IRI are skipped.
+ */
+pub fn posts(outbox: &Object) -> Vec<&Object> {
+    let mut dest = vec![];
+    let activities = outbox
+        .ordered_collection_items
+        .ordered_items
+        .iter()
+        .chain(outbox.collection_items.items.iter());
+    for activity in activities {
+        let activity = match activity {
+            ObjectOrLink::Object(activity) if activity.typ.iter().any(|typ| typ == "Create") => {
+                activity
+            }
+            _ => continue,
+        };
+        for object in activity.activity_items.object.iter() {
+            if let ObjectOrLink::Object(object) = object {
+                if object
+                    .typ
+                    .iter()
+                    .any(|typ| POST_TYPES.contains(&typ.as_str()))
+                {
+                    dest.push(object);
+                }
+            }
+        }
+    }
+    dest
+}
+
+fn content_of(post: &Object) -> Option<&String> {
+    let items = &post.object_items;
+    items.content.first().or(items.content_map.values().next())
+}
+
+fn title_of(post: &Object) -> String {
+    if let Some(name) = post.object_items.name.first() {
+        return name.clone();
+    }
+    let text = content_of(post).map(|content| strip_tags(content));
+    let text = text.as_deref().unwrap_or_default().trim();
+    match text.char_indices().nth(80) {
+        None => text.to_string(),
+        Some((end, _)) => format!("{}…", &text[..end]),
+    }
+}
+
+fn link_of(object: &Object) -> Option<&String> {
+    object
+        .object_items
+        .url
+        .as_ref()
+        .map(|url| &url.href)
+        .or(object.id.as_ref())
+}
+
+fn author_name(actor: &Object) -> Option<&String> {
+    actor.object_items.name.first().or(actor
+        .actor_items
+        .as_ref()
+        .and_then(|items| items.preferred_username.as_ref()))
+}
+
+fn hashtags(post: &Object) -> Vec<&str> {
+    post.object_items
+        .tag
+        .iter()
+        .filter_map(|tag| match tag {
+            ObjectOrLink::Object(tag) if tag.typ.iter().any(|typ| typ == "Hashtag") => {
+                tag.object_items.name.first()
+            }
+            _ => None,
+        })
+        .map(|name| name.trim_start_matches('#'))
+        .collect()
+}
@@ -0,0 +1,131 @@
+use std::cell::Cell;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use serde_json::Value;
+
+use crate::json::JsonSerde;
+use crate::model::{Map, Object};
+
+/**
+ * Fuzz target which parses `data` as an object, serializes it and parses it
+ * again. Panics if the object changes through the round trip.
+ */
+pub fn round_trip_json(data: &[u8]) {
+    let object = match Object::from_json_bytes(data) {
+        Ok(object) => object,
+        Err(_) => return,
+    };
+    let serialized = object
+        .to_json_bytes()
+        .expect("a parsed object must be serializable");
+    let reparsed =
+        Object::from_json_bytes(&serialized).expect("a serialized object must be parsable");
+    assert_eq!(object, reparsed);
+}
+
+/**
+ * Fuzz target which builds an arbitrary object from `data` and serializes it.
+ * Arbitrary objects may not be representable in JSON, e.g. if unknown
+ * properties shadow known ones, so only panics are reported.
+ */
+pub fn serialize_arbitrary(data: &[u8]) {
+    let object = match Object::arbitrary(&mut Unstructured::new(data)) {
+        Ok(object) => object,
+        Err(_) => return,
+    };
+    if let Ok(serialized) = object.to_json_bytes() {
+        let _ = Object::from_json_bytes(&serialized);
+    }
+}
+
+// Objects are nested up to this depth, since a derived impl can nest them
+// until the stack overflows.
+const MAX_OBJECT_DEPTH: usize = 4;
+
+thread_local! {
+    static OBJECT_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+impl<'a> Arbitrary<'a> for Object {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let depth = OBJECT_DEPTH.get();
+        if depth >= MAX_OBJECT_DEPTH {
+            return Ok(Object::new(u.arbitrary()?, u.arbitrary()?));
+        }
+        OBJECT_DEPTH.set(depth + 1);
+        let object = arbitrary_object(u);
+        OBJECT_DEPTH.set(depth);
+        object
+    }
+}
+
+fn arbitrary_object(u: &mut Unstructured) -> Result<Object> {
+    Ok(Object {
+        schema_context: u.arbitrary()?,
+        id: u.arbitrary()?,
+        typ: u.arbitrary()?,
+        object_items: u.arbitrary()?,
+        actor_items: u.arbitrary()?,
+        activity_items: u.arbitrary()?,
+        collection_items: u.arbitrary()?,
+        ordered_collection_items: u.arbitrary()?,
+        collection_page_items: u.arbitrary()?,
+        ordered_collection_page_items: u.arbitrary()?,
+        relationship_items: u.arbitrary()?,
+        tombstone_items: u.arbitrary()?,
+        #[cfg(feature = "question")]
+        question_items: u.arbitrary()?,
+        #[cfg(feature = "place")]
+        place_items: u.arbitrary()?,
+        activity_streams_ext_items: u.arbitrary()?,
+        #[cfg(feature = "mastodon")]
+        mastodon_ext_items: u.arbitrary()?,
+        #[cfg(feature = "security")]
+        security_items: u.arbitrary()?,
+        property_items: u.arbitrary()?,
+        schema_org_items: u.arbitrary()?,
+        json_items: u.arbitrary()?,
+    })
+}
+
+pub(crate) fn arbitrary_optional_value(u: &mut Unstructured) -> Result<Option<Value>> {
+    if bool::arbitrary(u)? {
+        Ok(Some(arbitrary_value(u, 0)?))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn arbitrary_properties(u: &mut Unstructured) -> Result<Map<String, Value>> {
+    let mut dest = Map::new();
+    for _ in 0..u.arbitrary_len::<(String, u8)>()? {
+        dest.insert(String::arbitrary(u)?, arbitrary_value(u, 0)?);
+    }
+    Ok(dest)
+}
+
+// Values are nested up to a few levels, which is enough for unknown
+// properties.
+fn arbitrary_value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let max_kind = if depth < 3 { 5 } else { 3 };
+    Ok(match u.int_in_range(0..=max_kind)? {
+        0 => Value::Null,
+        1 => Value::Bool(bool::arbitrary(u)?),
+        2 => Value::from(i64::arbitrary(u)?),
+        3 => Value::String(String::arbitrary(u)?),
+        4 => {
+            let mut items = vec![];
+            for _ in 0..u.arbitrary_len::<u8>()? {
+                items.push(arbitrary_value(u, depth + 1)?);
+            }
+            Value::Array(items)
+        }
+        _ => {
+            let mut properties = serde_json::Map::new();
+            for _ in 0..u.arbitrary_len::<(String, u8)>()? {
+                properties.insert(String::arbitrary(u)?, arbitrary_value(u, depth + 1)?);
+            }
+            Value::Object(properties)
+        }
+    })
+}
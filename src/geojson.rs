@@ -0,0 +1,116 @@
+use std::error::Error;
+
+use serde_json::{json, Map, Value};
+
+use crate::model::Object;
+
+/**
+ * Meters in one of the `units` of a place, which default to meters.
+ */
+pub fn meters_per_unit(units: Option<&str>) -> Option<f64> {
+    match units.unwrap_or("m") {
+        "cm" => Some(0.01),
+        "feet" => Some(0.3048),
+        "inches" => Some(0.0254),
+        "km" => Some(1000.0),
+        "m" => Some(1.0),
+        "miles" => Some(1609.344),
+        _ => None,
+    }
+}
+
+/**
+ * The position of a place as a GeoJSON `Point`, with the altitude in
+ * meters. `None` if the latitude or longitude is unknown, or the altitude
+ * is in units which can not be converted.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc7946#section-3.1.2
+ */
+pub fn to_point(place: &Object) -> Option<Value> {
+    let items = &place.place_items;
+    let mut coordinates = vec![json!(items.longitude?), json!(items.latitude?)];
+    if let Some(altitude) = items.altitude {
+        coordinates.push(json!(altitude * meters_per_unit(items.units.as_deref())?));
+    }
+    Some(json!({
+        "type": "Point",
+        "coordinates": coordinates,
+    }))
+}
+
+/**
+ * A place as a GeoJSON `Feature`, with its `id`, `name`, `accuracy`,
+ * `radius` and `units` as properties.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc7946#section-3.2
+ */
+pub fn to_feature(place: &Object) -> Option<Value> {
+    let items = &place.place_items;
+    let mut properties = Map::new();
+    if let Some(name) = place.object_items.name.first() {
+        properties.insert("name".to_string(), json!(name));
+    }
+    if let Some(accuracy) = items.accuracy {
+        properties.insert("accuracy".to_string(), json!(accuracy));
+    }
+    if let Some(radius) = items.radius {
+        properties.insert("radius".to_string(), json!(radius));
+        properties.insert(
+            "units".to_string(),
+            json!(items.units.as_deref().unwrap_or("m")),
+        );
+    }
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": to_point(place)?,
+        "properties": properties,
+    });
+    if let Some(id) = &place.id {
+        feature["id"] = json!(id);
+    }
+    Some(feature)
+}
+
+/**
+ * A `Place` from a GeoJSON `Point`, or a `Feature` of one in the form of
+ * `to_feature`. Other geometries are not places of ActivityStreams.
+ */
+pub fn from_geojson(value: &Value) -> Result<Object, Box<dyn Error>> {
+    let (geometry, properties) = match value["type"].as_str() {
+        Some("Point") => (value, &Value::Null),
+        Some("Feature") => (&value["geometry"], &value["properties"]),
+        _ => return Err("GeoJSON is neither a Point nor a Feature".into()),
+    };
+    if geometry["type"] != "Point" {
+        return Err(format!("{} is not a Point", geometry["type"]).into());
+    }
+    let coordinates: Vec<f64> = geometry["coordinates"]
+        .as_array()
+        .ok_or("a Point has no coordinates")?
+        .iter()
+        .map(|value| value.as_f64().ok_or("coordinates must be numbers"))
+        .collect::<Result<_, _>>()?;
+    let [longitude, latitude, rest @ ..] = coordinates.as_slice() else {
+        return Err("a Point must have a longitude and a latitude".into());
+    };
+
+    let mut place = Object::new(
+        value["id"].as_str().map(String::from),
+        vec!["Place".to_string()],
+    );
+    if let Some(name) = properties["name"].as_str() {
+        place.object_items.name = vec![name.to_string()];
+    }
+    let items = &mut place.place_items;
+    items.longitude = Some(*longitude);
+    items.latitude = Some(*latitude);
+    items.accuracy = properties["accuracy"].as_f64();
+    items.radius = properties["radius"].as_f64();
+    items.units = properties["units"].as_str().map(String::from);
+    if let Some(altitude) = rest.first() {
+        let meters = meters_per_unit(items.units.as_deref())
+            .ok_or_else(|| format!("unknown units {:?}", items.units))?;
+        items.altitude = Some(altitude / meters);
+    }
+    Ok(place)
+}
@@ -0,0 +1,130 @@
+use std::error::Error;
+
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::resolver::Resolver;
+use crate::visit::{objects_mut, values_mut};
+
+// Properties addressing the object, which `inline` keeps as references.
+const ADDRESSING: &[&str] = &["audience", "bcc", "bto", "cc", "to"];
+
+/**
+ * Splits an object into documents, one per object with an `id`. Embedded
+ * objects with an `id` are replaced with references to it, and follow the
+ * root object in the result in the order they are found. Embedded objects
+ * without an `id` are kept as they are, since they cannot be referred to.
+ *
+ * Properties which only hold objects, e.g. `replies`, get objects which only
+ * have an `id` instead.
+ */
+pub fn flatten(object: Object) -> Vec<Object> {
+    let schema_context = object.schema_context.clone();
+    let mut dest = vec![];
+    flatten_into(object, &mut dest);
+    for extracted in dest.iter_mut().skip(1) {
+        if extracted.schema_context.is_none() {
+            extracted.schema_context.clone_from(&schema_context);
+        }
+    }
+    dest
+}
+
+fn flatten_into(mut object: Object, dest: &mut Vec<Object>) {
+    let mut extracted = vec![];
+    extract(&mut object, &mut extracted);
+    dest.push(object);
+    for embedded in extracted {
+        flatten_into(embedded.clone_without_schema_context(), dest);
+    }
+}
+
+fn extract(object: &mut Object, dest: &mut Vec<Object>) {
+    for (_, value) in values_mut(object) {
+        if let ObjectOrLink::Object(embedded) = value {
+            match &embedded.id {
+                Some(id) => {
+                    let reference = ObjectOrLink::Link(Link::simple(id.clone()));
+                    if let ObjectOrLink::Object(embedded) = std::mem::replace(value, reference) {
+                        dest.push(embedded);
+                    }
+                }
+                None => extract(embedded, dest),
+            }
+        }
+    }
+    for (_, embedded) in objects_mut(object) {
+        match embedded.id.clone() {
+            Some(id) => dest.push(std::mem::replace(embedded, reference_object(id))),
+            None => extract(embedded, dest),
+        }
+    }
+}
+
+/**
+ * Replaces references with the objects they refer to, up to `depth` levels
+ * of embedding. The addressing properties, i.e. `to`, `bto`, `cc`, `bcc` and
+ * `audience`, are kept as references, as they are mostly collections of
+ * actors. Objects which only have an `id` in properties which only hold
+ * objects, e.g. `replies`, are resolved too.
+ *
+ * References which cannot be resolved are kept as they are, and returned
+ * with the error from the resolver.
+ */
+pub fn inline<R: Resolver + ?Sized>(
+    object: &mut Object,
+    resolver: &R,
+    depth: usize,
+) -> Vec<(String, Box<dyn Error>)> {
+    let mut errors = vec![];
+    inline_into(object, resolver, depth, &mut errors);
+    errors
+}
+
+fn inline_into<R: Resolver + ?Sized>(
+    object: &mut Object,
+    resolver: &R,
+    depth: usize,
+    errors: &mut Vec<(String, Box<dyn Error>)>,
+) {
+    if depth == 0 {
+        return;
+    }
+    for (_, value) in values_mut(object).filter(|(name, _)| !ADDRESSING.contains(name)) {
+        // Links with other properties than `href`, e.g. mentions, are not
+        // references to objects.
+        let reference = match value {
+            ObjectOrLink::Link(link) if *link == Link::simple(link.href.clone()) => {
+                Some(link.href.clone())
+            }
+            _ => None,
+        };
+        if let Some(iri) = reference {
+            match resolver.resolve(&iri) {
+                Ok(resolved) => {
+                    *value = ObjectOrLink::Object(resolved.clone_without_schema_context())
+                }
+                Err(error) => errors.push((iri, error)),
+            }
+        }
+        if let ObjectOrLink::Object(embedded) = value {
+            inline_into(embedded, resolver, depth - 1, errors);
+        }
+    }
+    for (_, embedded) in objects_mut(object) {
+        if let Some(id) = embedded.id.clone() {
+            if *embedded == reference_object(id.clone()) {
+                match resolver.resolve(&id) {
+                    Ok(resolved) => *embedded = resolved.clone_without_schema_context(),
+                    Err(error) => errors.push((id, error)),
+                }
+            }
+        }
+        inline_into(embedded, resolver, depth - 1, errors);
+    }
+}
+
+fn reference_object(id: String) -> Object {
+    Object {
+        schema_context: None,
+        ..Object::new(Some(id), vec![])
+    }
+}
@@ -0,0 +1,174 @@
+use serde_json::Value;
+use unicode_general_category::{get_general_category, GeneralCategory};
+
+use crate::html::words_of;
+use crate::model::{Object, ObjectOrLink};
+
+/**
+ * A hashtag, which Mastodon sends as a `Hashtag` tag named with `#` and
+ * linking to the page of the tag on the server.
+ *
+ * Reference: https://docs.joinmastodon.org/spec/activitypub/#Hashtag
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hashtag {
+    // Without `#`, as written.
+    pub name: String,
+    pub href: Option<String>,
+}
+
+impl Hashtag {
+    pub fn from_tag(tag: &Object) -> Option<Self> {
+        if !tag.typ.iter().any(|typ| typ == "Hashtag") {
+            return None;
+        }
+        let name = tag.object_items.name.first()?.trim_start_matches('#');
+        let href = match tag.json_items.unknown_properties.get("href") {
+            Some(Value::String(href)) => Some(href.clone()),
+            _ => None,
+        };
+        Some(Self {
+            name: name.to_string(),
+            href,
+        })
+    }
+
+    pub fn to_tag(&self) -> Object {
+        let mut tag = Object::new(None, vec!["Hashtag".to_string()]);
+        tag.schema_context = None;
+        tag.object_items.name = vec![format!("#{}", self.name)];
+        if let Some(href) = &self.href {
+            tag.json_items
+                .unknown_properties
+                .insert("href".to_string(), Value::String(href.clone()));
+        }
+        tag
+    }
+
+    /**
+     * The name compared with others, see `normalize`.
+     */
+    pub fn normalized(&self) -> String {
+        normalize(&self.name)
+    }
+}
+
+/**
+ * The form of a hashtag in which two hashtags are the same, without `#`
+ * and in lower case. With the `nfc` feature, it is also in NFKC, so
+ * full-width and composed letters are folded as Mastodon does.
+ */
+pub fn normalize(name: &str) -> String {
+    let name = name.trim_start_matches('#');
+    #[cfg(feature = "nfc")]
+    let name: String = unicode_normalization::UnicodeNormalization::nfkc(name).collect();
+    name.to_lowercase()
+}
+
+/**
+ * The hashtags in plain text, without `#` or duplicates under `normalize`.
+ * A hashtag is a run of letters, digits, marks, `_` and joiners with at
+ * least one letter or `_`, after a `#` which is not in a word, a URL or an
+ * entity.
+ */
+pub fn hashtags(text: &str) -> Vec<&str> {
+    let mut dest: Vec<&str> = vec![];
+    let mut normalized: Vec<String> = vec![];
+    let mut start = 0;
+    while let Some(offset) = text[start..].find('#') {
+        let open = start + offset;
+        let body = &text[open + 1..];
+        let len: usize = body
+            .chars()
+            .take_while(|c| is_tag_char(*c))
+            .map(char::len_utf8)
+            .sum();
+        let name = &body[..len];
+        let after_boundary = text[..open]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_tag_char(c) && !"=/)&#".contains(c));
+        if after_boundary && name.chars().any(|c| c.is_alphabetic() || c == '_') {
+            let key = normalize(name);
+            if !normalized.contains(&key) {
+                normalized.push(key);
+                dest.push(name);
+            }
+        }
+        start = open + 1 + len;
+    }
+    dest
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\u{200c}' || c == '\u{200d}' || is_mark(c)
+}
+
+// Marks, e.g. vowel signs of Indic scripts, which `char::is_alphanumeric`
+// does not cover but are parts of words.
+fn is_mark(c: char) -> bool {
+    matches!(
+        get_general_category(c),
+        GeneralCategory::NonspacingMark
+            | GeneralCategory::SpacingMark
+            | GeneralCategory::EnclosingMark
+    )
+}
+
+/**
+ * The hashtags of the object, from its `Hashtag` tags. Tags given as links
+ * are read too.
+ */
+pub fn tagged_hashtags(object: &Object) -> Vec<Hashtag> {
+    object
+        .object_items
+        .tag
+        .iter()
+        .filter_map(|tag| match tag {
+            ObjectOrLink::Object(tag) => Hashtag::from_tag(tag),
+            ObjectOrLink::Link(link) if link.typ.iter().any(|typ| typ == "Hashtag") => {
+                Some(Hashtag {
+                    name: link.name.first()?.trim_start_matches('#').to_string(),
+                    href: Some(link.href.clone()),
+                })
+            }
+            ObjectOrLink::Link(_) => None,
+        })
+        .collect()
+}
+
+/**
+ * Adds `Hashtag` tags for the hashtags in the `content` of a composed
+ * object, linked to the page `tag_url` gives for the normalized name.
+ * Hashtags already tagged are left as they are.
+ */
+pub fn add_hashtag_tags(object: &mut Object, tag_url: impl Fn(&str) -> String) {
+    let mut known: Vec<String> = tagged_hashtags(object)
+        .iter()
+        .map(Hashtag::normalized)
+        .collect();
+    let items = &object.object_items;
+    let texts: Vec<String> = items
+        .content
+        .iter()
+        .chain(items.content_map.values())
+        .map(|content| words_of(content))
+        .collect();
+    let mut found: Vec<Hashtag> = vec![];
+    for name in texts.iter().flat_map(|text| hashtags(text)) {
+        let key = normalize(name);
+        if known.contains(&key) {
+            continue;
+        }
+        found.push(Hashtag {
+            name: name.to_string(),
+            href: Some(tag_url(&key)),
+        });
+        known.push(key);
+    }
+    object.object_items.tag.extend(
+        found
+            .iter()
+            .map(|hashtag| ObjectOrLink::Object(hashtag.to_tag())),
+    );
+}
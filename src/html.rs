@@ -0,0 +1,60 @@
+/**
+ * Converts HTML into plain text by dropping tags and decoding the basic
+ * entities.
+ */
+pub(crate) fn strip_tags(html: &str) -> String {
+    let mut dest = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => dest.push(c),
+            _ => {}
+        }
+    }
+    unescape(&dest)
+}
+
+/**
+ * Same as `strip_tags`, but with tags as spaces, so that words of adjacent
+ * elements are kept apart and no attribute is read as text.
+ */
+pub(crate) fn words_of(html: &str) -> String {
+    let mut dest = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                dest.push(' ');
+            }
+            c if !in_tag => dest.push(c),
+            _ => {}
+        }
+    }
+    unescape(&dest)
+}
+
+/**
+ * Decodes the basic entities.
+ */
+pub(crate) fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/**
+ * Escapes text for HTML and XML, including attribute values.
+ */
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
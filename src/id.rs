@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::clock::{Clock, SystemClock};
+use crate::html::strip_tags;
+use crate::model::Object;
+use crate::routes::Routes;
+
+// Words and characters of a slug.
+const MAX_SLUG_WORDS: usize = 6;
+const MAX_SLUG_LENGTH: usize = 48;
+
+/**
+ * Assigns ids to new activities and objects, so that servers control the
+ * shape of their URLs. Closures returning an id are minters too.
+ */
+pub trait IdMinter {
+    /**
+     * A new id for the object, which may be used to pick the id, e.g. by
+     * its type or name.
+     */
+    fn mint(&mut self, object: &Object) -> String;
+}
+
+impl<F: FnMut() -> String> IdMinter for F {
+    fn mint(&mut self, _object: &Object) -> String {
+        self()
+    }
+}
+
+impl IdMinter for &mut dyn IdMinter {
+    fn mint(&mut self, object: &Object) -> String {
+        (**self).mint(object)
+    }
+}
+
+/**
+ * Random UUIDs under a prefix, e.g. `https://example.com/objects/`.
+ */
+#[cfg(feature = "uuid")]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct UuidMinter {
+    pub prefix: String,
+}
+
+#[cfg(feature = "uuid")]
+impl IdMinter for UuidMinter {
+    fn mint(&mut self, _object: &Object) -> String {
+        format!("{}{}", self.prefix, uuid::Uuid::new_v4())
+    }
+}
+
+/**
+ * Snowflake ids under a prefix, which are ordered by the time they are
+ * minted: 41 bits of milliseconds since `epoch`, 10 bits of the worker and
+ * 12 bits of a sequence within the millisecond. Workers minting at the same
+ * time need distinct worker numbers. Ids are minted at the time of `clock`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SnowflakeMinter<C = SystemClock> {
+    pub prefix: String,
+    pub epoch: DateTime<Utc>,
+    pub worker: u16,
+    pub clock: C,
+    last: i64,
+    sequence: u64,
+}
+
+impl SnowflakeMinter {
+    pub fn new(prefix: &str, worker: u16) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            // The Unix epoch.
+            epoch: DateTime::default(),
+            worker,
+            clock: SystemClock,
+            last: 0,
+            sequence: 0,
+        }
+    }
+}
+
+impl<C: Clock> SnowflakeMinter<C> {
+    pub fn with_clock<D: Clock>(self, clock: D) -> SnowflakeMinter<D> {
+        SnowflakeMinter {
+            prefix: self.prefix,
+            epoch: self.epoch,
+            worker: self.worker,
+            clock,
+            last: self.last,
+            sequence: self.sequence,
+        }
+    }
+
+    /**
+     * The id minted at `now`, which is never less than the previous one
+     * even if the clock goes back.
+     */
+    pub fn next_id(&mut self, now: DateTime<Utc>) -> u64 {
+        let millis = (now - self.epoch).num_milliseconds().max(self.last);
+        if millis == self.last {
+            self.sequence += 1;
+            if self.sequence >= 1 << 12 {
+                self.last += 1;
+                self.sequence = 0;
+            }
+        } else {
+            self.last = millis;
+            self.sequence = 0;
+        }
+        ((self.last as u64 & ((1 << 41) - 1)) << 22)
+            | (u64::from(self.worker & 0x3ff) << 12)
+            | self.sequence
+    }
+}
+
+impl<C: Clock> IdMinter for SnowflakeMinter<C> {
+    fn mint(&mut self, _object: &Object) -> String {
+        let id = self.next_id(self.clock.now());
+        format!("{}{}", self.prefix, id)
+    }
+}
+
+/**
+ * Readable ids under a prefix, made of the first words of the `name`,
+ * `summary` or `content` of the object, or of its type. Slugs are made
+ * unique among the ones minted by the minter with a numeric suffix; ids
+ * already in use on the server are given with `with_used`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SlugMinter {
+    pub prefix: String,
+    used: HashSet<String>,
+}
+
+impl SlugMinter {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            used: HashSet::new(),
+        }
+    }
+
+    pub fn with_used<I: IntoIterator<Item = String>>(mut self, slugs: I) -> Self {
+        self.used.extend(slugs);
+        self
+    }
+}
+
+impl IdMinter for SlugMinter {
+    fn mint(&mut self, object: &Object) -> String {
+        let items = &object.object_items;
+        let text = items
+            .name
+            .first()
+            .or(items.summary.first())
+            .map(|text| strip_tags(text))
+            .or_else(|| items.content.first().map(|content| strip_tags(content)))
+            .unwrap_or_default();
+        let mut base = slugify(&text);
+        if base.is_empty() {
+            base = slugify(object.typ.first().map_or("object", String::as_str));
+        }
+        if base.is_empty() {
+            base = "object".to_string();
+        }
+        let mut slug = base.clone();
+        let mut suffix = 1;
+        while self.used.contains(&slug) {
+            suffix += 1;
+            slug = format!("{}-{}", base, suffix);
+        }
+        self.used.insert(slug.clone());
+        format!("{}{}", self.prefix, slug)
+    }
+}
+
+fn slugify(text: &str) -> String {
+    let mut dest = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(MAX_SLUG_WORDS)
+    {
+        if dest.len() + word.len() + 1 > MAX_SLUG_LENGTH && !dest.is_empty() {
+            break;
+        }
+        if !dest.is_empty() {
+            dest.push('-');
+        }
+        dest.push_str(&word.to_ascii_lowercase());
+    }
+    dest.truncate(MAX_SLUG_LENGTH);
+    dest
+}
+
+/**
+ * Ids in the layout of `routes`, with `activity` for activities and
+ * `object` for the others. `tokens` mints the values filled in, e.g. a
+ * `SnowflakeMinter` with an empty prefix.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RoutedMinter<M> {
+    pub routes: Routes,
+    pub tokens: M,
+}
+
+impl<M: IdMinter> IdMinter for RoutedMinter<M> {
+    fn mint(&mut self, object: &Object) -> String {
+        let token = self.tokens.mint(object);
+        if object.is_activity() {
+            self.routes.activity_id(&token)
+        } else {
+            self.routes.object_id(&token)
+        }
+    }
+}
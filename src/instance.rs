@@ -0,0 +1,162 @@
+use std::error::Error;
+#[cfg(feature = "signing")]
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::model::{ActorItems, ActorType, Context, Key, Map, Object};
+#[cfg(feature = "signing")]
+use crate::signature::request::Signer;
+
+/**
+ * The instance level `Application` actor, which signs authorized fetches and
+ * subscribes to relays on behalf of the server. Generating and storing its
+ * key pair is left to the caller, e.g. with `openssl genpkey`, as ring
+ * cannot generate RSA keys. With the `signing` feature, the private key can
+ * be given with `with_private_key` to sign requests as the actor.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InstanceActor {
+    base_url: Url,
+    username: String,
+    public_key_pem: String,
+    #[cfg(feature = "signing")]
+    signer: Option<Arc<Signer>>,
+}
+
+impl InstanceActor {
+    /**
+     * The actor is named after the host of `base_url`, as Mastodon does.
+     */
+    pub fn new(base_url: &str, public_key_pem: &str) -> Result<Self, Box<dyn Error>> {
+        let base_url = Url::parse(base_url)?;
+        let username = base_url
+            .host_str()
+            .ok_or(format!("{} has no host", base_url))?
+            .to_string();
+        Ok(Self {
+            base_url,
+            username,
+            public_key_pem: public_key_pem.to_string(),
+            #[cfg(feature = "signing")]
+            signer: None,
+        })
+    }
+
+    /**
+     * The actor holding the private key of its `public_key_pem`, in PEM as
+     * `Signer::from_pem` reads. Fails if the private key is not the one of
+     * `public_key_pem`, as remote servers would then reject its signatures.
+     */
+    #[cfg(feature = "signing")]
+    pub fn with_private_key(self, private_key_pem: &str) -> Result<Self, Box<dyn Error>> {
+        let signer = Signer::from_pem(&self.key_id(), private_key_pem)?;
+        if !signer.matches_public_key_pem(&self.public_key_pem) {
+            return Err("the private key does not match the public key of the actor".into());
+        }
+        Ok(Self {
+            signer: Some(Arc::new(signer)),
+            ..self
+        })
+    }
+
+    /**
+     * The signer of requests made as the actor, e.g. authorized fetches
+     * and relay subscriptions, if its private key is given.
+     */
+    #[cfg(feature = "signing")]
+    pub fn signer(&self) -> Option<&Signer> {
+        self.signer.as_deref()
+    }
+
+    pub fn with_username(self, username: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            ..self
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        self.base_url
+            .join(path)
+            .map(String::from)
+            .unwrap_or_else(|_| format!("{}{}", self.base_url, path))
+    }
+
+    pub fn id(&self) -> String {
+        self.url("/actor")
+    }
+
+    pub fn key_id(&self) -> String {
+        format!("{}#main-key", self.id())
+    }
+
+    pub fn shared_inbox(&self) -> String {
+        self.url("/inbox")
+    }
+
+    /**
+     * The `acct:` URI which WebFinger resolves to the actor.
+     */
+    pub fn acct(&self) -> String {
+        format!(
+            "acct:{}@{}",
+            self.username,
+            self.base_url.host_str().unwrap_or_default()
+        )
+    }
+
+    /**
+     * The actor document.
+     */
+    pub fn to_object(&self) -> Object {
+        let id = self.id();
+        let mut endpoints = Map::new();
+        endpoints.insert("sharedInbox".to_string(), self.shared_inbox());
+        let mut actor = Object::new_actor(
+            id.clone(),
+            ActorType::Application,
+            ActorItems {
+                inbox: format!("{}/inbox", id),
+                outbox: format!("{}/outbox", id),
+                following: format!("{}/following", id),
+                followers: format!("{}/followers", id),
+                preferred_username: Some(self.username.clone()),
+                endpoints,
+            },
+        );
+        actor.schema_context = Some(Context::object_default());
+        actor.activity_streams_ext_items.manually_approves_followers = Some(true);
+        actor.security_items.public_key = Some(Key {
+            id: self.key_id(),
+            owner: id,
+            public_key_pem: Some(self.public_key_pem.clone()),
+        });
+        actor
+    }
+
+    /**
+     * Whether a WebFinger `resource` query refers to the actor.
+     */
+    pub fn matches_resource(&self, resource: &str) -> bool {
+        resource == self.acct() || resource == self.id()
+    }
+
+    /**
+     * The WebFinger JRD of the actor.
+     *
+     * Reference: https://www.rfc-editor.org/rfc/rfc7033
+     */
+    pub fn webfinger(&self) -> Value {
+        json!({
+            "subject": self.acct(),
+            "aliases": [self.id()],
+            "links": [{
+                "rel": "self",
+                "type": "application/activity+json",
+                "href": self.id()
+            }]
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use std::{fmt, marker::PhantomData};
+
+use ::activitypub_federation::{config::Data, traits::ActivityHandler};
+use async_trait::async_trait;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use url::Url;
+
+use crate::model::Object;
+
+/**
+ * Handling of activities received through activitypub_federation, which is
+ * implemented on the application data. `ActivityHandler` cannot be
+ * implemented on the model by applications since both are foreign to them,
+ * so `Activity` implements it by delegating to this trait.
+ *
+ * Reference: https://docs.rs/activitypub_federation/0.6/activitypub_federation/traits/trait.ActivityHandler.html
+ */
+#[async_trait]
+pub trait ActivityReceiver: Clone + Send + Sync + Sized + 'static {
+    type Error;
+
+    async fn verify(activity: &Object, data: &Data<Self>) -> Result<(), Self::Error> {
+        let _ = (activity, data);
+        Ok(())
+    }
+
+    async fn receive(activity: Object, data: &Data<Self>) -> Result<(), Self::Error>;
+}
+
+/**
+ * An activity of the model usable as the activity type of
+ * activitypub_federation. Only activities with an `id` and an `actor` can be
+ * deserialized, since the handler has to answer both.
+ */
+pub struct Activity<D> {
+    object: Object,
+    id: Url,
+    actor: Url,
+    data: PhantomData<fn() -> D>,
+}
+
+impl<D> Activity<D> {
+    pub fn new(object: Object) -> Result<Self, String> {
+        let id = match &object.id {
+            None => return Err("activity has no id".to_string()),
+            Some(id) => Url::parse(id).map_err(|err| format!("invalid id {}: {}", id, err))?,
+        };
+        let actor = match object
+            .activity_items
+            .actor
+            .first()
+            .and_then(|actor| actor.id())
+        {
+            None => return Err("activity has no actor".to_string()),
+            Some(actor) => {
+                Url::parse(actor).map_err(|err| format!("invalid actor {}: {}", actor, err))?
+            }
+        };
+        Ok(Self {
+            object,
+            id,
+            actor,
+            data: PhantomData,
+        })
+    }
+
+    pub fn object(&self) -> &Object {
+        &self.object
+    }
+
+    pub fn into_object(self) -> Object {
+        self.object
+    }
+}
+
+impl<D> Clone for Activity<D> {
+    fn clone(&self) -> Self {
+        Self {
+            object: self.object.clone(),
+            id: self.id.clone(),
+            actor: self.actor.clone(),
+            data: PhantomData,
+        }
+    }
+}
+
+impl<D> fmt::Debug for Activity<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Activity")
+            .field("object", &self.object)
+            .finish()
+    }
+}
+
+impl<D> Serialize for Activity<D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.object.serialize(serializer)
+    }
+}
+
+impl<'de, D> Deserialize<'de> for Activity<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let object = Object::deserialize(deserializer)?;
+        Self::new(object).map_err(serde::de::Error::custom)
+    }
+}
+
+#[async_trait]
+impl<D: ActivityReceiver> ActivityHandler for Activity<D> {
+    type DataType = D;
+    type Error = D::Error;
+
+    fn id(&self) -> &Url {
+        &self.id
+    }
+
+    fn actor(&self) -> &Url {
+        &self.actor
+    }
+
+    async fn verify(&self, data: &Data<D>) -> Result<(), D::Error> {
+        D::verify(&self.object, data).await
+    }
+
+    async fn receive(self, data: &Data<D>) -> Result<(), D::Error> {
+        D::receive(self.object, data).await
+    }
+}
@@ -0,0 +1,66 @@
+use std::error::Error;
+
+use ::activitystreams::{activity, actor, base, collection, object};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::json::JsonSerde;
+use crate::model::{Object, ObjectOrLink};
+
+/**
+ * Conversions between the model and the types of the activitystreams crate.
+ * They go through the JSON representation, so properties which one side does
+ * not know are dropped.
+ *
+ * Reference: https://docs.rs/activitystreams/0.7.0-alpha.25/activitystreams/
+ */
+macro_rules! impl_conversions {
+    ($model:ty => $([$($param:ident)?] $typ:ty),* $(,)?) => {$(
+        impl$(<$param>)? TryFrom<$typ> for $model
+        where
+            $typ: Serialize,
+        {
+            type Error = Box<dyn Error>;
+
+            fn try_from(value: $typ) -> Result<Self, Self::Error> {
+                <$model>::from_value(&serde_json::to_value(value)?)
+            }
+        }
+
+        impl$(<$param>)? TryFrom<&$model> for $typ
+        where
+            $typ: DeserializeOwned,
+        {
+            type Error = Box<dyn Error>;
+
+            fn try_from(value: &$model) -> Result<Self, Self::Error> {
+                Ok(serde_json::from_value(value.to_value()?)?)
+            }
+        }
+    )*};
+}
+
+impl_conversions!(ObjectOrLink => [] base::AnyBase);
+
+impl_conversions!(
+    Object =>
+    [Kind] base::Base<Kind>,
+    [Kind] object::Object<Kind>,
+    [Inner] object::ApObject<Inner>,
+    [] object::Place,
+    [] object::Profile,
+    [] object::Relationship,
+    [] object::Tombstone,
+    [Kind] actor::Actor<Kind>,
+    [Inner] actor::ApActor<Inner>,
+    [Kind] activity::Activity<Kind>,
+    [Inner] activity::ActivityActor<Inner>,
+    [Inner] activity::ActivityObject<Inner>,
+    [Inner] activity::Origin<Inner>,
+    [Inner] activity::OptOrigin<Inner>,
+    [Inner] activity::Target<Inner>,
+    [Inner] activity::OptTarget<Inner>,
+    [] activity::Question,
+    [Kind] collection::Collection<Kind>,
+    [Kind] collection::CollectionPage<Kind>,
+    [] collection::OrderedCollectionPage,
+);
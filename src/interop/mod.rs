@@ -0,0 +1,4 @@
+#[cfg(feature = "activitypub_federation")]
+pub mod activitypub_federation;
+#[cfg(feature = "activitystreams")]
+pub mod activitystreams;
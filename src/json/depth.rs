@@ -0,0 +1,111 @@
+use std::{error::Error, fmt};
+
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+
+/**
+ * Parses a JSON value, failing as soon as arrays and objects are nested
+ * deeper than `max_depth`, before the rest of the document is read. With
+ * `batch`, a top level array is not counted, so that the limit applies to
+ * each of its items.
+ */
+pub fn from_slice(
+    bytes: &[u8],
+    max_depth: Option<usize>,
+    batch: bool,
+) -> Result<Value, Box<dyn Error>> {
+    let Some(max_depth) = max_depth else {
+        return Ok(serde_json::from_slice(bytes)?);
+    };
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = DepthLimited {
+        max_depth,
+        remaining: max_depth,
+        batch,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+#[derive(Clone, Copy)]
+struct DepthLimited {
+    max_depth: usize,
+    remaining: usize,
+    batch: bool,
+}
+
+impl DepthLimited {
+    fn nested<E: serde::de::Error>(self) -> Result<Self, E> {
+        match self.remaining.checked_sub(1) {
+            None => Err(E::custom(format!("nested deeper than {}", self.max_depth))),
+            Some(remaining) => Ok(Self {
+                remaining,
+                batch: false,
+                ..self
+            }),
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DepthLimited {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DepthLimited {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let item = if self.batch { self } else { self.nested()? };
+        let mut items = vec![];
+        while let Some(value) = seq.next_element_seed(item)? {
+            items.push(value);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let value = self.nested()?;
+        let mut properties = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            properties.insert(key, map.next_value_seed(value)?);
+        }
+        Ok(Value::Object(properties))
+    }
+}
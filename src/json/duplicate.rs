@@ -0,0 +1,100 @@
+use std::{collections::HashSet, error::Error, fmt};
+
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+/**
+ * Finds keys which appear more than once in the same JSON object, as JSON
+ * Pointers. serde_json silently keeps the last value of a duplicated key, and
+ * documents relying on it have been used to bypass signature verification.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc6901
+ */
+pub fn find_duplicate_keys(bytes: &[u8]) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut duplicates = vec![];
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    KeyChecker {
+        path: String::new(),
+        duplicates: &mut duplicates,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(duplicates)
+}
+
+struct KeyChecker<'a> {
+    path: String,
+    duplicates: &'a mut Vec<String>,
+}
+
+impl<'de> DeserializeSeed<'de> for KeyChecker<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeyChecker<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0;
+        while seq
+            .next_element_seed(KeyChecker {
+                path: format!("{}/{}", self.path, index),
+                duplicates: &mut *self.duplicates,
+            })?
+            .is_some()
+        {
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut keys = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let path = format!(
+                "{}/{}",
+                self.path,
+                key.replace('~', "~0").replace('/', "~1")
+            );
+            if !keys.insert(key) {
+                self.duplicates.push(path.clone());
+            }
+            map.next_value_seed(KeyChecker {
+                path,
+                duplicates: &mut *self.duplicates,
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+// Functional properties, which have at most one value.
+const FUNCTIONAL_PROPERTIES: &[&str] = &[
+    "id",
+    "duration",
+    "endTime",
+    "startTime",
+    "published",
+    "updated",
+    "deleted",
+    "replies",
+    "describes",
+    "likes",
+    "shares",
+    "inbox",
+    "outbox",
+    "following",
+    "followers",
+    "preferredUsername",
+    "endpoints",
+    "totalItems",
+    "current",
+    "first",
+    "last",
+    "next",
+    "prev",
+    "partOf",
+    "startIndex",
+    "subject",
+    "accuracy",
+    "altitude",
+    "latitude",
+    "longitude",
+    // Misspellings accepted as aliases.
+    "latitute",
+    "longitute",
+    "radius",
+    "units",
+    "manuallyApprovesFollowers",
+    "movedTo",
+    "sensitive",
+    "featured",
+    "featuredTags",
+    "discoverable",
+    "suspended",
+    "devices",
+    "publicKey",
+    "href",
+    "hreflang",
+    "height",
+    "width",
+];
+
+/**
+ * Replaces arrays given to functional properties with their only item, in
+ * the nodes at any depth. Empty arrays are removed. If an array has more
+ * than one item, the first one is taken, or an error is returned with
+ * `reject_multiple`. Values of unknown properties are left as they are.
+ */
+pub fn collapse(value: &mut Value, reject_multiple: bool) -> Result<(), Box<dyn Error>> {
+    super::node::try_for_each_node(value, &mut |properties| {
+        properties.retain(|key, value| {
+            !(FUNCTIONAL_PROPERTIES.contains(&key.as_str())
+                && value.as_array().is_some_and(Vec::is_empty))
+        });
+        for (key, value) in properties.iter_mut() {
+            if FUNCTIONAL_PROPERTIES.contains(&key.as_str()) {
+                if let Value::Array(items) = value {
+                    if reject_multiple && items.len() > 1 {
+                        return Err(format!("{} must have a single value", key).into());
+                    }
+                    *value = items.swap_remove(0);
+                }
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,63 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+
+/**
+ * Deserializes a number which may be encoded as a string, like
+ * `"totalItems": "5"`, as some servers emit.
+ */
+pub fn number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lax<T> {
+        Number(T),
+        String(String),
+    }
+
+    match Option::<Lax<T>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Lax::Number(value)) => Ok(Some(value)),
+        Some(Lax::String(value)) => match value.trim().parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(serde::de::Error::custom(format!(
+                "invalid number: {:?}",
+                value
+            ))),
+        },
+    }
+}
+
+/**
+ * Deserializes a boolean which may be encoded as `"true"`, `"false"`, `0` or
+ * `1`, as some servers emit.
+ */
+pub fn boolean<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Lax {
+        Bool(bool),
+        Number(u64),
+        String(String),
+    }
+
+    match Option::<Lax>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Lax::Bool(value)) => Ok(Some(value)),
+        Some(Lax::Number(0)) => Ok(Some(false)),
+        Some(Lax::Number(1)) => Ok(Some(true)),
+        Some(Lax::String(value)) if value == "true" => Ok(Some(true)),
+        Some(Lax::String(value)) if value == "false" => Ok(Some(false)),
+        Some(Lax::Number(value)) => Err(serde::de::Error::custom(format!(
+            "invalid boolean: {}",
+            value
+        ))),
+        Some(Lax::String(value)) => Err(serde::de::Error::custom(format!(
+            "invalid boolean: {:?}",
+            value
+        ))),
+    }
+}
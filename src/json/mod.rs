@@ -1,3 +1,5 @@
+#[cfg(feature = "tokio")]
+use std::future::Future;
 use std::{error::Error, io};
 
 use serde::{de::DeserializeOwned, ser::Serialize};
@@ -8,11 +10,42 @@ use serde_json::{
     Deserializer, Serializer, Value,
 };
 
-use crate::model;
-
+pub use self::duplicate::find_duplicate_keys;
 use self::model_conv::ModelConv;
+pub use self::options::{
+    DefaultContext, DeserializeOptions, MultipleValues, SerializeOptions, UnknownProperties,
+};
+pub use self::quirks::Quirks;
+use crate::model;
 
+mod depth;
+mod duplicate;
+mod functional;
+mod lax;
 mod model_conv;
+pub mod ndjson;
+mod node;
+mod options;
+mod quirks;
+mod relative;
+mod size;
+pub mod stream;
+mod strict;
+
+/**
+ * How strictly documents are read. `Lax` accepts encodings which are
+ * common in the wild but not allowed by the specifications. `Strict` also
+ * rejects duplicate keys when reading from bytes.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ParseMode {
+    #[default]
+    Lax,
+    Strict,
+}
+
+// An item of a batch read by `JsonSerde::from_json_batch`.
+pub type BatchItem<T> = Result<T, Box<dyn Error>>;
 
 pub trait JsonSerde
 where
@@ -27,6 +60,12 @@ where
     fn from_value(value: &Value) -> Result<Self, Box<dyn Error>>;
     fn to_value(&self) -> Result<Value, Box<dyn Error>>;
 
+    fn from_value_with_mode(value: &Value, mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        let mut value = value.clone();
+        DeserializeOptions::from(mode).apply(&mut value)?;
+        Self::from_value(&value)
+    }
+
     fn from_json_reader<'de, R: Read<'de>>(reader: R) -> Result<Self, Box<dyn Error>> {
         let de = Deserializer::new(reader);
         Self::read_json(de)
@@ -46,10 +85,134 @@ where
         self.write_json(&mut ser)
     }
 
-    fn from_json_bytes<'a>(bytes: &'a [u8]) -> Result<Self, Box<dyn Error>> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(?options), err)
+    )]
+    fn io_write_json_with<W: io::Write>(
+        &self,
+        writer: W,
+        options: &SerializeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut value = self.to_value()?;
+        options.apply(&mut value)?;
+        if options.pretty {
+            serde_json::to_writer_pretty(writer, &value)?;
+        } else {
+            serde_json::to_writer(writer, &value)?;
+        }
+        Ok(())
+    }
+
+    /**
+     * serde_json has no incremental parser, so the body is buffered in memory
+     * before being parsed. At most `max_length` bytes are read; a longer body
+     * is an error, so an untrusted peer cannot make it grow unbounded.
+     */
+    #[cfg(feature = "tokio")]
+    fn io_read_json_async<R: tokio::io::AsyncRead + Unpin + Send>(
+        reader: R,
+        max_length: u64,
+    ) -> impl Future<Output = Result<Self, Box<dyn Error>>> + Send {
+        async move {
+            let mut bytes = Vec::with_capacity(128);
+            let mut reader = tokio::io::AsyncReadExt::take(reader, max_length.saturating_add(1));
+            tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut bytes).await?;
+            if bytes.len() as u64 > max_length {
+                return Err(format!("document exceeds {} bytes", max_length).into());
+            }
+            Self::from_json_bytes(&bytes)
+        }
+    }
+
+    /**
+     * The document is serialized into a buffer as large as its JSON
+     * representation before being written.
+     */
+    #[cfg(feature = "tokio")]
+    fn io_write_json_async<W: tokio::io::AsyncWrite + Unpin + Send>(
+        &self,
+        mut writer: W,
+    ) -> impl Future<Output = Result<(), Box<dyn Error>>> + Send {
+        let bytes = self.to_json_bytes().map_err(|err| err.to_string());
+        async move {
+            let bytes = bytes?;
+            tokio::io::AsyncWriteExt::write_all(&mut writer, &bytes).await?;
+            tokio::io::AsyncWriteExt::flush(&mut writer).await?;
+            Ok(())
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(size = bytes.len()), err)
+    )]
+    fn from_json_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
         Self::from_json_reader(SliceRead::new(bytes))
     }
 
+    fn from_json_bytes_with_mode(bytes: &[u8], mode: ParseMode) -> Result<Self, Box<dyn Error>> {
+        Self::from_json_with(bytes, &DeserializeOptions::from(mode))
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(size = bytes.len(), ?options), err)
+    )]
+    fn from_json_with(bytes: &[u8], options: &DeserializeOptions) -> Result<Self, Box<dyn Error>> {
+        if options.reject_duplicate_keys {
+            if let Some(key) = find_duplicate_keys(bytes)?.first() {
+                return Err(format!("duplicate key: {}", key).into());
+            }
+        }
+        let mut value = depth::from_slice(bytes, options.max_depth, false)?;
+        options.apply(&mut value)?;
+        Self::from_value(&value)
+    }
+
+    /**
+     * Reads a JSON array of documents, e.g. activities delivered together by
+     * a relay, and a single document as a batch of one. An item which fails
+     * is reported with its index instead of failing the whole batch.
+     */
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(size = bytes.len(), ?options), err)
+    )]
+    fn from_json_batch(
+        bytes: &[u8],
+        options: &DeserializeOptions,
+    ) -> Result<Vec<BatchItem<Self>>, Box<dyn Error>> {
+        let duplicates = if options.reject_duplicate_keys {
+            find_duplicate_keys(bytes)?
+        } else {
+            vec![]
+        };
+        let (items, is_array) = match depth::from_slice(bytes, options.max_depth, true)? {
+            Value::Array(items) => (items, true),
+            value => (vec![value], false),
+        };
+        let read_item = |index: usize, mut value: Value| {
+            let prefix = if is_array {
+                format!("/{}/", index)
+            } else {
+                "/".to_string()
+            };
+            if let Some(key) = duplicates.iter().find(|key| key.starts_with(&prefix)) {
+                return Err(format!("duplicate key: {}", &key[prefix.len() - 1..]).into());
+            }
+            options.apply(&mut value)?;
+            Self::from_value(&value)
+        };
+        Ok(items
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                read_item(index, value).map_err(|err| format!("item {}: {}", index, err).into())
+            })
+            .collect())
+    }
+
     fn to_json_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
         let mut writer = Vec::with_capacity(128);
         self.io_write_json(&mut writer)?;
@@ -62,18 +225,33 @@ where
         Ok(writer)
     }
 
-    fn from_json_str<'a>(str: &'a str) -> Result<Self, Box<dyn Error>> {
+    fn to_json_bytes_with(&self, options: &SerializeOptions) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = Vec::with_capacity(128);
+        self.io_write_json_with(&mut writer, options)?;
+        Ok(writer)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(size = str.len()), err)
+    )]
+    fn from_json_str(str: &str) -> Result<Self, Box<dyn Error>> {
         Self::from_json_reader(StrRead::new(str))
     }
 
     fn to_json_string(&self) -> Result<String, Box<dyn Error>> {
         let bytes = self.to_json_bytes()?;
-        Ok(unsafe { String::from_utf8_unchecked(bytes) })
+        Ok(String::from_utf8(bytes)?)
     }
 
     fn to_string_pretty(&self) -> Result<String, Box<dyn Error>> {
         let bytes = self.to_json_bytes_pretty()?;
-        Ok(unsafe { String::from_utf8_unchecked(bytes) })
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    fn to_json_string_with(&self, options: &SerializeOptions) -> Result<String, Box<dyn Error>> {
+        let bytes = self.to_json_bytes_with(options)?;
+        Ok(String::from_utf8(bytes)?)
     }
 }
 
@@ -114,46 +292,23 @@ impl<T: Serialize + DeserializeOwned> JsonSerde for SerdeJsonValue<T> {
     }
 }
 
-impl JsonSerde for model::Context {
-    fn read_json<'de, R: Read<'de>>(
-        mut deserializer: Deserializer<R>,
-    ) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(&mut deserializer)?;
-        deserializer.end()?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn write_json<W: io::Write, F: Formatter>(
-        &self,
-        serializer: &mut Serializer<W, F>,
-    ) -> Result<(), Box<dyn Error>> {
-        let value = self.from_model()?;
-        value.serialize(serializer)?;
-        Ok(())
-    }
-
-    fn from_value(value: &Value) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(value)?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn to_value(&self) -> Result<Value, Box<dyn Error>> {
-        Ok(serde_json::to_value(self.from_model()?)?)
-    }
-}
-
-impl JsonSerde for model::Object {
+impl<T: ModelConv> JsonSerde for T {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(model = std::any::type_name::<T>()), err)
+    )]
     fn read_json<'de, R: Read<'de>>(
         mut deserializer: Deserializer<R>,
     ) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(&mut deserializer)?;
+        let value: T::JsonSerdeValue = serde::de::Deserialize::deserialize(&mut deserializer)?;
         deserializer.end()?;
-        Ok(ModelConv::to_model(value)?)
+        T::to_model(value)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(model = std::any::type_name::<T>()), err)
+    )]
     fn write_json<W: io::Write, F: Formatter>(
         &self,
         serializer: &mut Serializer<W, F>,
@@ -163,73 +318,56 @@ impl JsonSerde for model::Object {
         Ok(())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(model = std::any::type_name::<T>()), err)
+    )]
     fn from_value(value: &Value) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(value)?;
-        Ok(ModelConv::to_model(value)?)
+        let value: T::JsonSerdeValue = serde::de::Deserialize::deserialize(value)?;
+        T::to_model(value)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(model = std::any::type_name::<T>()), err)
+    )]
     fn to_value(&self) -> Result<Value, Box<dyn Error>> {
         Ok(serde_json::to_value(self.from_model()?)?)
     }
 }
 
-impl JsonSerde for model::Link {
-    fn read_json<'de, R: Read<'de>>(
-        mut deserializer: Deserializer<R>,
-    ) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(&mut deserializer)?;
-        deserializer.end()?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn write_json<W: io::Write, F: Formatter>(
-        &self,
-        serializer: &mut Serializer<W, F>,
-    ) -> Result<(), Box<dyn Error>> {
-        let value = self.from_model()?;
-        value.serialize(serializer)?;
-        Ok(())
-    }
-
-    fn from_value(value: &Value) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(value)?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn to_value(&self) -> Result<Value, Box<dyn Error>> {
-        Ok(serde_json::to_value(self.from_model()?)?)
-    }
+/**
+ * serde implementations of the model through the same conversion as
+ * `JsonSerde`, so model types can be embedded in serde types or used where
+ * serde types are expected, e.g. as the `Kind` of activitypub_federation's
+ * `Object` and `Actor` traits.
+ *
+ * Reference: https://docs.rs/activitypub_federation/latest/activitypub_federation/traits/trait.Object.html
+ */
+macro_rules! impl_serde {
+    ($($typ:ty),* $(,)?) => {$(
+        impl Serialize for $typ {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.from_model()
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $typ {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <$typ as ModelConv>::JsonSerdeValue::deserialize(deserializer)?;
+                Self::to_model(value).map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
 }
 
-impl JsonSerde for model::ObjectOrLink {
-    fn read_json<'de, R: Read<'de>>(
-        mut deserializer: Deserializer<R>,
-    ) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(&mut deserializer)?;
-        deserializer.end()?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn write_json<W: io::Write, F: Formatter>(
-        &self,
-        serializer: &mut Serializer<W, F>,
-    ) -> Result<(), Box<dyn Error>> {
-        let value = self.from_model()?;
-        value.serialize(serializer)?;
-        Ok(())
-    }
-
-    fn from_value(value: &Value) -> Result<Self, Box<dyn Error>> {
-        let value: <Self as ModelConv>::JsonSerdeValue =
-            serde::de::Deserialize::deserialize(value)?;
-        Ok(ModelConv::to_model(value)?)
-    }
-
-    fn to_value(&self) -> Result<Value, Box<dyn Error>> {
-        Ok(serde_json::to_value(self.from_model()?)?)
-    }
-}
+impl_serde!(
+    model::Object,
+    model::Link,
+    model::ObjectOrLink,
+    model::Context,
+    model::Iri,
+    model::Key,
+);
@@ -1,12 +1,18 @@
-use std::{collections::HashMap, error::Error};
+use std::{error::Error, fmt};
 
 use chrono::{DateTime, Utc};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{
+    de::{self, DeserializeOwned},
+    ser::{self, SerializeMap},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use serde_json::Value;
 use serde_with::skip_serializing_none;
 
-use crate::model;
+use super::lax;
+use crate::model::{self, Map};
 
+#[allow(clippy::wrong_self_convention)]
 pub trait ModelConv
 where
     Self: Sized,
@@ -38,8 +44,15 @@ impl ModelConv for model::Object {
                 None => (None, None, None, None, None, None),
             };
 
-        Ok(Object {
-            schema_context: from_model_opt(self.schema_context.as_ref())?,
+        let properties = ObjectProperties {
+            schema_context: match &self.schema_context {
+                Some(context) if !self.schema_org_items.is_empty() => {
+                    let mut context = context.clone();
+                    context.define_terms(self.schema_org_items.terms());
+                    Some(context.from_model()?)
+                }
+                context => from_model_opt(context.as_ref())?,
+            },
             id: self.id.clone(),
             typ: to_lax_array(&self.typ)?,
             attachment: to_lax_array(&self.object_items.attachment)?,
@@ -55,21 +68,21 @@ impl ModelConv for model::Object {
             in_reply_to: to_lax_array(&self.object_items.in_reply_to)?,
             location: to_lax_array(&self.object_items.location)?,
             preview: to_lax_array(&self.object_items.preview)?,
-            replies: boxed_from_model_opt(self.object_items.replies.as_ref())?,
+            replies: boxed_from_model_opt(self.object_items.replies.as_deref())?,
             tag: to_lax_array(&self.object_items.tag)?,
             to: to_lax_array(&self.object_items.to)?,
             url: match &self.object_items.url {
                 None => None,
                 Some(item) => {
-                    if {
-                        item.height.is_none()
-                            && item.hreflang.is_none()
-                            && item.id.is_none()
-                            && item.media_type.is_empty()
-                            && item.rel.is_empty()
-                            && item.typ.is_empty()
-                            && item.width.is_none()
-                    } {
+                    if item.height.is_none()
+                        && item.hreflang.is_none()
+                        && item.id.is_none()
+                        && item.media_type.is_empty()
+                        && item.name.is_empty()
+                        && item.rel.is_empty()
+                        && item.typ.is_empty()
+                        && item.width.is_none()
+                    {
                         Some(Value::String(item.href.clone()))
                     } else {
                         Some(serde_json::to_value(item.from_model()?)?)
@@ -90,6 +103,7 @@ impl ModelConv for model::Object {
             },
             duration: self.object_items.duration.clone(),
             media_type: to_lax_array(&self.object_items.media_type)?,
+            start_time: from_model_opt(self.object_items.start_time.as_ref())?,
             end_time: from_model_opt(self.object_items.end_time.as_ref())?,
             published: from_model_opt(self.object_items.published.as_ref())?,
             summary: to_lax_array(&self.object_items.summary)?,
@@ -99,7 +113,9 @@ impl ModelConv for model::Object {
                 Some(self.object_items.summary_map.clone())
             },
             updated: from_model_opt(self.object_items.updated.as_ref())?,
-            describes: boxed_from_model_opt(self.object_items.describes.as_ref())?,
+            describes: boxed_from_model_opt(self.object_items.describes.as_deref())?,
+            likes: boxed_from_model_opt(self.object_items.likes.as_deref())?,
+            shares: boxed_from_model_opt(self.object_items.shares.as_deref())?,
             inbox: inbox.cloned(),
             outbox: outbox.cloned(),
             followers: followers.cloned(),
@@ -113,27 +129,36 @@ impl ModelConv for model::Object {
             result: to_lax_array(&self.activity_items.result)?,
             target: to_lax_array(&self.activity_items.target)?,
             total_items: self.collection_items.total_items,
-            current: boxed_from_model_opt(self.collection_items.current.as_ref())?,
-            first: boxed_from_model_opt(self.collection_items.first.as_ref())?,
-            last: boxed_from_model_opt(self.collection_items.last.as_ref())?,
+            current: boxed_from_model_opt(self.collection_items.current.as_deref())?,
+            first: boxed_from_model_opt(self.collection_items.first.as_deref())?,
+            last: boxed_from_model_opt(self.collection_items.last.as_deref())?,
             items: to_lax_array(&self.collection_items.items)?,
             ordered_items: to_lax_array(&self.ordered_collection_items.ordered_items)?,
-            next: boxed_from_model_opt(self.collection_page_items.next.as_ref())?,
-            prev: boxed_from_model_opt(self.collection_page_items.prev.as_ref())?,
-            part_of: boxed_from_model_opt(self.collection_page_items.part_of.as_ref())?,
+            next: boxed_from_model_opt(self.collection_page_items.next.as_deref())?,
+            prev: boxed_from_model_opt(self.collection_page_items.prev.as_deref())?,
+            part_of: boxed_from_model_opt(self.collection_page_items.part_of.as_deref())?,
             start_index: self.ordered_collection_page_items.start_index,
-            subject: boxed_from_model_opt(self.relationship_items.subject.as_ref())?,
+            subject: boxed_from_model_opt(self.relationship_items.subject.as_deref())?,
             relationship: to_lax_array(&self.relationship_items.relationship)?,
             former_type: to_lax_array(&self.tombstone_items.former_type)?,
             deleted: from_model_opt(self.tombstone_items.deleted.as_ref())?,
+            #[cfg(feature = "question")]
             one_of: to_lax_array(&self.question_items.one_of)?,
+            #[cfg(feature = "question")]
             any_of: to_lax_array(&self.question_items.any_of)?,
+            #[cfg(feature = "question")]
             closed: self.question_items.closed.clone(),
+            #[cfg(feature = "place")]
             accuracy: self.place_items.accuracy,
+            #[cfg(feature = "place")]
             altitude: self.place_items.altitude,
-            latitute: self.place_items.latitute,
-            longitute: self.place_items.longitute,
+            #[cfg(feature = "place")]
+            latitude: self.place_items.latitude,
+            #[cfg(feature = "place")]
+            longitude: self.place_items.longitude,
+            #[cfg(feature = "place")]
             radius: self.place_items.radius,
+            #[cfg(feature = "place")]
             units: self.place_items.units.clone(),
             manually_approves_followers: self
                 .activity_streams_ext_items
@@ -141,17 +166,44 @@ impl ModelConv for model::Object {
             also_known_as: to_lax_array(&self.activity_streams_ext_items.also_known_as)?,
             moved_to: self.activity_streams_ext_items.moved_to.clone(),
             sensitive: self.activity_streams_ext_items.sensitive,
+            #[cfg(feature = "mastodon")]
             featured: self.mastodon_ext_items.featured.clone(),
+            #[cfg(feature = "mastodon")]
             featured_tags: self.mastodon_ext_items.featured_tags.clone(),
+            #[cfg(feature = "mastodon")]
             discoverable: self.mastodon_ext_items.discoverable,
+            #[cfg(feature = "mastodon")]
             suspended: self.mastodon_ext_items.suspended,
+            #[cfg(feature = "mastodon")]
             devices: self.mastodon_ext_items.devices.clone(),
+            #[cfg(feature = "security")]
             public_key: from_model_opt(self.security_items.public_key.as_ref())?,
             value: self.property_items.value.clone(),
+            category: to_lax_array(&self.schema_org_items.category)?,
+            license: from_model_opt(self.schema_org_items.license.as_ref())?
+                .map(serde_json::to_value)
+                .transpose()?,
+            identifier: self.schema_org_items.identifier.clone(),
+            unknown_properties: self.json_items.unknown_properties.clone(),
+        };
+
+        Ok(Object {
+            properties,
+            property_order: self.json_items.property_order.clone(),
+            null_properties: self.json_items.null_properties.clone(),
         })
     }
 
     fn to_model(origin: Self::JsonSerdeValue) -> Result<Self, Box<dyn Error>> {
+        let Object {
+            properties: origin,
+            property_order,
+            null_properties,
+        } = origin;
+        let mut unknown_properties = origin.unknown_properties;
+        let category = schema_org_value("category", origin.category, true, &mut unknown_properties);
+        let license = schema_org_value("license", origin.license, false, &mut unknown_properties);
+
         Ok(Self {
             schema_context: to_model_opt(origin.schema_context)?,
             id: origin.id,
@@ -175,33 +227,23 @@ impl ModelConv for model::Object {
                 to: from_lax_array(origin.to)?,
                 url: match origin.url {
                     None => None,
-                    Some(Value::String(item)) => Some(model::Link::from(item)),
-                    Some(item) => {
-                        let item: Link = serde_json::from_value(item)?;
-                        Some(model::Link::to_model(item)?)
-                    }
+                    Some(item) => url_to_model(item)?,
                 },
                 content: from_lax_array(origin.content)?,
-                content_map: match origin.content_map {
-                    None => HashMap::new(),
-                    Some(item) => item,
-                },
+                content_map: origin.content_map.unwrap_or_default(),
                 name: from_lax_array(origin.name)?,
-                name_map: match origin.name_map {
-                    None => HashMap::new(),
-                    Some(item) => item,
-                },
+                name_map: origin.name_map.unwrap_or_default(),
                 duration: origin.duration,
                 media_type: from_lax_array(origin.media_type)?,
+                start_time: to_model_opt(origin.start_time)?,
                 end_time: to_model_opt(origin.end_time)?,
                 published: to_model_opt(origin.published)?,
                 summary: from_lax_array(origin.summary)?,
-                summary_map: match origin.summary_map {
-                    None => HashMap::new(),
-                    Some(item) => item,
-                },
+                summary_map: origin.summary_map.unwrap_or_default(),
                 updated: to_model_opt(origin.updated)?,
                 describes: boxed_to_model_opt(origin.describes)?,
+                likes: boxed_to_model_opt(origin.likes)?,
+                shares: boxed_to_model_opt(origin.shares)?,
             },
             actor_items: match (
                 origin.inbox,
@@ -216,10 +258,7 @@ impl ModelConv for model::Object {
                         following,
                         followers,
                         preferred_username: origin.preferred_username,
-                        endpoints: match origin.endpoints {
-                            None => HashMap::new(),
-                            Some(item) => item,
-                        },
+                        endpoints: origin.endpoints.unwrap_or_default(),
                     })
                 }
                 _ => None,
@@ -258,16 +297,18 @@ impl ModelConv for model::Object {
                 former_type: from_lax_array(origin.former_type)?,
                 deleted: to_model_opt(origin.deleted)?,
             },
+            #[cfg(feature = "question")]
             question_items: model::QuestionItems {
                 one_of: from_lax_array(origin.one_of)?,
                 any_of: from_lax_array(origin.any_of)?,
                 closed: origin.closed,
             },
+            #[cfg(feature = "place")]
             place_items: model::PlaceItems {
                 accuracy: origin.accuracy,
                 altitude: origin.altitude,
-                latitute: origin.latitute,
-                longitute: origin.longitute,
+                latitude: origin.latitude,
+                longitude: origin.longitude,
                 radius: origin.radius,
                 units: origin.units,
             },
@@ -277,6 +318,7 @@ impl ModelConv for model::Object {
                 moved_to: origin.moved_to,
                 sensitive: origin.sensitive,
             },
+            #[cfg(feature = "mastodon")]
             mastodon_ext_items: model::MastodonExtItems {
                 featured: origin.featured,
                 featured_tags: origin.featured_tags,
@@ -284,12 +326,25 @@ impl ModelConv for model::Object {
                 suspended: origin.suspended,
                 devices: origin.devices,
             },
+            #[cfg(feature = "security")]
             security_items: model::SecurityItems {
                 public_key: to_model_opt(origin.public_key)?,
             },
             property_items: model::PropertyItems {
                 value: origin.value,
             },
+            schema_org_items: model::SchemaOrgItems {
+                category: from_lax_array(category)?,
+                license: license
+                    .map(|license| model::SchemaValue::to_model(serde_json::from_value(license)?))
+                    .transpose()?,
+                identifier: origin.identifier,
+            },
+            json_items: model::JsonItems {
+                property_order,
+                null_properties,
+                unknown_properties,
+            },
         })
     }
 }
@@ -306,6 +361,7 @@ impl ModelConv for model::Link {
             height: self.height,
             hreflang: self.hreflang.clone(),
             media_type: to_lax_array(&self.media_type)?,
+            name: to_lax_array(&self.name)?,
             rel: to_lax_array(&self.rel)?,
             width: self.width,
         })
@@ -320,6 +376,7 @@ impl ModelConv for model::Link {
             height: origin.height,
             hreflang: origin.hreflang,
             media_type: from_lax_array(origin.media_type)?,
+            name: from_lax_array(origin.name)?,
             rel: from_lax_array(origin.rel)?,
             width: origin.width,
         })
@@ -333,15 +390,15 @@ impl ModelConv for model::ObjectOrLink {
         match self {
             Self::Object(origin) => Ok(ObjectOrLink::Object(origin.from_model()?)),
             Self::Link(origin) => {
-                if {
-                    origin.height.is_none()
-                        && origin.hreflang.is_none()
-                        && origin.id.is_none()
-                        && origin.media_type.is_empty()
-                        && origin.rel.is_empty()
-                        && origin.typ.is_empty()
-                        && origin.width.is_none()
-                } {
+                if origin.height.is_none()
+                    && origin.hreflang.is_none()
+                    && origin.id.is_none()
+                    && origin.media_type.is_empty()
+                    && origin.name.is_empty()
+                    && origin.rel.is_empty()
+                    && origin.typ.is_empty()
+                    && origin.width.is_none()
+                {
                     Ok(ObjectOrLink::Uri(origin.href.clone()))
                 } else {
                     Ok(ObjectOrLink::Link(origin.from_model()?))
@@ -373,11 +430,20 @@ impl ModelConv for model::Context {
                 Ok(Context::Mix(dest))
             }
             Self::TermDefs(origin) => {
-                let mut dest = HashMap::with_capacity(origin.len());
-                for (key, item) in origin {
-                    dest.insert(key.clone(), item.from_model()?);
+                let mut terms = Map::with_capacity(origin.terms.len());
+                for (key, item) in &origin.terms {
+                    if key.starts_with('@') {
+                        return Err(format!("{} is not a term", key).into());
+                    }
+                    terms.insert(key.clone(), item.from_model()?);
                 }
-                Ok(Context::TermDefs(dest))
+                Ok(Context::TermDefs(ContextDefinition {
+                    language: origin.language.clone(),
+                    vocab: origin.vocab.clone(),
+                    base: origin.base.clone(),
+                    protected: origin.protected,
+                    terms,
+                }))
             }
         }
     }
@@ -393,11 +459,17 @@ impl ModelConv for model::Context {
                 Ok(model::Context::Mix(dest))
             }
             Context::TermDefs(origin) => {
-                let mut dest = HashMap::with_capacity(origin.len());
-                for (key, item) in origin {
-                    dest.insert(key, ModelConv::to_model(item)?);
+                let mut terms = Map::with_capacity(origin.terms.len());
+                for (key, item) in origin.terms {
+                    terms.insert(key, ModelConv::to_model(item)?);
                 }
-                Ok(model::Context::TermDefs(dest))
+                Ok(model::Context::TermDefs(model::ContextDefinition {
+                    language: origin.language,
+                    vocab: origin.vocab,
+                    base: origin.base,
+                    protected: origin.protected,
+                    terms,
+                }))
             }
         }
     }
@@ -409,9 +481,18 @@ impl ModelConv for model::Iri {
     fn from_model(&self) -> Result<Self::JsonSerdeValue, Box<dyn Error>> {
         match self {
             Self::Direct(origin) => Ok(Iri::Direct(origin.clone())),
-            Self::TypeCoercion { id, typ } => Ok(Iri::TypeCoercion(TypeCoercion {
+            Self::TypeCoercion {
+                id,
+                typ,
+                container,
+                protected,
+                context,
+            } => Ok(Iri::TypeCoercion(TypeCoercion {
                 id: id.clone(),
                 typ: typ.clone(),
+                container: container.clone(),
+                protected: *protected,
+                context: boxed_from_model_opt(context.as_deref())?,
             })),
         }
     }
@@ -422,6 +503,9 @@ impl ModelConv for model::Iri {
             Iri::TypeCoercion(origin) => Ok(model::Iri::TypeCoercion {
                 id: origin.id,
                 typ: origin.typ,
+                container: origin.container,
+                protected: origin.protected,
+                context: boxed_to_model_opt(origin.context)?,
             }),
         }
     }
@@ -447,6 +531,47 @@ impl ModelConv for model::Key {
     }
 }
 
+impl ModelConv for model::SchemaValue {
+    type JsonSerdeValue = SchemaValue;
+
+    fn from_model(&self) -> Result<Self::JsonSerdeValue, Box<dyn Error>> {
+        Ok(match self {
+            Self::Text(text) => SchemaValue::Text(text.clone()),
+            Self::Thing { identifier, name } => SchemaValue::Thing {
+                identifier: identifier.clone(),
+                name: name.clone(),
+            },
+        })
+    }
+
+    fn to_model(origin: Self::JsonSerdeValue) -> Result<Self, Box<dyn Error>> {
+        Ok(match origin {
+            SchemaValue::Text(text) => Self::Text(text),
+            SchemaValue::Thing { identifier, name } => Self::Thing { identifier, name },
+        })
+    }
+}
+
+impl<T: ModelConv> ModelConv for Vec<T> {
+    type JsonSerdeValue = Vec<T::JsonSerdeValue>;
+
+    fn from_model(&self) -> Result<Self::JsonSerdeValue, Box<dyn Error>> {
+        let mut dest = Vec::with_capacity(self.len());
+        for item in self {
+            dest.push(item.from_model()?);
+        }
+        Ok(dest)
+    }
+
+    fn to_model(origin: Self::JsonSerdeValue) -> Result<Self, Box<dyn Error>> {
+        let mut dest = Vec::with_capacity(origin.len());
+        for item in origin {
+            dest.push(T::to_model(item)?);
+        }
+        Ok(dest)
+    }
+}
+
 impl ModelConv for String {
     type JsonSerdeValue = String;
 
@@ -471,14 +596,63 @@ impl ModelConv for DateTime<Utc> {
     }
 }
 
-pub fn to_lax_array<T: ModelConv>(origin: &[T]) -> Result<Option<Value>, Box<dyn Error>> {
-    match origin.len() {
-        0 | 1 => {
-            for item in origin {
-                return Ok(Some(serde_json::to_value(item.from_model()?)?));
+// The link of `url`. Of a list, e.g. the renditions of a PeerTube video,
+// the first HTML page is taken, or the first link if none is.
+fn url_to_model(value: Value) -> Result<Option<model::Link>, Box<dyn Error>> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(href) => Ok(Some(model::Link::from(href))),
+        Value::Array(items) => {
+            let mut links = vec![];
+            for item in items {
+                links.extend(url_to_model(item)?);
             }
-            Ok(None)
+            let html = links.iter().position(|link| {
+                link.media_type
+                    .iter()
+                    .any(|media_type| media_type == "text/html")
+            });
+            Ok(match html {
+                Some(index) => Some(links.swap_remove(index)),
+                None => links.into_iter().next(),
+            })
         }
+        item => {
+            let item: Link = serde_json::from_value(item)?;
+            Ok(Some(model::Link::to_model(item)?))
+        }
+    }
+}
+
+// A value of a schema.org term, or `None` if it has another shape. Other
+// vocabularies may use the same names, so such values are kept as unknown
+// properties.
+fn schema_org_value(
+    name: &str,
+    value: Option<Value>,
+    multiple: bool,
+    unknown_properties: &mut Map<String, Value>,
+) -> Option<Value> {
+    let value = value?;
+    let is_schema_value = |value: &Value| SchemaValue::deserialize(value).is_ok();
+    let valid = match &value {
+        Value::Array(items) => multiple && items.iter().all(is_schema_value),
+        value => is_schema_value(value),
+    };
+    if valid {
+        Some(value)
+    } else {
+        unknown_properties.insert(name.to_string(), value);
+        None
+    }
+}
+
+pub fn to_lax_array<T: ModelConv>(origin: &[T]) -> Result<Option<Value>, Box<dyn Error>> {
+    match origin.len() {
+        0 | 1 => match origin.first() {
+            None => Ok(None),
+            Some(item) => Ok(Some(serde_json::to_value(item.from_model()?)?)),
+        },
         _ => {
             let mut dest = Vec::with_capacity(origin.len());
             for item in origin {
@@ -526,7 +700,7 @@ pub fn to_model_opt<T: ModelConv>(
 }
 
 pub fn boxed_from_model_opt<T: ModelConv>(
-    origin: Option<&Box<T>>,
+    origin: Option<&T>,
 ) -> Result<Option<Box<T::JsonSerdeValue>>, Box<dyn Error>> {
     match origin {
         None => Ok(None),
@@ -551,7 +725,40 @@ pub fn boxed_to_model_opt<T: ModelConv>(
 pub enum Context {
     Single(Iri),
     Mix(Vec<Context>),
-    TermDefs(HashMap<String, Iri>),
+    TermDefs(ContextDefinition),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ContextDefinition {
+    #[serde(
+        rename = "@language",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    language: Option<Option<String>>,
+    #[serde(
+        rename = "@vocab",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    vocab: Option<Option<String>>,
+    #[serde(
+        rename = "@base",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::double_option"
+    )]
+    base: Option<Option<String>>,
+    #[serde(
+        rename = "@protected",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    protected: Option<bool>,
+    #[serde(flatten)]
+    terms: Map<String, Iri>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -568,11 +775,306 @@ pub struct TypeCoercion {
     id: String,
     #[serde(rename = "@type")]
     typ: Option<String>,
+    #[serde(rename = "@container")]
+    container: Option<String>,
+    #[serde(rename = "@protected")]
+    protected: Option<bool>,
+    #[serde(rename = "@context")]
+    context: Option<Box<Context>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Object {
+    properties: ObjectProperties,
+    property_order: Vec<String>,
+    null_properties: Vec<String>,
+}
+
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.property_order.is_empty() && self.null_properties.is_empty() {
+            return self.properties.serialize(serializer);
+        }
+
+        let mut properties = match serde_json::to_value(&self.properties) {
+            Ok(Value::Object(properties)) => properties,
+            Ok(_) => return Err(ser::Error::custom("object properties must be a map")),
+            Err(err) => return Err(ser::Error::custom(err)),
+        };
+        for key in self.null_properties.iter() {
+            properties.entry(key.clone()).or_insert(Value::Null);
+        }
+        let mut map = serializer.serialize_map(Some(properties.len()))?;
+        for key in self.property_order.iter() {
+            if let Some(value) = properties.get(key) {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        for (key, value) in properties.iter() {
+            if !self.property_order.contains(key) {
+                map.serialize_entry(key, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Object {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+struct ObjectVisitor;
+
+impl<'de> de::Visitor<'de> for ObjectVisitor {
+    type Value = Object;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Object, A::Error> {
+        let mut keys = RecordingMapAccess {
+            map,
+            key: None,
+            keys: vec![],
+            null_keys: vec![],
+        };
+        let properties =
+            ObjectProperties::deserialize(de::value::MapAccessDeserializer::new(&mut keys))?;
+        // Sorted as the keys of `serde_json::Map` are, so that objects
+        // compare equal whatever order their documents have.
+        keys.null_keys.sort();
+        #[cfg(feature = "preserve_order")]
+        let property_order = keys.keys;
+        #[cfg(not(feature = "preserve_order"))]
+        let property_order = vec![];
+        Ok(Object {
+            properties,
+            property_order,
+            null_properties: keys.null_keys,
+        })
+    }
+}
+
+// Records the keys and the keys given `null` of an object while its
+// properties are read, so that the entries are read once.
+struct RecordingMapAccess<A> {
+    map: A,
+    key: Option<String>,
+    keys: Vec<String>,
+    null_keys: Vec<String>,
+}
+
+impl<'de, A: de::MapAccess<'de>> de::MapAccess<'de> for RecordingMapAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        match self.map.next_key::<String>()? {
+            None => Ok(None),
+            Some(key) => {
+                let value = seed.deserialize(
+                    de::IntoDeserializer::<A::Error>::into_deserializer(key.as_str()),
+                )?;
+                self.keys.push(key.clone());
+                self.key = Some(key);
+                Ok(Some(value))
+            }
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, A::Error> {
+        let mut is_null = false;
+        let value = self.map.next_value_seed(NullRecordingSeed {
+            seed,
+            is_null: &mut is_null,
+        })?;
+        if is_null {
+            if let Some(key) = self.key.take() {
+                self.null_keys.push(key);
+            }
+        }
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct NullRecordingSeed<'a, S> {
+    seed: S,
+    is_null: &'a mut bool,
+}
+
+impl<'de, 'a, S: de::DeserializeSeed<'de>> de::DeserializeSeed<'de> for NullRecordingSeed<'a, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        self.seed.deserialize(NullRecordingDeserializer {
+            deserializer,
+            is_null: self.is_null,
+        })
+    }
+}
+
+struct NullRecordingDeserializer<'a, D> {
+    deserializer: D,
+    is_null: &'a mut bool,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $typ:ty),*)),* $(,)?) => {$(
+        fn $method<V: de::Visitor<'de>>(
+            self,
+            $($arg: $typ,)*
+            visitor: V,
+        ) -> Result<V::Value, D::Error> {
+            self.deserializer.$method($($arg,)* NullRecordingVisitor {
+                visitor,
+                is_null: self.is_null,
+            })
+        }
+    )*};
+}
+
+impl<'de, 'a, D: Deserializer<'de>> Deserializer<'de> for NullRecordingDeserializer<'a, D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+}
+
+struct NullRecordingVisitor<'a, V> {
+    visitor: V,
+    is_null: &'a mut bool,
+}
+
+macro_rules! forward_visits {
+    ($($method:ident($($typ:ty)?)),* $(,)?) => {$(
+        fn $method<E: de::Error>(self, $(value: $typ)?) -> Result<V::Value, E> {
+            self.visitor.$method($(value as $typ)?)
+        }
+    )*};
+}
+
+impl<'de, 'a, V: de::Visitor<'de>> de::Visitor<'de> for NullRecordingVisitor<'a, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        *self.is_null = true;
+        self.visitor.visit_unit()
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        *self.is_null = true;
+        self.visitor.visit_none()
+    }
+
+    forward_visits!(
+        visit_bool(bool),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f64(f64),
+        visit_str(&str),
+        visit_borrowed_str(&'de str),
+        visit_string(String),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    );
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.visitor.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.visitor.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_enum(data)
+    }
+}
+
+/**
+ * Keys of a JSON object which are not properties of the model and so would be
+ * kept in `unknown_properties`.
+ */
+pub fn unknown_keys(properties: &serde_json::Map<String, Value>) -> Vec<String> {
+    // Only the keys matter, so the values are not copied nor validated.
+    let keys = properties
+        .keys()
+        .map(|key| (key.clone(), Value::Null))
+        .collect();
+    match ObjectProperties::deserialize(Value::Object(keys)) {
+        Ok(properties) => properties.unknown_properties.keys().cloned().collect(),
+        Err(_) => vec![],
+    }
 }
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
-pub struct Object {
+struct ObjectProperties {
     #[serde(rename = "@context")]
     schema_context: Option<Context>,
     id: Option<String>,
@@ -581,7 +1083,7 @@ pub struct Object {
 
     // https://www.w3.org/ns/activitystreams#Object
     attachment: Option<Value>,
-    #[serde(rename = "attributeTo")]
+    #[serde(rename = "attributedTo", alias = "attributeTo")]
     attributed_to: Option<Value>,
     audience: Option<Value>,
     bcc: Option<Value>,
@@ -604,22 +1106,31 @@ pub struct Object {
     url: Option<Value>,
     content: Option<Value>,
     #[serde(rename = "contentMap")]
-    content_map: Option<HashMap<String, String>>,
+    content_map: Option<Map<String, String>>,
     name: Option<Value>,
     #[serde(rename = "nameMap")]
-    name_map: Option<HashMap<String, String>>,
+    name_map: Option<Map<String, String>>,
     duration: Option<String>,
     #[serde(rename = "mediaType")]
     media_type: Option<Value>,
+    #[serde(rename = "startTime")]
+    start_time: Option<String>,
     #[serde(rename = "endTime")]
     end_time: Option<String>,
     published: Option<String>,
     summary: Option<Value>,
     #[serde(rename = "summaryMap")]
-    summary_map: Option<HashMap<String, String>>,
+    summary_map: Option<Map<String, String>>,
     updated: Option<String>,
     describes: Option<Box<Object>>,
 
+    // https://www.w3.org/TR/activitypub/#likes
+    // Range: Collection | Link
+    likes: Option<Box<ObjectOrLink>>,
+    // https://www.w3.org/TR/activitypub/#shares
+    // Range: Collection | Link
+    shares: Option<Box<ObjectOrLink>>,
+
     // https://www.w3.org/ns/activitystreams#Actor
     inbox: Option<String>,
     outbox: Option<String>,
@@ -627,7 +1138,7 @@ pub struct Object {
     followers: Option<String>,
     #[serde(rename = "preferredUsername")]
     preferred_username: Option<String>,
-    endpoints: Option<HashMap<String, String>>,
+    endpoints: Option<Map<String, String>>,
 
     // https://www.w3.org/ns/activitystreams#Activity
     actor: Option<Value>,
@@ -639,7 +1150,8 @@ pub struct Object {
 
     // https://www.w3.org/ns/activitystreams#Collection
     #[serde(rename = "totalItems")]
-    total_items: Option<usize>,
+    #[serde(default, deserialize_with = "lax::number")]
+    total_items: Option<u64>,
     // Range: CollectionPage | Link
     current: Option<Box<ObjectOrLink>>,
     // Range: CollectionPage | Link
@@ -661,7 +1173,8 @@ pub struct Object {
 
     // https://www.w3.org/ns/activitystreams#OrderedCollectionPage
     #[serde(rename = "startIndex")]
-    start_index: Option<usize>,
+    #[serde(default, deserialize_with = "lax::number")]
+    start_index: Option<u64>,
 
     // https://www.w3.org/ns/activitystreams#Relationship
     subject: Option<Box<ObjectOrLink>>,
@@ -672,69 +1185,115 @@ pub struct Object {
     deleted: Option<String>,
 
     // https://www.w3.org/ns/activitystreams#Question
+    #[cfg(feature = "question")]
     #[serde(rename = "oneOf")]
     one_of: Option<Value>,
+    #[cfg(feature = "question")]
     #[serde(rename = "anyOf")]
     any_of: Option<Value>,
+    #[cfg(feature = "question")]
     closed: Option<Value>,
 
     // https://www.w3.org/ns/activitystreams#Place
+    #[cfg(feature = "place")]
+    #[serde(default, deserialize_with = "lax::number")]
     accuracy: Option<f64>,
+    #[cfg(feature = "place")]
+    #[serde(default, deserialize_with = "lax::number")]
     altitude: Option<f64>,
-    latitute: Option<f64>,
-    longitute: Option<f64>,
+    #[cfg(feature = "place")]
+    #[serde(alias = "latitute")]
+    #[serde(default, deserialize_with = "lax::number")]
+    latitude: Option<f64>,
+    #[cfg(feature = "place")]
+    #[serde(alias = "longitute")]
+    #[serde(default, deserialize_with = "lax::number")]
+    longitude: Option<f64>,
+    #[cfg(feature = "place")]
+    #[serde(default, deserialize_with = "lax::number")]
     radius: Option<f64>,
+    #[cfg(feature = "place")]
     units: Option<String>,
 
     // https://docs.joinmastodon.org/spec/activitypub/#as
     #[serde(rename = "manuallyApprovesFollowers")]
+    #[serde(default, deserialize_with = "lax::boolean")]
     manually_approves_followers: Option<bool>,
     #[serde(rename = "alsoKnownAs")]
     also_known_as: Option<Value>,
     #[serde(rename = "movedTo")]
     moved_to: Option<String>,
+    #[serde(default, deserialize_with = "lax::boolean")]
     sensitive: Option<bool>,
 
     // http://joinmastodon.org/ns#featured
+    #[cfg(feature = "mastodon")]
     featured: Option<String>,
 
     // http://joinmastodon.org/ns#featuredTags
+    #[cfg(feature = "mastodon")]
     #[serde(rename = "featuredTags")]
     featured_tags: Option<String>,
 
     // http://joinmastodon.org/ns#discoverable
+    #[cfg(feature = "mastodon")]
+    #[serde(default, deserialize_with = "lax::boolean")]
     discoverable: Option<bool>,
 
     // http://joinmastodon.org/ns#suspended
+    #[cfg(feature = "mastodon")]
+    #[serde(default, deserialize_with = "lax::boolean")]
     suspended: Option<bool>,
 
     // http://joinmastodon.org/ns#devices
+    #[cfg(feature = "mastodon")]
     devices: Option<String>,
 
     // https://w3id.org/security/v1
+    #[cfg(feature = "security")]
     #[serde(rename = "publicKey")]
     public_key: Option<Key>,
 
     // https://schema.org/PropertyValue
     value: Option<String>,
+
+    // https://schema.org/category
+    category: Option<Value>,
+
+    // https://schema.org/license
+    #[serde(alias = "licence")]
+    license: Option<Value>,
+
+    // https://schema.org/identifier
+    identifier: Option<String>,
+
+    #[serde(flatten)]
+    unknown_properties: Map<String, Value>,
 }
 
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Link {
+    #[serde(rename = "@context")]
     schema_context: Option<Context>,
     id: Option<String>,
+    #[serde(rename = "type")]
     typ: Option<Value>,
 
     // https://www.w3.org/ns/activitystreams#Link
     href: String,
+    #[serde(default, deserialize_with = "lax::number")]
     height: Option<usize>,
     hreflang: Option<String>,
+    #[serde(rename = "mediaType")]
     media_type: Option<Value>,
+    name: Option<Value>,
     rel: Option<Value>,
+    #[serde(default, deserialize_with = "lax::number")]
     width: Option<usize>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 #[serde(untagged)]
 pub enum ObjectOrLink {
@@ -743,6 +1302,17 @@ pub enum ObjectOrLink {
     Object(Object),
 }
 
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum SchemaValue {
+    Text(String),
+    Thing {
+        identifier: Option<String>,
+        name: Option<String>,
+    },
+}
+
 /**
  * Reference: https://w3c.github.io/vc-data-integrity/vocab/security/vocabulary.html#Key
  */
@@ -0,0 +1,72 @@
+use std::{error::Error, io, marker::PhantomData};
+
+use super::JsonSerde;
+
+/**
+ * Reference: https://github.com/ndjson/ndjson-spec
+ */
+pub struct NdjsonReader<R, T> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<R: io::BufRead, T: JsonSerde> NdjsonReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<R: io::BufRead, T: JsonSerde> Iterator for NdjsonReader<R, T> {
+    type Item = Result<T, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut self.buffer) {
+                Err(err) => return Some(Err(err.into())),
+                Ok(0) => return None,
+                Ok(_) => {}
+            }
+
+            let line = self.buffer.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(
+                T::from_json_str(line).map_err(|err| format!("line {}: {}", self.line, err).into()),
+            );
+        }
+    }
+}
+
+pub struct NdjsonWriter<W> {
+    writer: W,
+}
+
+impl<W: io::Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write<T: JsonSerde>(&mut self, value: &T) -> Result<(), Box<dyn Error>> {
+        value.io_write_json(&mut self.writer)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,97 @@
+use std::error::Error;
+
+use serde_json::{Map, Value};
+
+use super::model_conv;
+
+// Properties whose values are not objects of the model.
+const NON_OBJECT_PROPERTIES: &[&str] = &[
+    "@context",
+    "contentMap",
+    "nameMap",
+    "summaryMap",
+    "endpoints",
+    "publicKey",
+];
+
+// Properties of the model read from a link, which is tried before an object
+// if `href` is given.
+const LINK_PROPERTIES: &[&str] = &[
+    "@context",
+    "id",
+    "type",
+    "href",
+    "height",
+    "hreflang",
+    "mediaType",
+    "name",
+    "rel",
+    "width",
+];
+
+/**
+ * Calls `f` with the properties of each ActivityStreams node of a document,
+ * i.e. the top level object and the objects and links given to properties
+ * of the model, at any depth. Values of unknown properties are JSON of other
+ * vocabularies, so their keys are not taken as properties of the model.
+ */
+pub fn try_for_each_node<F>(value: &mut Value, f: &mut F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(&mut Map<String, Value>) -> Result<(), Box<dyn Error>>,
+{
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                try_for_each_node(value, f)?;
+            }
+        }
+        Value::Object(properties) => {
+            f(properties)?;
+            if is_link(properties) {
+                return Ok(());
+            }
+            let unknown = unknown_keys(properties);
+            for (key, value) in properties.iter_mut() {
+                if has_node_values(key) && !unknown.contains(key) {
+                    try_for_each_node(value, f)?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn for_each_node<F: FnMut(&mut Map<String, Value>)>(value: &mut Value, f: &mut F) {
+    let _ = try_for_each_node(value, &mut |properties| {
+        f(properties);
+        Ok(())
+    });
+}
+
+/**
+ * Keys of a node which are not properties of the model.
+ */
+pub fn unknown_keys(properties: &Map<String, Value>) -> Vec<String> {
+    if is_link(properties) {
+        properties
+            .keys()
+            .filter(|key| !LINK_PROPERTIES.contains(&key.as_str()))
+            .cloned()
+            .collect()
+    } else {
+        model_conv::unknown_keys(properties)
+    }
+}
+
+/**
+ * Whether the values of the property are nodes, as opposed to literals like
+ * language maps.
+ */
+pub fn has_node_values(key: &str) -> bool {
+    !NON_OBJECT_PROPERTIES.contains(&key)
+}
+
+fn is_link(properties: &Map<String, Value>) -> bool {
+    properties.get("href").is_some_and(Value::is_string)
+}
@@ -0,0 +1,338 @@
+use std::error::Error;
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::{ParseMode, Quirks};
+use crate::jsonld::ACTIVITY_STREAMS_CONTEXT;
+use crate::nodeinfo::NodeInfo;
+
+// Properties which are not functional and so may be written as arrays.
+const ARRAY_PROPERTIES: &[&str] = &[
+    "type",
+    "attachment",
+    "attributedTo",
+    "audience",
+    "bcc",
+    "bto",
+    "cc",
+    "context",
+    "generator",
+    "icon",
+    "image",
+    "inReplyTo",
+    "location",
+    "preview",
+    "tag",
+    "to",
+    "content",
+    "name",
+    "summary",
+    "actor",
+    "instrument",
+    "origin",
+    "object",
+    "result",
+    "target",
+    "items",
+    "orderedItems",
+    "relationship",
+    "formerType",
+    "oneOf",
+    "anyOf",
+    "alsoKnownAs",
+];
+
+/**
+ * How the ActivityStreams `@context` of the top level object is written.
+ * `Include` adds it if the object has no `@context`, and `Omit` drops
+ * `@context` if it is only the ActivityStreams context.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum DefaultContext {
+    #[default]
+    Keep,
+    Include,
+    Omit,
+}
+
+/**
+ * Shape of the written JSON. Servers differ in what they accept, e.g. some
+ * expect `to` to always be an array. With `always_arrays`, non-functional
+ * properties are written as arrays even if they have a single value, and
+ * with `relative_to`, ids, hrefs and references of the same origin are made
+ * relative to the URL, which must be absolute. With `normalize_nfc`, names,
+ * summaries and contents are written in Unicode NFC. `quirks` tune the
+ * document for a particular peer.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SerializeOptions {
+    pub always_arrays: bool,
+    pub default_context: DefaultContext,
+    pub pretty: bool,
+    pub sort_keys: bool,
+    pub relative_to: Option<String>,
+    #[cfg(feature = "nfc")]
+    pub normalize_nfc: bool,
+    pub quirks: Quirks,
+}
+
+impl SerializeOptions {
+    /**
+     * The default options with the quirks of the peer, for a document
+     * delivered to it.
+     */
+    pub fn for_peer(node_info: &NodeInfo) -> Self {
+        Self {
+            quirks: Quirks::for_peer(node_info),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn apply(&self, value: &mut Value) -> Result<(), Box<dyn Error>> {
+        #[cfg(feature = "nfc")]
+        if self.normalize_nfc {
+            normalize_nfc(value);
+        }
+        if let Value::Object(properties) = value {
+            match self.default_context {
+                DefaultContext::Keep => {}
+                DefaultContext::Include => {
+                    if !properties.contains_key("@context") {
+                        properties.insert(
+                            "@context".to_string(),
+                            Value::String(ACTIVITY_STREAMS_CONTEXT.to_string()),
+                        );
+                    }
+                }
+                DefaultContext::Omit => {
+                    if properties.get("@context").is_some_and(is_default_context) {
+                        properties.remove("@context");
+                    }
+                }
+            }
+        }
+        if let Some(base) = self.relative_to.as_deref() {
+            let base = Url::parse(base).map_err(|e| format!("invalid base {}: {}", base, e))?;
+            super::relative::relativize(value, &base);
+        }
+        self.quirks.apply(value);
+        if self.always_arrays {
+            wrap_arrays(value, &|key| ARRAY_PROPERTIES.contains(&key));
+        }
+        if self.sort_keys {
+            sort_keys(value);
+        }
+        Ok(())
+    }
+}
+
+fn is_default_context(context: &Value) -> bool {
+    match context {
+        Value::String(iri) => iri == ACTIVITY_STREAMS_CONTEXT,
+        Value::Array(contexts) => match contexts.as_slice() {
+            [context] => is_default_context(context),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Writes the properties of the nodes as arrays, at any depth. `null`s are
+// kept, since they clear properties.
+pub(super) fn wrap_arrays(value: &mut Value, is_array: &dyn Fn(&str) -> bool) {
+    super::node::for_each_node(value, &mut |properties| {
+        for (key, value) in properties.iter_mut() {
+            if is_array(key) && !value.is_array() && !value.is_null() {
+                *value = Value::Array(vec![value.take()]);
+            }
+        }
+    });
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(properties) => {
+            let mut entries: Vec<(String, Value)> =
+                std::mem::take(properties).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            *properties = entries
+                .into_iter()
+                .map(|(key, mut value)| {
+                    sort_keys(&mut value);
+                    (key, value)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        Value::Array(values) => values.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+/**
+ * What to do with properties the model does not know, which are kept in
+ * `unknown_properties` by default.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum UnknownProperties {
+    #[default]
+    Keep,
+    Drop,
+    Reject,
+}
+
+/**
+ * What to do with multiple values given to a functional property, e.g. an
+ * array of `published`. `TakeFirst` keeps the first value.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum MultipleValues {
+    #[default]
+    TakeFirst,
+    Reject,
+}
+
+/**
+ * How documents are read. `lenient_values` accepts numbers and booleans
+ * encoded as strings, and `max_depth` limits the nesting of arrays and
+ * objects while the bytes are parsed, before a deeper document is built;
+ * serde_json itself stops at a depth of 128. Relative ids, hrefs and
+ * references are resolved against the `@base` of the document, or
+ * `base_url` if it has none. With `normalize_nfc`, names, summaries and
+ * contents are read in Unicode NFC, so that names, contents and hashtags of
+ * servers emitting other normal forms compare equal.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DeserializeOptions {
+    pub reject_duplicate_keys: bool,
+    pub max_depth: Option<usize>,
+    pub lenient_values: bool,
+    pub unknown_properties: UnknownProperties,
+    pub multiple_values: MultipleValues,
+    pub base_url: Option<String>,
+    #[cfg(feature = "nfc")]
+    pub normalize_nfc: bool,
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        Self::from(ParseMode::Lax)
+    }
+}
+
+impl From<ParseMode> for DeserializeOptions {
+    fn from(mode: ParseMode) -> Self {
+        let strict = mode == ParseMode::Strict;
+        Self {
+            reject_duplicate_keys: strict,
+            max_depth: None,
+            lenient_values: !strict,
+            unknown_properties: UnknownProperties::Keep,
+            multiple_values: if strict {
+                MultipleValues::Reject
+            } else {
+                MultipleValues::TakeFirst
+            },
+            base_url: None,
+            #[cfg(feature = "nfc")]
+            normalize_nfc: false,
+        }
+    }
+}
+
+impl DeserializeOptions {
+    pub(crate) fn apply(&self, value: &mut Value) -> Result<(), Box<dyn Error>> {
+        if !self.lenient_values {
+            super::strict::check(value)?;
+        }
+        #[cfg(feature = "nfc")]
+        if self.normalize_nfc {
+            normalize_nfc(value);
+        }
+        super::functional::collapse(value, self.multiple_values == MultipleValues::Reject)?;
+        if self.unknown_properties != UnknownProperties::Keep {
+            filter_unknown_properties(value, self.unknown_properties, "")?;
+        }
+        let base = match super::relative::context_base(value) {
+            Some(base) => base,
+            None => self.base_url.as_deref(),
+        };
+        if let Some(base) = base {
+            let base = Url::parse(base).map_err(|e| format!("invalid base {}: {}", base, e))?;
+            super::relative::resolve(value, &base);
+        }
+        Ok(())
+    }
+}
+
+// Properties in natural language, which are normalized. Hashtags are tags
+// with a name. IRIs, e.g. ids and hrefs, compare by their code points, so
+// they are left as they are.
+#[cfg(feature = "nfc")]
+const NATURAL_LANGUAGE_PROPERTIES: &[&str] = &[
+    "content",
+    "contentMap",
+    "name",
+    "nameMap",
+    "preferredUsername",
+    "summary",
+    "summaryMap",
+];
+
+#[cfg(feature = "nfc")]
+fn normalize_nfc(value: &mut Value) {
+    use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+    fn normalize(value: &mut Value) {
+        match value {
+            Value::String(text) if !is_nfc(text) => *text = text.nfc().collect(),
+            Value::Array(values) => values.iter_mut().for_each(normalize),
+            Value::Object(language_map) => language_map.values_mut().for_each(normalize),
+            _ => {}
+        }
+    }
+
+    super::node::for_each_node(value, &mut |properties| {
+        for (key, value) in properties.iter_mut() {
+            if NATURAL_LANGUAGE_PROPERTIES.contains(&key.as_str()) {
+                normalize(value);
+            }
+        }
+    });
+}
+
+fn filter_unknown_properties(
+    value: &mut Value,
+    policy: UnknownProperties,
+    path: &str,
+) -> Result<(), Box<dyn Error>> {
+    match value {
+        Value::Object(properties) => {
+            for key in super::node::unknown_keys(properties) {
+                if policy == UnknownProperties::Reject {
+                    return Err(
+                        format!("unknown property: {}/{}", path, escape_pointer(&key)).into(),
+                    );
+                }
+                properties.remove(&key);
+            }
+            for (key, value) in properties.iter_mut() {
+                if super::node::has_node_values(key) {
+                    let path = format!("{}/{}", path, escape_pointer(key));
+                    filter_unknown_properties(value, policy, &path)?;
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                filter_unknown_properties(value, policy, &format!("{}/{}", path, index))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
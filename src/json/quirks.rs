@@ -0,0 +1,82 @@
+use serde_json::Value;
+
+use crate::model::Map;
+use crate::nodeinfo::NodeInfo;
+
+/**
+ * Tweaks of the written JSON for what particular servers accept beyond the
+ * specifications, e.g. Mastodon expecting `tag` as an array. Profiles of
+ * known software are selected per delivery target by `for_peer`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Quirks {
+    // Properties written as arrays even with a single value.
+    pub array_properties: Vec<String>,
+    // Terms defined in the `@context` of the top level object, if it has one
+    // and does not define them yet.
+    pub context_terms: Map<String, String>,
+}
+
+impl Quirks {
+    /**
+     * The profile of the software the peer runs, empty for software without
+     * known quirks.
+     */
+    pub fn for_peer(node_info: &NodeInfo) -> Self {
+        match node_info.software_name.as_str() {
+            // Mastodon reads single mentions, hashtags and attachments
+            // inconsistently outside of arrays.
+            "mastodon" | "hometown" => Self {
+                array_properties: ["to", "cc", "tag", "attachment"].map(String::from).to_vec(),
+                ..Self::default()
+            },
+            // Pleroma and Akkoma expand documents as JSON-LD and drop the
+            // terms which the ActivityStreams context lacks.
+            "pleroma" | "akkoma" => Self {
+                context_terms: [("Hashtag", "as:Hashtag"), ("sensitive", "as:sensitive")]
+                    .into_iter()
+                    .map(|(term, iri)| (term.to_string(), iri.to_string()))
+                    .collect(),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub(crate) fn apply(&self, value: &mut Value) {
+        if !self.array_properties.is_empty() {
+            super::options::wrap_arrays(value, &|key| {
+                self.array_properties.iter().any(|property| property == key)
+            });
+        }
+        if !self.context_terms.is_empty() {
+            add_context_terms(value, &self.context_terms);
+        }
+    }
+}
+
+fn add_context_terms(value: &mut Value, terms: &Map<String, String>) {
+    let Some(context) = value.get_mut("@context") else {
+        return;
+    };
+    let mut contexts = match context.take() {
+        Value::Array(contexts) => contexts,
+        context => vec![context],
+    };
+    let is_defined = |term: &str| contexts.iter().any(|context| context.get(term).is_some());
+    let missing: Vec<(String, Value)> = terms
+        .iter()
+        .filter(|(term, _)| !is_defined(term))
+        .map(|(term, iri)| (term.clone(), Value::String(iri.clone())))
+        .collect();
+    if !missing.is_empty() {
+        match contexts.last_mut() {
+            Some(Value::Object(definitions)) => definitions.extend(missing),
+            _ => contexts.push(Value::Object(missing.into_iter().collect())),
+        }
+    }
+    *context = match <[Value; 1]>::try_from(contexts) {
+        Ok([context]) => context,
+        Err(contexts) => Value::Array(contexts),
+    };
+}
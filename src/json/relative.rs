@@ -0,0 +1,134 @@
+use serde_json::Value;
+use url::Url;
+
+// Properties whose string values are IRIs to resolve, i.e. ids, hrefs and
+// references to other objects.
+const IRI_PROPERTIES: &[&str] = &[
+    "actor",
+    "alsoKnownAs",
+    "anyOf",
+    "attachment",
+    "attributedTo",
+    "audience",
+    "bcc",
+    "bto",
+    "cc",
+    "context",
+    "current",
+    "describes",
+    "devices",
+    "featured",
+    "featuredTags",
+    "first",
+    "followers",
+    "following",
+    "generator",
+    "href",
+    "icon",
+    "id",
+    "image",
+    "inReplyTo",
+    "inbox",
+    "instrument",
+    "items",
+    "last",
+    "liked",
+    "likes",
+    "location",
+    "movedTo",
+    "next",
+    "object",
+    "oneOf",
+    "orderedItems",
+    "origin",
+    "outbox",
+    "owner",
+    "partOf",
+    "prev",
+    "preview",
+    "proxyUrl",
+    "relationship",
+    "replies",
+    "result",
+    "sharedInbox",
+    "shares",
+    "streams",
+    "subject",
+    "tag",
+    "target",
+    "to",
+    "url",
+];
+
+/**
+ * The `@base` given in the top level `@context`, which is `Some(None)` if it
+ * is reset by `null`.
+ */
+pub fn context_base(value: &Value) -> Option<Option<&str>> {
+    match value.get("@context")? {
+        Value::Array(contexts) => contexts.iter().rev().find_map(definition_base),
+        context => definition_base(context),
+    }
+}
+
+fn definition_base(context: &Value) -> Option<Option<&str>> {
+    match context.get("@base")? {
+        Value::Null => Some(None),
+        base => Some(base.as_str()),
+    }
+}
+
+/**
+ * Resolves relative IRIs of ids, hrefs and references against `base`, at
+ * any depth.
+ */
+pub fn resolve(value: &mut Value, base: &Url) {
+    map_iris(value, &|iri| match Url::parse(iri) {
+        Ok(_) => None,
+        Err(_) if iri.starts_with("_:") => None,
+        Err(_) => base.join(iri).ok().map(String::from),
+    });
+}
+
+/**
+ * Makes ids, hrefs and references relative to `base` if they share its
+ * origin.
+ */
+pub fn relativize(value: &mut Value, base: &Url) {
+    map_iris(value, &|iri| {
+        let url = Url::parse(iri).ok()?;
+        if url.origin() != base.origin() {
+            return None;
+        }
+        // An empty reference is left absolute, as it reads as a missing id.
+        base.make_relative(&url)
+            .filter(|relative| !relative.is_empty())
+    });
+}
+
+fn map_iris(value: &mut Value, f: &impl Fn(&str) -> Option<String>) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|item| map_iris(item, f)),
+        Value::Object(properties) => {
+            for (key, value) in properties.iter_mut() {
+                if key == "@context" {
+                    continue;
+                }
+                if IRI_PROPERTIES.contains(&key.as_str()) {
+                    match value {
+                        Value::Array(items) => items.iter_mut().for_each(|item| map_iri(item, f)),
+                        _ => map_iri(value, f),
+                    }
+                }
+                map_iris(value, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn map_iri(value: &mut Value, f: &impl Fn(&str) -> Option<String>) {
+    if let Some(iri) = value.as_str().and_then(f) {
+        *value = Value::String(iri);
+    }
+}
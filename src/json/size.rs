@@ -0,0 +1,414 @@
+use std::fmt::{self, Display};
+use std::io;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value;
+
+use crate::model::{Context, Iri, Link, Map, Object, ObjectOrLink, SchemaValue};
+
+impl Object {
+    /**
+     * An estimate of the length in bytes of the compact JSON of the object,
+     * computed from the model without serializing it. Strings and unknown
+     * properties are measured exactly; numbers and dates may be off by a
+     * few bytes, and the terms which serialization adds to `@context` are
+     * not counted.
+     *
+     * For enforcing size budgets, e.g. deciding when embedded objects are
+     * better sent as references, rather than for exact lengths.
+     */
+    pub fn estimated_json_size(&self) -> usize {
+        object_size(self)
+    }
+}
+
+// The size of a JSON object, accumulated property by property.
+#[derive(Default)]
+struct Properties {
+    size: usize,
+    count: usize,
+}
+
+impl Properties {
+    fn add(&mut self, key: &str, value: usize) {
+        self.size += string_size(key) + 1 + value;
+        self.count += 1;
+    }
+
+    fn total(&self) -> usize {
+        2 + self.size + self.count.saturating_sub(1)
+    }
+
+    fn str(&mut self, key: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.add(key, string_size(value));
+        }
+    }
+
+    fn strs(&mut self, key: &str, values: &[String]) {
+        if !values.is_empty() {
+            self.add(
+                key,
+                lax_array_size(values.iter().map(|value| string_size(value))),
+            );
+        }
+    }
+
+    fn values(&mut self, key: &str, values: &[ObjectOrLink]) {
+        if !values.is_empty() {
+            self.add(key, lax_array_size(values.iter().map(object_or_link_size)));
+        }
+    }
+
+    fn objects(&mut self, key: &str, objects: &[Object]) {
+        if !objects.is_empty() {
+            self.add(key, lax_array_size(objects.iter().map(object_size)));
+        }
+    }
+
+    fn value(&mut self, key: &str, value: Option<&ObjectOrLink>) {
+        if let Some(value) = value {
+            self.add(key, object_or_link_size(value));
+        }
+    }
+
+    fn object(&mut self, key: &str, object: Option<&Object>) {
+        if let Some(object) = object {
+            self.add(key, object_size(object));
+        }
+    }
+
+    fn map(&mut self, key: &str, map: &Map<String, String>) {
+        if !map.is_empty() {
+            self.add(key, map_size(map));
+        }
+    }
+
+    fn date(&mut self, key: &str, date: Option<&DateTime<Utc>>) {
+        if let Some(date) = date {
+            self.add(
+                key,
+                2 + date.to_rfc3339_opts(SecondsFormat::Secs, true).len(),
+            );
+        }
+    }
+
+    fn display<T: Display>(&mut self, key: &str, value: Option<T>) {
+        if let Some(value) = value {
+            self.add(key, display_size(value));
+        }
+    }
+}
+
+fn object_size(object: &Object) -> usize {
+    let mut dest = Properties::default();
+    if let Some(context) = &object.schema_context {
+        dest.add("@context", context_size(context));
+    }
+    dest.str("id", object.id.as_deref());
+    dest.strs("type", &object.typ);
+
+    let items = &object.object_items;
+    dest.values("attachment", &items.attachment);
+    dest.values("attributedTo", &items.attributed_to);
+    dest.values("audience", &items.audience);
+    dest.values("bcc", &items.bcc);
+    dest.values("bto", &items.bto);
+    dest.values("cc", &items.cc);
+    dest.values("context", &items.context);
+    dest.values("generator", &items.generator);
+    dest.values("icon", &items.icon);
+    dest.values("image", &items.image);
+    dest.values("inReplyTo", &items.in_reply_to);
+    dest.values("location", &items.location);
+    dest.values("preview", &items.preview);
+    dest.object("replies", items.replies.as_deref());
+    dest.values("tag", &items.tag);
+    dest.values("to", &items.to);
+    if let Some(url) = &items.url {
+        dest.add("url", link_size(url));
+    }
+    dest.strs("content", &items.content);
+    dest.map("contentMap", &items.content_map);
+    dest.strs("name", &items.name);
+    dest.map("nameMap", &items.name_map);
+    dest.str("duration", items.duration.as_deref());
+    dest.strs("mediaType", &items.media_type);
+    dest.date("startTime", items.start_time.as_ref());
+    dest.date("endTime", items.end_time.as_ref());
+    dest.date("published", items.published.as_ref());
+    dest.strs("summary", &items.summary);
+    dest.map("summaryMap", &items.summary_map);
+    dest.date("updated", items.updated.as_ref());
+    dest.object("describes", items.describes.as_deref());
+    dest.value("likes", items.likes.as_deref());
+    dest.value("shares", items.shares.as_deref());
+
+    if let Some(items) = &object.actor_items {
+        dest.str("inbox", Some(&items.inbox));
+        dest.str("outbox", Some(&items.outbox));
+        dest.str("following", Some(&items.following));
+        dest.str("followers", Some(&items.followers));
+        dest.str("preferredUsername", items.preferred_username.as_deref());
+        dest.map("endpoints", &items.endpoints);
+    }
+
+    let items = &object.activity_items;
+    dest.values("actor", &items.actor);
+    dest.values("instrument", &items.instrument);
+    dest.values("origin", &items.origin);
+    dest.values("object", &items.object);
+    dest.values("result", &items.result);
+    dest.values("target", &items.target);
+
+    let items = &object.collection_items;
+    dest.display("totalItems", items.total_items);
+    dest.value("current", items.current.as_deref());
+    dest.value("first", items.first.as_deref());
+    dest.value("last", items.last.as_deref());
+    dest.values("items", &items.items);
+    dest.values(
+        "orderedItems",
+        &object.ordered_collection_items.ordered_items,
+    );
+    let items = &object.collection_page_items;
+    dest.value("next", items.next.as_deref());
+    dest.value("prev", items.prev.as_deref());
+    dest.value("partOf", items.part_of.as_deref());
+    dest.display(
+        "startIndex",
+        object.ordered_collection_page_items.start_index,
+    );
+
+    dest.value("subject", object.relationship_items.subject.as_deref());
+    dest.objects("relationship", &object.relationship_items.relationship);
+    dest.objects("formerType", &object.tombstone_items.former_type);
+    dest.date("deleted", object.tombstone_items.deleted.as_ref());
+
+    #[cfg(feature = "question")]
+    {
+        let items = &object.question_items;
+        dest.values("oneOf", &items.one_of);
+        dest.values("anyOf", &items.any_of);
+        if let Some(closed) = &items.closed {
+            dest.add("closed", json_size(closed));
+        }
+    }
+    #[cfg(feature = "place")]
+    {
+        let items = &object.place_items;
+        dest.display("accuracy", items.accuracy);
+        dest.display("altitude", items.altitude);
+        dest.display("latitude", items.latitude);
+        dest.display("longitude", items.longitude);
+        dest.display("radius", items.radius);
+        dest.str("units", items.units.as_deref());
+    }
+
+    let items = &object.activity_streams_ext_items;
+    dest.display(
+        "manuallyApprovesFollowers",
+        items.manually_approves_followers,
+    );
+    dest.strs("alsoKnownAs", &items.also_known_as);
+    dest.str("movedTo", items.moved_to.as_deref());
+    dest.display("sensitive", items.sensitive);
+    #[cfg(feature = "mastodon")]
+    {
+        let items = &object.mastodon_ext_items;
+        dest.str("featured", items.featured.as_deref());
+        dest.str("featuredTags", items.featured_tags.as_deref());
+        dest.display("discoverable", items.discoverable);
+        dest.display("suspended", items.suspended);
+        dest.str("devices", items.devices.as_deref());
+    }
+    #[cfg(feature = "security")]
+    if let Some(key) = &object.security_items.public_key {
+        let mut properties = Properties::default();
+        properties.str("id", Some(&key.id));
+        properties.str("owner", Some(&key.owner));
+        properties.str("publicKeyPem", key.public_key_pem.as_deref());
+        dest.add("publicKey", properties.total());
+    }
+
+    dest.str("value", object.property_items.value.as_deref());
+    let items = &object.schema_org_items;
+    if !items.category.is_empty() {
+        dest.add(
+            "category",
+            lax_array_size(items.category.iter().map(schema_value_size)),
+        );
+    }
+    if let Some(license) = &items.license {
+        dest.add("license", schema_value_size(license));
+    }
+    dest.str("identifier", items.identifier.as_deref());
+
+    let items = &object.json_items;
+    for key in &items.null_properties {
+        dest.add(key, 4);
+    }
+    for (key, value) in &items.unknown_properties {
+        dest.add(key, json_size(value));
+    }
+    dest.total()
+}
+
+fn object_or_link_size(value: &ObjectOrLink) -> usize {
+    match value {
+        ObjectOrLink::Object(object) => object_size(object),
+        ObjectOrLink::Link(link) => link_size(link),
+    }
+}
+
+// Links with only `href` are written as the IRI.
+fn link_size(link: &Link) -> usize {
+    let mut dest = Properties::default();
+    if let Some(context) = &link.schema_context {
+        dest.add("@context", context_size(context));
+    }
+    dest.str("id", link.id.as_deref());
+    dest.strs("type", &link.typ);
+    dest.display("height", link.height);
+    dest.str("hreflang", link.hreflang.as_deref());
+    dest.strs("mediaType", &link.media_type);
+    dest.strs("name", &link.name);
+    dest.strs("rel", &link.rel);
+    dest.display("width", link.width);
+    if dest.count == 0 {
+        return string_size(&link.href);
+    }
+    dest.str("href", Some(&link.href));
+    dest.total()
+}
+
+fn context_size(context: &Context) -> usize {
+    match context {
+        Context::Single(iri) => iri_size(iri),
+        Context::Mix(contexts) => array_size(contexts.iter().map(context_size)),
+        Context::TermDefs(definition) => {
+            let mut dest = Properties::default();
+            for (key, value) in [
+                ("@language", &definition.language),
+                ("@vocab", &definition.vocab),
+                ("@base", &definition.base),
+            ] {
+                if let Some(value) = value {
+                    dest.add(key, value.as_deref().map_or(4, string_size));
+                }
+            }
+            dest.display("@protected", definition.protected);
+            for (term, iri) in &definition.terms {
+                dest.add(term, iri_size(iri));
+            }
+            dest.total()
+        }
+    }
+}
+
+fn iri_size(iri: &Iri) -> usize {
+    match iri {
+        Iri::Direct(iri) => string_size(iri),
+        Iri::TypeCoercion {
+            id,
+            typ,
+            container,
+            protected,
+            context,
+        } => {
+            let mut dest = Properties::default();
+            dest.str("@id", Some(id));
+            dest.str("@type", typ.as_deref());
+            dest.str("@container", container.as_deref());
+            dest.display("@protected", *protected);
+            if let Some(context) = context {
+                dest.add("@context", context_size(context));
+            }
+            dest.total()
+        }
+    }
+}
+
+fn schema_value_size(value: &SchemaValue) -> usize {
+    match value {
+        SchemaValue::Text(text) => string_size(text),
+        SchemaValue::Thing { identifier, name } => {
+            let mut dest = Properties::default();
+            dest.str("identifier", identifier.as_deref());
+            dest.str("name", name.as_deref());
+            dest.total()
+        }
+    }
+}
+
+fn map_size(map: &Map<String, String>) -> usize {
+    let mut dest = Properties::default();
+    for (key, value) in map {
+        dest.add(key, string_size(value));
+    }
+    dest.total()
+}
+
+// A single value is written without an array.
+fn lax_array_size<I: ExactSizeIterator<Item = usize>>(sizes: I) -> usize {
+    if sizes.len() == 1 {
+        sizes.sum()
+    } else {
+        array_size(sizes)
+    }
+}
+
+fn array_size<I: ExactSizeIterator<Item = usize>>(sizes: I) -> usize {
+    let separators = sizes.len().saturating_sub(1);
+    2 + separators + sizes.sum::<usize>()
+}
+
+// The length of a JSON string, with the escapes of serde_json.
+fn string_size(text: &str) -> usize {
+    let escapes: usize = text
+        .bytes()
+        .map(|byte| match byte {
+            b'"' | b'\\' | b'\n' | b'\r' | b'\t' | 0x08 | 0x0c => 1,
+            0x00..=0x1f => 5,
+            _ => 0,
+        })
+        .sum();
+    2 + text.len() + escapes
+}
+
+fn json_size(value: &Value) -> usize {
+    let mut counter = Counter(0);
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    }
+}
+
+fn display_size<T: Display>(value: T) -> usize {
+    let mut counter = Counter(0);
+    match fmt::write(&mut counter, format_args!("{}", value)) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    }
+}
+
+// Counts the bytes written to it.
+struct Counter(usize);
+
+impl io::Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::{error::Error, io};
+
+use serde_json::Value;
+
+use super::JsonSerde;
+use crate::model::{Object, ObjectOrLink};
+
+/**
+ * Writes an `OrderedCollection` or `OrderedCollectionPage` item by item, so
+ * that e.g. an export of an outbox of millions of activities is written
+ * without building the collection in memory. The envelope, i.e. the
+ * collection without its `orderedItems`, is written first, and its
+ * `totalItems` is to be known beforehand.
+ */
+pub struct OrderedCollectionWriter<W> {
+    writer: W,
+    count: usize,
+}
+
+impl<W: io::Write> OrderedCollectionWriter<W> {
+    /**
+     * Writes the envelope up to the opening of `orderedItems`. Items of the
+     * envelope itself are ignored.
+     */
+    pub fn new(mut writer: W, envelope: &Object) -> Result<Self, Box<dyn Error>> {
+        let mut properties = match envelope.to_value()? {
+            Value::Object(properties) => properties,
+            _ => return Err("a collection must be written as an object".into()),
+        };
+        properties.remove("orderedItems");
+        let is_empty = properties.is_empty();
+        let mut head = serde_json::to_vec(&Value::Object(properties))?;
+        // Reopen the object for the items.
+        head.pop();
+        writer.write_all(&head)?;
+        if !is_empty {
+            writer.write_all(b",")?;
+        }
+        writer.write_all(br#""orderedItems":["#)?;
+        Ok(Self { writer, count: 0 })
+    }
+
+    pub fn write_item(&mut self, item: &ObjectOrLink) -> Result<(), Box<dyn Error>> {
+        if self.count > 0 {
+            self.writer.write_all(b",")?;
+        }
+        item.io_write_json(&mut self.writer)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /**
+     * The number of items written so far.
+     */
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /**
+     * Closes the collection and returns the writer.
+     */
+    pub fn finish(mut self) -> Result<W, Box<dyn Error>> {
+        self.writer.write_all(b"]}")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/**
+ * Writes the collection with the items of the iterator, stopping at the
+ * first one failing, e.g. to be read from a database.
+ */
+pub fn write_ordered_collection<W, I>(
+    writer: W,
+    envelope: &Object,
+    items: I,
+) -> Result<W, Box<dyn Error>>
+where
+    W: io::Write,
+    I: IntoIterator<Item = Result<ObjectOrLink, Box<dyn Error>>>,
+{
+    let mut writer = OrderedCollectionWriter::new(writer, envelope)?;
+    for item in items {
+        writer.write_item(&item?)?;
+    }
+    writer.finish()
+}
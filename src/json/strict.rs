@@ -0,0 +1,62 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::model::Rel;
+
+// Properties whose values must be JSON numbers in the strict mode.
+const NUMBER_PROPERTIES: &[&str] = &[
+    "totalItems",
+    "startIndex",
+    "width",
+    "height",
+    "accuracy",
+    "altitude",
+    "latitude",
+    "longitude",
+    // Misspellings accepted as aliases.
+    "latitute",
+    "longitute",
+    "radius",
+];
+
+// Properties whose values must be JSON booleans in the strict mode.
+const BOOLEAN_PROPERTIES: &[&str] = &[
+    "manuallyApprovesFollowers",
+    "sensitive",
+    "discoverable",
+    "suspended",
+];
+
+/**
+ * Rejects the encodings which the lax mode accepts, in the nodes at any
+ * depth. Values of unknown properties are not checked.
+ */
+pub fn check(value: &mut Value) -> Result<(), Box<dyn Error>> {
+    super::node::try_for_each_node(value, &mut |properties| {
+        for (key, value) in properties.iter() {
+            if NUMBER_PROPERTIES.contains(&key.as_str())
+                && !matches!(value, Value::Number(_) | Value::Null)
+            {
+                return Err(format!("{} must be a number", key).into());
+            }
+            if BOOLEAN_PROPERTIES.contains(&key.as_str())
+                && !matches!(value, Value::Bool(_) | Value::Null)
+            {
+                return Err(format!("{} must be a boolean", key).into());
+            }
+            if key == "rel" && !rel_is_valid(value) {
+                return Err("rel must not contain spaces or commas".into());
+            }
+        }
+        Ok(())
+    })
+}
+
+fn rel_is_valid(value: &Value) -> bool {
+    match value {
+        Value::String(rel) => Rel::is_valid(rel),
+        Value::Array(rels) => rels.iter().all(rel_is_valid),
+        _ => true,
+    }
+}
@@ -0,0 +1,246 @@
+use std::{collections::HashMap, error::Error};
+
+use serde_json::{Map, Value};
+
+use super::ActiveContext;
+use crate::json::JsonSerde;
+use crate::model::{Context, Object};
+
+// Embedded nodes are nested up to this depth.
+const MAX_EMBED_DEPTH: usize = 4;
+
+/**
+ * Which node of a document to pick with `frame`. Types may be given as terms
+ * of the ActivityStreams context, e.g. `Person`, or as absolute IRIs.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Frame {
+    pub id: Option<String>,
+    pub typ: Option<String>,
+}
+
+/**
+ * Reshapes a JSON-LD document into an object of the model: the first node
+ * matching the frame is picked from the document or its `@graph`, its
+ * properties are compacted to the terms of the ActivityStreams context, and
+ * the nodes it refers to in the document are embedded. Properties which
+ * cannot be compacted keep their keys.
+ *
+ * This implements a subset of framing, without remote contexts other than
+ * the built-in ones.
+ *
+ * Reference: https://www.w3.org/TR/json-ld11-framing/
+ */
+pub fn frame(document: &Value, frame: &Frame) -> Result<Object, Box<dyn Error>> {
+    let compactor = Compactor::new(document);
+    let mut nodes = vec![];
+    collect_nodes(document, &mut nodes);
+    let nodes: Vec<Map<String, Value>> = nodes
+        .into_iter()
+        .map(|node| compactor.compact_node(node))
+        .collect();
+
+    let matched = nodes
+        .iter()
+        .find(|node| compactor.matches(node, frame))
+        .ok_or("no node matches the frame")?;
+    let by_id: HashMap<&str, &Map<String, Value>> = nodes
+        .iter()
+        .filter_map(|node| Some((node.get("id")?.as_str()?, node)))
+        .collect();
+
+    let mut dest = embed(matched, &by_id, &mut vec![]);
+    dest.insert(
+        "@context".to_string(),
+        Context::object_default().to_value()?,
+    );
+    Object::from_value(&Value::Object(dest))
+}
+
+fn collect_nodes<'a>(value: &'a Value, dest: &mut Vec<&'a Map<String, Value>>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_nodes(item, dest)),
+        Value::Object(properties) => {
+            if let Some(graph) = properties.get("@graph") {
+                collect_nodes(graph, dest);
+            }
+            // Objects only with `@id` are references to nodes.
+            let is_node = properties
+                .keys()
+                .any(|key| key == "@type" || (!key.starts_with('@') && key != "id"));
+            if is_node && !properties.contains_key("@value") {
+                dest.push(properties);
+            }
+            for (key, value) in properties {
+                if key != "@context" && key != "@graph" {
+                    collect_nodes(value, dest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn embed(
+    node: &Map<String, Value>,
+    by_id: &HashMap<&str, &Map<String, Value>>,
+    path: &mut Vec<String>,
+) -> Map<String, Value> {
+    let id = node.get("id").and_then(Value::as_str).map(str::to_string);
+    path.extend(id.clone());
+    let dest = node
+        .iter()
+        .map(|(key, value)| {
+            let value = match key.as_str() {
+                "id" | "type" => value.clone(),
+                _ => embed_value(value, by_id, path),
+            };
+            (key.clone(), value)
+        })
+        .collect();
+    if id.is_some() {
+        path.pop();
+    }
+    dest
+}
+
+fn embed_value(
+    value: &Value,
+    by_id: &HashMap<&str, &Map<String, Value>>,
+    path: &mut Vec<String>,
+) -> Value {
+    match value {
+        Value::String(iri) => match by_id.get(iri.as_str()) {
+            Some(node) if path.len() < MAX_EMBED_DEPTH && !path.contains(iri) => {
+                Value::Object(embed(node, by_id, path))
+            }
+            _ => value.clone(),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| embed_value(item, by_id, path))
+                .collect(),
+        ),
+        Value::Object(node) => Value::Object(embed(node, by_id, path)),
+        _ => value.clone(),
+    }
+}
+
+struct Compactor {
+    document_context: ActiveContext,
+    target_context: ActiveContext,
+    // Expanded IRIs to the terms of the target context.
+    terms: HashMap<String, String>,
+}
+
+impl Compactor {
+    fn new(document: &Value) -> Self {
+        let document_context = document
+            .get("@context")
+            .and_then(|context| Context::from_value(context).ok())
+            .map(|context| ActiveContext::new(&context))
+            .unwrap_or_default();
+        let target_context = ActiveContext::new(&Context::object_default());
+        let mut terms = HashMap::new();
+        // Prefer terms without a container, e.g. `name` over `nameMap`.
+        let mut candidates: Vec<_> = target_context.terms.iter().collect();
+        candidates.sort_by_key(|(term, def)| (def.container.is_some(), term.as_str()));
+        for (term, _) in candidates {
+            if term.contains(':') {
+                continue;
+            }
+            if let Some(iri) = target_context.expand_iri(term) {
+                if !iri.starts_with('@') && !iri.ends_with('#') && !iri.ends_with('/') {
+                    terms.entry(iri).or_insert(term.clone());
+                }
+            }
+        }
+        Self {
+            document_context,
+            target_context,
+            terms,
+        }
+    }
+
+    fn compact_iri(&self, value: &str) -> Option<String> {
+        let iri = self.document_context.expand_iri(value)?;
+        self.terms.get(&iri).cloned()
+    }
+
+    fn compact_node(&self, node: &Map<String, Value>) -> Map<String, Value> {
+        let mut dest = Map::new();
+        for (key, value) in node {
+            let (key, value) = match key.as_str() {
+                "@id" | "id" => ("id".to_string(), self.compact_value(value)),
+                "@type" | "type" => ("type".to_string(), self.compact_types(value)),
+                key if key.starts_with('@') => continue,
+                _ => (
+                    self.compact_iri(key).unwrap_or(key.clone()),
+                    self.compact_value(value),
+                ),
+            };
+            dest.insert(key, value);
+        }
+        dest
+    }
+
+    fn compact_types(&self, value: &Value) -> Value {
+        match value {
+            Value::String(typ) => Value::String(self.compact_iri(typ).unwrap_or(typ.clone())),
+            Value::Array(types) if types.len() == 1 => self.compact_types(&types[0]),
+            Value::Array(types) => {
+                Value::Array(types.iter().map(|typ| self.compact_types(typ)).collect())
+            }
+            _ => value.clone(),
+        }
+    }
+
+    fn compact_value(&self, value: &Value) -> Value {
+        match value {
+            Value::Array(items) if items.len() == 1 => self.compact_value(&items[0]),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.compact_value(item)).collect())
+            }
+            Value::Object(properties) => {
+                if let Some(value) = properties.get("@value") {
+                    return value.clone();
+                }
+                if let Some(items) = properties.get("@list").or(properties.get("@set")) {
+                    return self.compact_value(items);
+                }
+                match properties.get("@id") {
+                    Some(id) if properties.len() == 1 => id.clone(),
+                    _ => Value::Object(self.compact_node(properties)),
+                }
+            }
+            _ => value.clone(),
+        }
+    }
+
+    fn matches(&self, node: &Map<String, Value>, frame: &Frame) -> bool {
+        if let Some(id) = &frame.id {
+            if node.get("id").and_then(Value::as_str) != Some(id) {
+                return false;
+            }
+        }
+        if let Some(typ) = &frame.typ {
+            let typ = self
+                .target_context
+                .expand_iri(typ)
+                .and_then(|iri| self.terms.get(&iri).cloned())
+                .unwrap_or(typ.clone());
+            let types = match node.get("type") {
+                Some(Value::String(node_type)) => vec![node_type.as_str()],
+                Some(Value::Array(node_types)) => {
+                    node_types.iter().filter_map(Value::as_str).collect()
+                }
+                _ => vec![],
+            };
+            if !types.contains(&typ.as_str()) {
+                return false;
+            }
+        }
+        true
+    }
+}
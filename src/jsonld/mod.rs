@@ -0,0 +1,381 @@
+use std::{collections::HashMap, error::Error};
+
+use serde_json::Value;
+
+use crate::json::JsonSerde;
+use crate::model::{Context, Iri, Object};
+
+pub mod frame;
+
+pub const ACTIVITY_STREAMS_CONTEXT: &str = "https://www.w3.org/ns/activitystreams";
+pub const ACTIVITY_STREAMS_NAMESPACE: &str = "https://www.w3.org/ns/activitystreams#";
+pub const SECURITY_V1_CONTEXT: &str = "https://w3id.org/security/v1";
+pub const SECURITY_NAMESPACE: &str = "https://w3id.org/security#";
+pub const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema#";
+
+// Terms of https://www.w3.org/ns/activitystreams whose values are IRIs.
+const ACTIVITY_STREAMS_ID_TERMS: &[&str] = &[
+    "actor",
+    "anyOf",
+    "attachment",
+    "attributedTo",
+    "audience",
+    "bcc",
+    "bto",
+    "cc",
+    "context",
+    "current",
+    "describes",
+    "endpoints",
+    "first",
+    "followers",
+    "following",
+    "formerType",
+    "generator",
+    "href",
+    "icon",
+    "image",
+    "inReplyTo",
+    "instrument",
+    "items",
+    "last",
+    "liked",
+    "likes",
+    "location",
+    "next",
+    "oauthAuthorizationEndpoint",
+    "oauthTokenEndpoint",
+    "object",
+    "oneOf",
+    "orderedItems",
+    "origin",
+    "outbox",
+    "partOf",
+    "prev",
+    "preview",
+    "provideClientKey",
+    "proxyUrl",
+    "relationship",
+    "replies",
+    "result",
+    "shares",
+    "sharedInbox",
+    "signClientKey",
+    "source",
+    "streams",
+    "subject",
+    "tag",
+    "target",
+    "to",
+    "uploadMedia",
+    "url",
+];
+
+// Terms of https://www.w3.org/ns/activitystreams whose values are literals.
+const ACTIVITY_STREAMS_LITERAL_TERMS: &[(&str, Option<&str>)] = &[
+    ("accuracy", Some("xsd:float")),
+    ("altitude", Some("xsd:float")),
+    ("closed", None),
+    ("content", None),
+    ("deleted", Some("xsd:dateTime")),
+    ("duration", Some("xsd:duration")),
+    ("endTime", Some("xsd:dateTime")),
+    ("height", Some("xsd:nonNegativeInteger")),
+    ("hreflang", None),
+    ("latitude", Some("xsd:float")),
+    ("longitude", Some("xsd:float")),
+    ("mediaType", None),
+    ("name", None),
+    ("preferredUsername", None),
+    ("published", Some("xsd:dateTime")),
+    ("radius", Some("xsd:float")),
+    ("rel", None),
+    ("startIndex", Some("xsd:nonNegativeInteger")),
+    ("startTime", Some("xsd:dateTime")),
+    ("summary", None),
+    ("totalItems", Some("xsd:nonNegativeInteger")),
+    ("units", None),
+    ("updated", Some("xsd:dateTime")),
+    ("width", Some("xsd:nonNegativeInteger")),
+];
+
+const ACTIVITY_STREAMS_TYPES: &[&str] = &[
+    "Accept",
+    "Activity",
+    "Add",
+    "Announce",
+    "Application",
+    "Arrive",
+    "Article",
+    "Audio",
+    "Block",
+    "Collection",
+    "CollectionPage",
+    "Create",
+    "Delete",
+    "Dislike",
+    "Document",
+    "Event",
+    "Flag",
+    "Follow",
+    "Group",
+    "Ignore",
+    "Image",
+    "IntransitiveActivity",
+    "Invite",
+    "Join",
+    "Leave",
+    "Like",
+    "Link",
+    "Listen",
+    "Mention",
+    "Move",
+    "Note",
+    "Object",
+    "Offer",
+    "OrderedCollection",
+    "OrderedCollectionPage",
+    "Organization",
+    "Page",
+    "Person",
+    "Place",
+    "Profile",
+    "Question",
+    "Read",
+    "Reject",
+    "Relationship",
+    "Remove",
+    "Service",
+    "TentativeAccept",
+    "TentativeReject",
+    "Tombstone",
+    "Travel",
+    "Undo",
+    "Update",
+    "Video",
+    "View",
+];
+
+const SECURITY_V1_TERMS: &[(&str, Option<&str>)] = &[
+    ("CryptographicKey", None),
+    ("Key", None),
+    ("expires", Some("xsd:dateTime")),
+    ("nonce", None),
+    ("owner", Some("@id")),
+    ("privateKeyPem", None),
+    ("publicKey", Some("@id")),
+    ("publicKeyPem", None),
+    ("revoked", Some("xsd:dateTime")),
+    ("signature", None),
+    ("signatureAlgorithm", None),
+    ("signatureValue", None),
+];
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TermDefinition {
+    pub id: String,
+    pub typ: Option<String>,
+    pub container: Option<String>,
+}
+
+/**
+ * A simplified active context. Remote contexts are not fetched; the
+ * ActivityStreams and security contexts are built in and other remote
+ * contexts are ignored.
+ *
+ * Reference: https://www.w3.org/TR/json-ld11-api/#context-processing-algorithm
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ActiveContext {
+    terms: HashMap<String, TermDefinition>,
+    language: Option<String>,
+    vocab: Option<String>,
+    base: Option<String>,
+}
+
+impl ActiveContext {
+    pub fn new(context: &Context) -> Self {
+        let mut dest = Self::default();
+        dest.process(context);
+        dest
+    }
+
+    pub fn process(&mut self, context: &Context) {
+        match context {
+            Context::Single(Iri::Direct(iri)) => self.process_remote(iri),
+            Context::Single(Iri::TypeCoercion { .. }) => {}
+            Context::Mix(items) => {
+                for item in items {
+                    self.process(item);
+                }
+            }
+            Context::TermDefs(defs) => {
+                if let Some(language) = &defs.language {
+                    self.language = language.clone();
+                }
+                if let Some(vocab) = &defs.vocab {
+                    self.vocab = vocab.clone();
+                }
+                if let Some(base) = &defs.base {
+                    self.base = base.clone();
+                }
+                for (term, iri) in &defs.terms {
+                    self.terms.insert(term.clone(), TermDefinition::from(iri));
+                }
+            }
+        }
+    }
+
+    fn process_remote(&mut self, iri: &str) {
+        match iri.trim_end_matches('#') {
+            ACTIVITY_STREAMS_CONTEXT => {
+                self.define("as", ACTIVITY_STREAMS_NAMESPACE, None);
+                self.define("ldp", "http://www.w3.org/ns/ldp#", None);
+                self.define("xsd", XSD_NAMESPACE, None);
+                self.define("id", "@id", None);
+                self.define("type", "@type", None);
+                for term in ACTIVITY_STREAMS_TYPES {
+                    self.define(term, &format!("as:{}", term), None);
+                }
+                for term in ACTIVITY_STREAMS_ID_TERMS {
+                    self.define(term, &format!("as:{}", term), Some("@id"));
+                }
+                for (term, typ) in ACTIVITY_STREAMS_LITERAL_TERMS {
+                    self.define(term, &format!("as:{}", term), *typ);
+                }
+                self.define("inbox", "ldp:inbox", Some("@id"));
+                for term in ["content", "name", "summary"] {
+                    self.terms.insert(
+                        format!("{}Map", term),
+                        TermDefinition {
+                            id: format!("as:{}", term),
+                            typ: None,
+                            container: Some("@language".to_string()),
+                        },
+                    );
+                }
+            }
+            SECURITY_V1_CONTEXT => {
+                self.define("sec", SECURITY_NAMESPACE, None);
+                self.define("xsd", XSD_NAMESPACE, None);
+                for (term, typ) in SECURITY_V1_TERMS {
+                    self.define(term, &format!("sec:{}", term), *typ);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn define(&mut self, term: &str, id: &str, typ: Option<&str>) {
+        self.terms.insert(
+            term.to_string(),
+            TermDefinition {
+                id: id.to_string(),
+                typ: typ.map(str::to_string),
+                container: None,
+            },
+        );
+    }
+
+    pub fn term_definition(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term)
+    }
+
+    /**
+     * The default language of strings, given by `@language`.
+     */
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    /**
+     * The base IRI of relative IRIs, given by `@base`.
+     */
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_deref()
+    }
+
+    /**
+     * Expands a term, a compact IRI or an absolute IRI. Keywords expand to
+     * themselves, and other words are prefixed with `@vocab` if any.
+     */
+    pub fn expand_iri(&self, value: &str) -> Option<String> {
+        self.expand_iri_with_depth(value, 0)
+    }
+
+    fn expand_iri_with_depth(&self, value: &str, depth: usize) -> Option<String> {
+        // Guard against cyclic definitions.
+        if depth > 8 {
+            return None;
+        }
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+        if let Some(def) = self.terms.get(value) {
+            if def.id == value {
+                return Some(value.to_string());
+            }
+            return self.expand_iri_with_depth(&def.id, depth + 1);
+        }
+        match value.split_once(':') {
+            None => self
+                .vocab
+                .as_ref()
+                .and_then(|vocab| self.expand_iri_with_depth(vocab, depth + 1))
+                .map(|vocab| format!("{}{}", vocab, value)),
+            Some((_, suffix)) if suffix.starts_with("//") => Some(value.to_string()),
+            Some((prefix, suffix)) => match self.terms.get(prefix) {
+                None => Some(value.to_string()),
+                Some(def) => self
+                    .expand_iri_with_depth(&def.id, depth + 1)
+                    .map(|prefix| format!("{}{}", prefix, suffix)),
+            },
+        }
+    }
+}
+
+impl From<&Iri> for TermDefinition {
+    fn from(value: &Iri) -> Self {
+        match value {
+            Iri::Direct(id) => Self {
+                id: id.clone(),
+                typ: None,
+                container: None,
+            },
+            Iri::TypeCoercion {
+                id, typ, container, ..
+            } => Self {
+                id: id.clone(),
+                typ: typ.clone(),
+                container: container.clone(),
+            },
+        }
+    }
+}
+
+impl Object {
+    pub fn active_context(&self) -> ActiveContext {
+        match &self.schema_context {
+            None => ActiveContext::new(&Context::pure_ap()),
+            Some(context) => ActiveContext::new(context),
+        }
+    }
+
+    /**
+     * Looks up a property by its expanded IRI, including properties this
+     * model does not know.
+     */
+    pub fn get_property(&self, iri: &str) -> Result<Option<Value>, Box<dyn Error>> {
+        let context = self.active_context();
+        let properties = match self.to_value()? {
+            Value::Object(properties) => properties,
+            _ => return Ok(None),
+        };
+        for (key, value) in properties {
+            if context.expand_iri(&key).as_deref() == Some(iri) {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
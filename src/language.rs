@@ -0,0 +1,127 @@
+use crate::model::{Map, Object};
+
+/**
+ * The language ranges of an `Accept-Language` header, the most preferred
+ * first. Ranges with `q=0`, which are not acceptable, are dropped, and
+ * ranges of the same weight keep their order.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc9110#section-12.5.4
+ */
+pub fn preferred_languages(accept_language: &str) -> Vec<&str> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|item| {
+            let mut params = item.split(';');
+            let range = params.next()?.trim();
+            let weight = params
+                .find_map(|param| {
+                    let (name, value) = param.split_once('=')?;
+                    (name.trim() == "q").then(|| value.trim().parse::<f32>().ok())
+                })
+                .unwrap_or(Some(1.0))?;
+            (!range.is_empty() && weight > 0.0).then_some((range, weight))
+        })
+        .collect();
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/**
+ * Picks the value for the most preferred language of `accept_language`
+ * among a language map and a default value in `default_language`, if
+ * known. A range matches its own tag first, then the tags it is a prefix
+ * of, and is retried without its last subtag otherwise, so that `en` and
+ * `en-GB` both match `en-US`. Without any match, the default value is
+ * taken, then the first value of the map.
+ */
+pub fn select<'a>(
+    accept_language: &str,
+    default: Option<&'a str>,
+    default_language: Option<&str>,
+    map: &'a Map<String, String>,
+) -> Option<&'a str> {
+    let candidates: Vec<(&str, &str)> = default
+        .zip(default_language)
+        .map(|(value, language)| (language, value))
+        .into_iter()
+        .chain(
+            map.iter()
+                .map(|(language, value)| (language.as_str(), value.as_str())),
+        )
+        .collect();
+    for range in preferred_languages(accept_language) {
+        // Any language is acceptable, so the fallback is as good as any.
+        if range == "*" {
+            break;
+        }
+        let mut range = range;
+        loop {
+            let found = candidates
+                .iter()
+                .find(|(language, _)| language.eq_ignore_ascii_case(range))
+                .or_else(|| {
+                    candidates
+                        .iter()
+                        .find(|(language, _)| is_prefix_of(range, language))
+                });
+            if let Some((_, value)) = found {
+                return Some(value);
+            }
+            match range.rsplit_once('-') {
+                Some((rest, _)) => range = rest,
+                None => break,
+            }
+        }
+    }
+    default.or_else(|| map.values().next().map(String::as_str))
+}
+
+// Whether the tag is the range followed by more subtags.
+fn is_prefix_of(range: &str, tag: &str) -> bool {
+    tag.len() > range.len()
+        && tag.as_bytes()[range.len()] == b'-'
+        && tag[..range.len()].eq_ignore_ascii_case(range)
+}
+
+impl Object {
+    /**
+     * `content` in the language `accept_language` prefers, from
+     * `contentMap`. The default `content` is in the `@language` of the
+     * context.
+     */
+    pub fn content_for(&self, accept_language: &str) -> Option<&str> {
+        let items = &self.object_items;
+        self.select_for(accept_language, &items.content, &items.content_map)
+    }
+
+    /**
+     * `name` in the language `accept_language` prefers, as `content_for`.
+     */
+    pub fn name_for(&self, accept_language: &str) -> Option<&str> {
+        let items = &self.object_items;
+        self.select_for(accept_language, &items.name, &items.name_map)
+    }
+
+    /**
+     * `summary` in the language `accept_language` prefers, as `content_for`.
+     */
+    pub fn summary_for(&self, accept_language: &str) -> Option<&str> {
+        let items = &self.object_items;
+        self.select_for(accept_language, &items.summary, &items.summary_map)
+    }
+
+    fn select_for<'a>(
+        &self,
+        accept_language: &str,
+        default: &'a [String],
+        map: &'a Map<String, String>,
+    ) -> Option<&'a str> {
+        let context = self.active_context();
+        select(
+            accept_language,
+            default.first().map(String::as_str),
+            context.language(),
+            map,
+        )
+    }
+}
@@ -1,2 +1,54 @@
+pub mod archive;
+pub mod attachment;
+pub mod audit;
+pub mod authorization;
+pub mod cache;
+pub mod char_count;
+pub mod clock;
+pub mod conformance;
+pub mod delivery;
+pub mod emoji;
+pub mod federation;
+pub mod feed;
+pub mod fetch;
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+#[cfg(feature = "place")]
+pub mod geojson;
+pub mod graph;
+pub mod hashtag;
+mod html;
+pub mod id;
+#[cfg(feature = "security")]
+pub mod instance;
+pub mod interop;
 pub mod json;
+pub mod jsonld;
+pub mod language;
+pub mod mention;
+pub mod mf2;
+pub mod migration;
 pub mod model;
+pub mod nodeinfo;
+pub mod observe;
+pub mod opengraph;
+mod origin;
+pub mod outbox;
+pub mod paging;
+pub mod preflight;
+pub mod projection;
+pub mod quote;
+pub mod rdf;
+pub mod relay;
+pub mod resolver;
+pub mod routes;
+pub mod side_effect;
+pub mod signature;
+pub mod store;
+pub mod suspension;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod visit;
+pub mod webfinger;
+#[cfg(feature = "webmention")]
+pub mod webmention;
@@ -0,0 +1,214 @@
+use serde_json::Value;
+
+use crate::html::words_of;
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::webfinger::WebFinger;
+
+/**
+ * An account mentioned in text as `@user@host`, or as `@user` for an
+ * account of the local server.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Acct<'a> {
+    pub user: &'a str,
+    pub host: Option<&'a str>,
+}
+
+/**
+ * A mention of an actor, which Mastodon sends as a `Mention` tag named by
+ * the account and linking to the actor.
+ *
+ * Reference: https://docs.joinmastodon.org/spec/activitypub/#Mention
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Mention {
+    // With `@`, e.g. `@alice@example.com`.
+    pub name: String,
+    pub href: String,
+}
+
+impl Mention {
+    pub fn from_tag(tag: &Object) -> Option<Self> {
+        if !tag.typ.iter().any(|typ| typ == "Mention") {
+            return None;
+        }
+        let href = match tag.json_items.unknown_properties.get("href") {
+            Some(Value::String(href)) => href.clone(),
+            _ => tag.id.clone()?,
+        };
+        Some(Self {
+            name: tag.object_items.name.first()?.clone(),
+            href,
+        })
+    }
+
+    pub fn to_tag(&self) -> Object {
+        let mut tag = Object::new(None, vec!["Mention".to_string()]);
+        tag.schema_context = None;
+        tag.object_items.name = vec![self.name.clone()];
+        tag.json_items
+            .unknown_properties
+            .insert("href".to_string(), Value::String(self.href.clone()));
+        tag
+    }
+}
+
+/**
+ * The accounts mentioned in plain text, without duplicates. As in
+ * Mastodon, a user is ASCII letters, digits, `_`, and `.` or `-` between
+ * them, and the `@` is not in a word or a URL.
+ */
+pub fn mentions(text: &str) -> Vec<Acct<'_>> {
+    let mut dest: Vec<Acct> = vec![];
+    let mut start = 0;
+    while let Some(offset) = text[start..].find('@') {
+        let open = start + offset;
+        match acct_at(text, open) {
+            Some((acct, end)) => {
+                if !dest.iter().any(|other| same_acct(other, &acct)) {
+                    dest.push(acct);
+                }
+                start = end;
+            }
+            None => start = open + 1,
+        }
+    }
+    dest
+}
+
+/**
+ * The account mentioned by the `@` at `open`, and the end of the mention.
+ */
+pub(crate) fn acct_at(text: &str, open: usize) -> Option<(Acct<'_>, usize)> {
+    let after_boundary = text[..open]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !c.is_alphanumeric() && c != '_' && c != '/');
+    if !after_boundary {
+        return None;
+    }
+    let user = run(&text[open + 1..], |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+    });
+    if user.is_empty() {
+        return None;
+    }
+    let mut end = open + 1 + user.len();
+    let host = text[end..]
+        .strip_prefix('@')
+        .map(|rest| {
+            run(rest, |c| {
+                c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+            })
+        })
+        .filter(|host| !host.is_empty());
+    if let Some(host) = host {
+        end += 1 + host.len();
+    }
+    Some((Acct { user, host }, end))
+}
+
+// The longest prefix of the chars, without `.` or `-` at the end.
+fn run(text: &str, is_part: impl Fn(char) -> bool) -> &str {
+    let len: usize = text
+        .chars()
+        .take_while(|c| is_part(*c))
+        .map(char::len_utf8)
+        .sum();
+    text[..len].trim_end_matches(['.', '-'])
+}
+
+fn same_acct(a: &Acct, b: &Acct) -> bool {
+    a.user.eq_ignore_ascii_case(b.user)
+        && match (a.host, b.host) {
+            (Some(a), Some(b)) => a.to_lowercase() == b.to_lowercase(),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+}
+
+/**
+ * The mentions of the object, from its `Mention` tags.
+ */
+pub fn tagged_mentions(object: &Object) -> Vec<Mention> {
+    object
+        .object_items
+        .tag
+        .iter()
+        .filter_map(|tag| match tag {
+            ObjectOrLink::Object(tag) => Mention::from_tag(tag),
+            ObjectOrLink::Link(link) if link.typ.iter().any(|typ| typ == "Mention") => {
+                Some(Mention {
+                    name: link.name.first()?.clone(),
+                    href: link.href.clone(),
+                })
+            }
+            ObjectOrLink::Link(_) => None,
+        })
+        .collect()
+}
+
+/**
+ * Adds `Mention` tags for the accounts mentioned in the `content` of a
+ * composed object, resolving them by `webfinger`; accounts without a host
+ * are of `local_host`. The mentioned actors are also addressed: in `to` if
+ * it is empty, i.e. the object is a direct message, and in `cc` otherwise,
+ * as Mastodon does. Actors already mentioned or addressed are left as they
+ * are.
+ *
+ * Returns the accounts which could not be resolved, as `user@host`. They
+ * stay plain text.
+ */
+pub fn add_mention_tags<W: WebFinger + ?Sized>(
+    object: &mut Object,
+    local_host: &str,
+    webfinger: &W,
+) -> Vec<String> {
+    let items = &object.object_items;
+    let texts: Vec<String> = items
+        .content
+        .iter()
+        .chain(items.content_map.values())
+        .map(|content| words_of(content))
+        .collect();
+    let mut known: Vec<String> = tagged_mentions(object)
+        .into_iter()
+        .map(|mention| mention.href)
+        .collect();
+    let mut found: Vec<Mention> = vec![];
+    let mut unresolved: Vec<String> = vec![];
+    for acct in texts.iter().flat_map(|text| mentions(text)) {
+        let host = acct.host.unwrap_or(local_host);
+        match webfinger.lookup(acct.user, host) {
+            Ok(href) if !known.contains(&href) => {
+                known.push(href.clone());
+                found.push(Mention {
+                    name: format!("@{}@{}", acct.user, host),
+                    href,
+                });
+            }
+            Ok(_) => {}
+            Err(_) => unresolved.push(format!("{}@{}", acct.user, host)),
+        }
+    }
+    let direct = object.object_items.to.is_empty();
+    for mention in found {
+        let items = &mut object.object_items;
+        let addressed = items
+            .to
+            .iter()
+            .chain(&items.cc)
+            .chain(&items.bto)
+            .chain(&items.bcc)
+            .any(|audience| audience.id() == Some(&mention.href));
+        if !addressed {
+            let audience = ObjectOrLink::Link(Link::from(mention.href.as_str()));
+            if direct {
+                items.to.push(audience);
+            } else {
+                items.cc.push(audience);
+            }
+        }
+        items.tag.push(ObjectOrLink::Object(mention.to_tag()));
+    }
+    unresolved
+}
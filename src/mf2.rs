@@ -0,0 +1,284 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::html::{self, strip_tags};
+use crate::model::{Link, Map, Object, ObjectOrLink};
+
+/**
+ * A microformats2 item in its canonical JSON representation. Can be read and
+ * written with `json::SerdeJsonValue`.
+ *
+ * Reference: https://microformats.org/wiki/microformats2-parsing
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct Mf2Item {
+    #[serde(rename = "type")]
+    pub typ: Vec<String>,
+    #[serde(default)]
+    pub properties: Map<String, Vec<Mf2Value>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Mf2Value {
+    Text(String),
+    Html { html: String, value: String },
+    Item(Mf2Item),
+}
+
+impl Mf2Item {
+    pub fn new(typ: &str) -> Self {
+        Self {
+            typ: vec![typ.to_string()],
+            properties: Map::new(),
+        }
+    }
+
+    pub fn push(&mut self, property: &str, value: Mf2Value) {
+        self.properties
+            .entry(property.to_string())
+            .or_default()
+            .push(value);
+    }
+
+    pub fn texts(&self, property: &str) -> Vec<&str> {
+        self.properties
+            .get(property)
+            .map(|values| values.iter().filter_map(Mf2Value::as_text).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Mf2Value {
+    /**
+     * Plain text of the value. For embedded items, this is their first `url`
+     * or `name`, as the parsing specification suggests for consumers.
+     */
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Html { value, .. } => Some(value),
+            Self::Item(item) => item
+                .texts("url")
+                .first()
+                .or(item.texts("name").first())
+                .copied(),
+        }
+    }
+}
+
+/**
+ * Converts a Note or an Article into an h-entry.
+ *
+ * Reference: https://microformats.org/wiki/h-entry
+ */
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(id = object.id.as_deref()))
+)]
+pub fn to_h_entry(object: &Object) -> Mf2Item {
+    let items = &object.object_items;
+    let mut entry = Mf2Item::new("h-entry");
+
+    if let Some(id) = &object.id {
+        entry.push("uid", Mf2Value::Text(id.clone()));
+    }
+    match (&items.url, &object.id) {
+        (Some(url), _) => entry.push("url", Mf2Value::Text(url.href.clone())),
+        (None, Some(id)) => entry.push("url", Mf2Value::Text(id.clone())),
+        (None, None) => {}
+    }
+    for name in items.name.iter() {
+        entry.push("name", Mf2Value::Text(name.clone()));
+    }
+    for summary in items.summary.iter() {
+        entry.push("summary", Mf2Value::Text(strip_tags(summary)));
+    }
+    for content in items
+        .content
+        .iter()
+        .chain(items.content_map.values())
+        .take(1)
+    {
+        entry.push(
+            "content",
+            Mf2Value::Html {
+                html: content.clone(),
+                value: strip_tags(content),
+            },
+        );
+    }
+    if let Some(published) = items.published {
+        entry.push("published", Mf2Value::Text(published.to_rfc3339()));
+    }
+    if let Some(updated) = items.updated {
+        entry.push("updated", Mf2Value::Text(updated.to_rfc3339()));
+    }
+    for author in items.attributed_to.iter() {
+        entry.push("author", author_to_mf2(author));
+    }
+    for in_reply_to in items.in_reply_to.iter() {
+        if let Some(iri) = iri_of(in_reply_to) {
+            entry.push("in-reply-to", Mf2Value::Text(iri));
+        }
+    }
+    for tag in items.tag.iter() {
+        if let ObjectOrLink::Object(tag) = tag {
+            if tag.typ.iter().any(|typ| typ == "Hashtag") {
+                for name in tag.object_items.name.iter() {
+                    entry.push(
+                        "category",
+                        Mf2Value::Text(name.trim_start_matches('#').to_string()),
+                    );
+                }
+            }
+        }
+    }
+    for attachment in items.attachment.iter() {
+        let (url, media_type) = match attachment {
+            ObjectOrLink::Link(link) => (Some(&link.href), link.media_type.first()),
+            ObjectOrLink::Object(object) => (
+                object.object_items.url.as_ref().map(|url| &url.href),
+                object.object_items.media_type.first(),
+            ),
+        };
+        let property = match media_type.map(String::as_str) {
+            Some(media_type) if media_type.starts_with("video/") => "video",
+            Some(media_type) if media_type.starts_with("audio/") => "audio",
+            _ => "photo",
+        };
+        if let Some(url) = url {
+            entry.push(property, Mf2Value::Text(url.clone()));
+        }
+    }
+
+    entry
+}
+
+/**
+ * Converts an h-entry into an Article if it has a name distinct from its
+ * content, and into a Note otherwise.
+ *
+ * Reference: https://microformats.org/wiki/h-entry
+ */
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub fn from_h_entry(entry: &Mf2Item) -> Result<Object, Box<dyn Error>> {
+    if !entry.typ.iter().any(|typ| typ == "h-entry") {
+        return Err(format!("not an h-entry: {:?}", entry.typ).into());
+    }
+
+    let content = entry
+        .properties
+        .get("content")
+        .and_then(|values| values.first());
+    let name = entry.texts("name").first().map(|name| name.to_string());
+    let is_article = match (&name, content.and_then(Mf2Value::as_text)) {
+        (None, _) => false,
+        (Some(name), Some(content)) => name.trim() != content.trim(),
+        (Some(_), None) => true,
+    };
+
+    let uid = entry.texts("uid").first().map(|uid| uid.to_string());
+    let url = entry.texts("url").first().map(|url| url.to_string());
+    let mut object = Object::new(
+        uid.or(url.clone()),
+        vec![if is_article { "Article" } else { "Note" }.to_string()],
+    );
+    let items = &mut object.object_items;
+
+    items.url = url.map(Link::from);
+    if is_article {
+        items.name = name.into_iter().collect();
+    }
+    items.summary = entry
+        .texts("summary")
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    items.content = match content {
+        None => vec![],
+        Some(Mf2Value::Html { html, .. }) => vec![html.clone()],
+        Some(content) => content.as_text().map(html::escape).into_iter().collect(),
+    };
+    items.published = parse_date(entry.texts("published").first())?;
+    items.updated = parse_date(entry.texts("updated").first())?;
+    items.attributed_to = entry
+        .texts("author")
+        .into_iter()
+        .map(|author| ObjectOrLink::Link(Link::from(author)))
+        .collect();
+    items.in_reply_to = entry
+        .texts("in-reply-to")
+        .into_iter()
+        .map(|iri| ObjectOrLink::Link(Link::from(iri)))
+        .collect();
+    items.tag = entry
+        .texts("category")
+        .into_iter()
+        .map(|category| {
+            let mut tag = Object::new(None, vec!["Hashtag".to_string()]);
+            tag.schema_context = None;
+            tag.object_items.name = vec![format!("#{}", category.trim_start_matches('#'))];
+            ObjectOrLink::Object(tag)
+        })
+        .collect();
+    for (property, typ) in [("photo", "Image"), ("video", "Video"), ("audio", "Audio")] {
+        for url in entry.texts(property) {
+            let mut attachment = Object::new(None, vec![typ.to_string()]);
+            attachment.schema_context = None;
+            attachment.object_items.url = Some(Link::from(url));
+            items.attachment.push(ObjectOrLink::Object(attachment));
+        }
+    }
+
+    Ok(object)
+}
+
+fn author_to_mf2(author: &ObjectOrLink) -> Mf2Value {
+    match author {
+        ObjectOrLink::Link(link) => Mf2Value::Text(link.href.clone()),
+        ObjectOrLink::Object(actor) => {
+            let mut card = Mf2Item::new("h-card");
+            for name in actor.object_items.name.iter() {
+                card.push("name", Mf2Value::Text(name.clone()));
+            }
+            match (&actor.object_items.url, &actor.id) {
+                (Some(url), _) => card.push("url", Mf2Value::Text(url.href.clone())),
+                (None, Some(id)) => card.push("url", Mf2Value::Text(id.clone())),
+                (None, None) => {}
+            }
+            for icon in actor.object_items.icon.iter().take(1) {
+                if let Some(url) = iri_of(icon) {
+                    card.push("photo", Mf2Value::Text(url));
+                }
+            }
+            Mf2Value::Item(card)
+        }
+    }
+}
+
+fn iri_of(value: &ObjectOrLink) -> Option<String> {
+    match value {
+        ObjectOrLink::Link(link) => Some(link.href.clone()),
+        ObjectOrLink::Object(object) => object
+            .object_items
+            .url
+            .as_ref()
+            .map(|url| url.href.clone())
+            .or(object.id.clone()),
+    }
+}
+
+fn parse_date(value: Option<&&str>) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+    match value {
+        None => Ok(None),
+        Some(value) => Ok(Some(
+            DateTime::parse_from_rfc3339(value)?.with_timezone(&Utc),
+        )),
+    }
+}
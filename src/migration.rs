@@ -0,0 +1,142 @@
+use std::error::Error;
+
+use crate::id::IdMinter;
+use crate::model::{Link, Object, ObjectOrLink};
+
+pub fn export_account_iris(collection: &Object) -> Vec<String> {
+    let mut dest = vec![];
+    collect_item_iris(collection, &mut dest);
+    dest
+}
+
+fn collect_item_iris(collection: &Object, dest: &mut Vec<String>) {
+    for item in collection
+        .collection_items
+        .items
+        .iter()
+        .chain(collection.ordered_collection_items.ordered_items.iter())
+    {
+        if let Some(iri) = iri_of(item) {
+            dest.push(iri.to_string());
+        }
+    }
+
+    // Only embedded pages can be followed without fetching.
+    for page in [
+        collection.collection_items.first.as_deref(),
+        collection.collection_page_items.next.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let ObjectOrLink::Object(page) = page {
+            collect_item_iris(page, dest);
+        }
+    }
+}
+
+pub fn set_moved_to(old_actor: &mut Object, new_actor_iri: &str) {
+    old_actor.activity_streams_ext_items.moved_to = Some(new_actor_iri.to_string());
+}
+
+pub fn add_also_known_as(new_actor: &mut Object, old_actor_iri: &str) {
+    let also_known_as = &mut new_actor.activity_streams_ext_items.also_known_as;
+    if !also_known_as.iter().any(|item| item == old_actor_iri) {
+        also_known_as.push(old_actor_iri.to_string());
+    }
+}
+
+/**
+ * Reference: https://docs.joinmastodon.org/spec/activitypub/#Move
+ */
+pub fn move_activity(
+    id: Option<String>,
+    old_actor: &Object,
+    new_actor: &Object,
+) -> Result<Object, Box<dyn Error>> {
+    let old_actor_iri = old_actor.id.as_ref().ok_or("old actor has no id")?;
+    let new_actor_iri = new_actor.id.as_ref().ok_or("new actor has no id")?;
+    if !new_actor
+        .activity_streams_ext_items
+        .also_known_as
+        .contains(old_actor_iri)
+    {
+        return Err("new actor does not list the old actor in alsoKnownAs".into());
+    }
+
+    let mut activity = Object::new_activity(
+        id,
+        vec!["Move".to_string()],
+        vec![ObjectOrLink::Link(Link::from(old_actor_iri.as_str()))],
+        vec![ObjectOrLink::Link(Link::from(old_actor_iri.as_str()))],
+    );
+    activity.activity_items.target = vec![ObjectOrLink::Link(Link::from(new_actor_iri.as_str()))];
+    if let Some(actor_items) = &old_actor.actor_items {
+        activity.object_items.to = vec![ObjectOrLink::Link(Link::from(
+            actor_items.followers.as_str(),
+        ))];
+    }
+    Ok(activity)
+}
+
+pub fn verify_move(activity: &Object, new_actor: &Object) -> Result<(), Box<dyn Error>> {
+    if !activity.typ.iter().any(|typ| typ == "Move") {
+        return Err("activity is not a Move".into());
+    }
+    let old_actor_iri = single_iri(&activity.activity_items.object, "object")?;
+    if single_iri(&activity.activity_items.actor, "actor")? != old_actor_iri {
+        return Err("Move must be sent by the account being moved".into());
+    }
+    let new_actor_iri = single_iri(&activity.activity_items.target, "target")?;
+    if new_actor.id.as_deref() != Some(new_actor_iri) {
+        return Err("target of Move does not match the new actor".into());
+    }
+    if !new_actor
+        .activity_streams_ext_items
+        .also_known_as
+        .iter()
+        .any(|item| item == old_actor_iri)
+    {
+        return Err("new actor does not list the old actor in alsoKnownAs".into());
+    }
+    Ok(())
+}
+
+pub fn refollow_activities(
+    activity: &Object,
+    new_actor: &Object,
+    local_followers: &[String],
+    mut minter: impl IdMinter,
+) -> Result<Vec<Object>, Box<dyn Error>> {
+    verify_move(activity, new_actor)?;
+    let new_actor_iri = new_actor.id.as_ref().ok_or("new actor has no id")?;
+
+    Ok(local_followers
+        .iter()
+        .map(|follower| {
+            let mut follow = Object::new_activity(
+                None,
+                vec!["Follow".to_string()],
+                vec![ObjectOrLink::Link(Link::from(follower.as_str()))],
+                vec![ObjectOrLink::Link(Link::from(new_actor_iri.as_str()))],
+            );
+            follow.object_items.to = vec![ObjectOrLink::Link(Link::from(new_actor_iri.as_str()))];
+            follow.id = Some(minter.mint(&follow));
+            follow
+        })
+        .collect())
+}
+
+fn iri_of(item: &ObjectOrLink) -> Option<&str> {
+    match item {
+        ObjectOrLink::Link(link) => Some(&link.href),
+        ObjectOrLink::Object(object) => object.id.as_deref(),
+    }
+}
+
+fn single_iri<'a>(items: &'a [ObjectOrLink], property: &str) -> Result<&'a str, Box<dyn Error>> {
+    match items {
+        [item] => iri_of(item).ok_or_else(|| format!("`{}` has no id", property).into()),
+        _ => Err(format!("`{}` must have exactly one value", property).into()),
+    }
+}
@@ -1,16 +1,38 @@
-use std::collections::HashMap;
+use std::error::Error;
 
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
+// The same map type regardless of features, so that enabling
+// `preserve_order` anywhere in a dependency graph cannot break other crates.
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
+
 /**
  * Schema: https://www.w3.org/TR/json-ld/#the-context
  */
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Context {
     Single(Iri),
     Mix(Vec<Context>),
-    TermDefs(HashMap<String, Iri>),
+    TermDefs(ContextDefinition),
+}
+
+/**
+ * A context definition with the `@language`, `@vocab` and `@base` keywords,
+ * which are `Some(None)` if given as `null` to reset them. `@protected`
+ * applies to all the terms.
+ *
+ * Reference: https://www.w3.org/TR/json-ld11/#context-definitions
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ContextDefinition {
+    pub language: Option<Option<String>>,
+    pub vocab: Option<Option<String>>,
+    pub base: Option<Option<String>>,
+    pub protected: Option<bool>,
+    pub terms: Map<String, Iri>,
 }
 
 impl Context {
@@ -18,6 +40,40 @@ impl Context {
         Context::from("https://www.w3.org/ns/activitystreams")
     }
 
+    /**
+     * Whether a term definition of the context defines the term. Remote
+     * contexts are not looked into.
+     */
+    pub fn defines(&self, term: &str) -> bool {
+        match self {
+            Context::Single(_) => false,
+            Context::Mix(contexts) => contexts.iter().any(|context| context.defines(term)),
+            Context::TermDefs(definition) => definition.terms.contains_key(term),
+        }
+    }
+
+    /**
+     * Appends a term definition of the terms which the context does not
+     * define yet.
+     */
+    pub fn define_terms<I: IntoIterator<Item = (String, Iri)>>(&mut self, terms: I) {
+        let terms: Map<String, Iri> = terms
+            .into_iter()
+            .filter(|(term, _)| !self.defines(term))
+            .collect();
+        if terms.is_empty() {
+            return;
+        }
+        let defined = Context::TermDefs(ContextDefinition {
+            terms,
+            ..Default::default()
+        });
+        match self {
+            Context::Mix(contexts) => contexts.push(defined),
+            other => *other = Context::Mix(vec![other.clone(), defined]),
+        }
+    }
+
     pub fn object_default() -> Context {
         Context::Mix(vec![
             Context::from("https://www.w3.org/ns/activitystreams"),
@@ -28,6 +84,9 @@ impl Context {
                     Iri::TypeCoercion {
                         id: "as:alsoKnownAs".to_string(),
                         typ: Some("@id".to_string()),
+                        container: None,
+                        protected: None,
+                        context: None,
                     },
                 ),
                 (
@@ -39,6 +98,9 @@ impl Context {
                     Iri::TypeCoercion {
                         id: "as:movedTo".to_string(),
                         typ: Some("@id".to_string()),
+                        container: None,
+                        protected: None,
+                        context: None,
                     },
                 ),
             ]),
@@ -49,6 +111,9 @@ impl Context {
                     Iri::TypeCoercion {
                         id: "toot:devices".to_string(),
                         typ: Some("@id".to_string()),
+                        container: None,
+                        protected: None,
+                        context: None,
                     },
                 ),
                 ("discoverable", Iri::from("toot:discoverable")),
@@ -57,6 +122,9 @@ impl Context {
                     Iri::TypeCoercion {
                         id: "toot:featured".to_string(),
                         typ: Some("@id".to_string()),
+                        container: None,
+                        protected: None,
+                        context: None,
                     },
                 ),
                 (
@@ -64,6 +132,9 @@ impl Context {
                     Iri::TypeCoercion {
                         id: "toot:featuredTags".to_string(),
                         typ: Some("@id".to_string()),
+                        container: None,
+                        protected: None,
+                        context: None,
                     },
                 ),
                 ("suspended", Iri::from("toot:suspended")),
@@ -77,16 +148,32 @@ impl Context {
     }
 }
 
+/**
+ * A term definition, either an IRI or an expanded term definition. Only
+ * the `@type`, `@container`, `@protected` and `@context` keywords are
+ * supported in the expanded form.
+ *
+ * Reference: https://www.w3.org/TR/json-ld11/#expanded-term-definition
+ */
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Iri {
     Direct(String),
-    TypeCoercion { id: String, typ: Option<String> },
+    TypeCoercion {
+        id: String,
+        typ: Option<String>,
+        container: Option<String>,
+        protected: Option<bool>,
+        // A scoped context, used for the values of the term.
+        context: Option<Box<Context>>,
+    },
 }
 
 /**
  * Reference: https://www.w3.org/ns/activitystreams
  */
 #[derive(PartialEq, Debug, Clone)]
+// The `Arbitrary` impl is in `fuzz`, to limit the depth.
 pub struct Object {
     pub schema_context: Option<Context>,
     pub id: Option<String>,
@@ -101,12 +188,18 @@ pub struct Object {
     pub ordered_collection_page_items: OrderedCollectionPageItems,
     pub relationship_items: RelationshipItems,
     pub tombstone_items: TombstoneItems,
+    #[cfg(feature = "question")]
     pub question_items: QuestionItems,
+    #[cfg(feature = "place")]
     pub place_items: PlaceItems,
     pub activity_streams_ext_items: ActivityStreamExtItems,
+    #[cfg(feature = "mastodon")]
     pub mastodon_ext_items: MastodonExtItems,
+    #[cfg(feature = "security")]
     pub security_items: SecurityItems,
     pub property_items: PropertyItems,
+    pub schema_org_items: SchemaOrgItems,
+    pub json_items: JsonItems,
 }
 
 impl Object {
@@ -124,19 +217,128 @@ impl Object {
             ordered_collection_page_items: self.ordered_collection_page_items.clone(),
             relationship_items: self.relationship_items.clone(),
             tombstone_items: self.tombstone_items.clone(),
+            #[cfg(feature = "question")]
             question_items: self.question_items.clone(),
+            #[cfg(feature = "place")]
             place_items: self.place_items.clone(),
             activity_streams_ext_items: self.activity_streams_ext_items.clone(),
+            #[cfg(feature = "mastodon")]
             mastodon_ext_items: self.mastodon_ext_items.clone(),
+            #[cfg(feature = "security")]
             security_items: self.security_items.clone(),
             property_items: self.property_items.clone(),
+            schema_org_items: self.schema_org_items.clone(),
+            json_items: self.json_items.clone(),
+        }
+    }
+
+    pub fn new(id: Option<String>, typ: Vec<String>) -> Self {
+        Self {
+            schema_context: Some(Context::pure_ap()),
+            id,
+            typ,
+            object_items: ObjectItems::empty(),
+            actor_items: None,
+            activity_items: ActivityItems::empty(),
+            collection_items: CollectionItems::empty(),
+            ordered_collection_items: OrderedCollectionItems::empty(),
+            collection_page_items: CollectionPageItems::empty(),
+            ordered_collection_page_items: OrderedCollectionPageItems::empty(),
+            relationship_items: RelationshipItems::empty(),
+            tombstone_items: TombstoneItems::empty(),
+            #[cfg(feature = "question")]
+            question_items: QuestionItems::empty(),
+            #[cfg(feature = "place")]
+            place_items: PlaceItems::empty(),
+            activity_streams_ext_items: ActivityStreamExtItems::empty(),
+            #[cfg(feature = "mastodon")]
+            mastodon_ext_items: MastodonExtItems::empty(),
+            #[cfg(feature = "security")]
+            security_items: SecurityItems::empty(),
+            property_items: PropertyItems::empty(),
+            schema_org_items: SchemaOrgItems::empty(),
+            json_items: JsonItems::empty(),
         }
     }
 
+    pub fn new_actor(id: String, actor_type: ActorType, actor_items: ActorItems) -> Self {
+        Self {
+            actor_items: Some(actor_items),
+            ..Self::new(Some(id), vec![actor_type.to_string()])
+        }
+    }
+
+    /**
+     * The first actor type of the object, if it is an actor.
+     */
+    pub fn actor_type(&self) -> Option<ActorType> {
+        self.typ.iter().find_map(|typ| ActorType::from_type(typ))
+    }
+
+    pub fn is_activity(&self) -> bool {
+        self.typ
+            .iter()
+            .any(|typ| ACTIVITY_TYPES.contains(&typ.as_str()))
+    }
+
+    /**
+     * Applies `modify`, and sets `updated` to `now` if the object changed.
+     * Returns whether it changed.
+     */
+    pub fn modify<F: FnOnce(&mut Object)>(&mut self, now: DateTime<Utc>, modify: F) -> bool {
+        let before = self.clone();
+        modify(self);
+        if *self == before {
+            return false;
+        }
+        self.object_items.updated = Some(now);
+        true
+    }
+
+    /**
+     * Checks that `updated` is not before `published`.
+     */
+    pub fn validate_timestamps(&self) -> Result<(), Box<dyn Error>> {
+        let items = &self.object_items;
+        match (items.published, items.updated) {
+            (Some(published), Some(updated)) if updated < published => Err(format!(
+                "updated {} is before published {}",
+                updated.to_rfc3339(),
+                published.to_rfc3339()
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /**
+     * An `Update` of the modified object by the actor, published at its
+     * `updated` and addressed as the object is.
+     */
+    pub fn new_update(
+        id: Option<String>,
+        actor: &str,
+        object: &Object,
+    ) -> Result<Self, Box<dyn Error>> {
+        object.validate_timestamps()?;
+        let updated = object.object_items.updated.ok_or("object has no updated")?;
+        let mut activity = Self::new_activity(
+            id,
+            vec!["Update".to_string()],
+            vec![ObjectOrLink::Link(Link::from(actor))],
+            vec![ObjectOrLink::Object(object.clone_without_schema_context())],
+        );
+        activity.object_items.published = Some(updated);
+        activity.object_items.to.clone_from(&object.object_items.to);
+        activity.object_items.cc.clone_from(&object.object_items.cc);
+        Ok(activity)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new_collection(
         id: Option<String>,
         typ: Vec<String>,
-        total_items: Option<usize>,
+        total_items: Option<u64>,
         current: Option<Box<ObjectOrLink>>,
         first: Option<Box<ObjectOrLink>>,
         last: Option<Box<ObjectOrLink>>,
@@ -144,12 +346,6 @@ impl Object {
         ordered_items: Vec<ObjectOrLink>,
     ) -> Self {
         Self {
-            schema_context: Some(Context::pure_ap()),
-            id,
-            typ,
-            object_items: ObjectItems::empty(),
-            actor_items: None,
-            activity_items: ActivityItems::empty(),
             collection_items: CollectionItems {
                 total_items,
                 current,
@@ -158,24 +354,85 @@ impl Object {
                 items,
             },
             ordered_collection_items: OrderedCollectionItems { ordered_items },
-            collection_page_items: CollectionPageItems::empty(),
-            ordered_collection_page_items: OrderedCollectionPageItems::empty(),
-            relationship_items: RelationshipItems::empty(),
-            tombstone_items: TombstoneItems::empty(),
-            question_items: QuestionItems::empty(),
-            place_items: PlaceItems::empty(),
-            activity_streams_ext_items: ActivityStreamExtItems::empty(),
-            mastodon_ext_items: MastodonExtItems::empty(),
-            security_items: SecurityItems::empty(),
-            property_items: PropertyItems::empty(),
+            ..Self::new(id, typ)
+        }
+    }
+
+    pub fn new_activity(
+        id: Option<String>,
+        typ: Vec<String>,
+        actor: Vec<ObjectOrLink>,
+        object: Vec<ObjectOrLink>,
+    ) -> Self {
+        Self {
+            activity_items: ActivityItems {
+                actor,
+                object,
+                ..ActivityItems::empty()
+            },
+            ..Self::new(id, typ)
+        }
+    }
+
+    /**
+     * IRIs which the object refers to, including the ones in embedded
+     * objects, without duplicates. `url` is not included, as it is a
+     * representation of the object rather than another object.
+     */
+    pub fn referenced_iris(&self) -> impl Iterator<Item = &str> {
+        let mut dest = vec![];
+        collect_references(self, &mut dest);
+        let mut seen = std::collections::HashSet::new();
+        dest.into_iter()
+            .filter(move |iri| Some(*iri) != self.id.as_deref() && seen.insert(*iri))
+    }
+}
+
+fn collect_references<'a>(object: &'a Object, dest: &mut Vec<&'a str>) {
+    for (_, value) in crate::visit::values(object) {
+        match value {
+            ObjectOrLink::Link(link) => dest.push(&link.href),
+            ObjectOrLink::Object(object) => collect_embedded(object, dest),
         }
     }
+    for (_, object) in crate::visit::objects(object) {
+        collect_embedded(object, dest);
+    }
+
+    if let Some(actor_items) = &object.actor_items {
+        dest.extend([
+            actor_items.inbox.as_str(),
+            actor_items.outbox.as_str(),
+            actor_items.following.as_str(),
+            actor_items.followers.as_str(),
+        ]);
+    }
+    let ext_items = &object.activity_streams_ext_items;
+    dest.extend(ext_items.also_known_as.iter().map(String::as_str));
+    dest.extend(ext_items.moved_to.as_deref());
+    #[cfg(feature = "mastodon")]
+    {
+        let mastodon_ext_items = &object.mastodon_ext_items;
+        dest.extend(mastodon_ext_items.featured.as_deref());
+        dest.extend(mastodon_ext_items.featured_tags.as_deref());
+        dest.extend(mastodon_ext_items.devices.as_deref());
+    }
+    #[cfg(feature = "security")]
+    if let Some(key) = &object.security_items.public_key {
+        dest.push(&key.owner);
+    }
+}
+
+fn collect_embedded<'a>(object: &'a Object, dest: &mut Vec<&'a str>) {
+    dest.extend(object.id.as_deref());
+    collect_references(object, dest);
 }
 
 /**
  * Reference: https://www.w3.org/TR/activitystreams-vocabulary/#dfn-link
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Link {
     pub schema_context: Option<Context>,
     pub id: Option<String>,
@@ -185,20 +442,237 @@ pub struct Link {
     pub height: Option<usize>,
     pub hreflang: Option<String>,
     pub media_type: Vec<String>,
+    pub name: Vec<String>,
     pub rel: Vec<String>,
     pub width: Option<usize>,
 }
 
+impl Link {
+    /**
+     * A link with only `href`, which is written as a bare IRI.
+     */
+    pub fn simple<S: Into<String>>(href: S) -> Self {
+        Self::from(href.into())
+    }
+
+    pub fn builder<S: Into<String>>(href: S) -> LinkBuilder {
+        LinkBuilder {
+            link: Self::simple(href),
+        }
+    }
+
+    pub fn rels(&self) -> Vec<Rel> {
+        self.rel.iter().map(|rel| Rel::from(rel.as_str())).collect()
+    }
+}
+
+/**
+ * Link relation types. Relations which are not registered are kept as
+ * `Other`.
+ *
+ * Reference: https://www.iana.org/assignments/link-relations/link-relations.xhtml
+ */
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Rel {
+    Alternate,
+    Author,
+    Bookmark,
+    Canonical,
+    DescribedBy,
+    Edit,
+    Enclosure,
+    First,
+    Help,
+    Icon,
+    Last,
+    License,
+    Me,
+    Next,
+    NoFollow,
+    NoReferrer,
+    Payment,
+    Prev,
+    Preview,
+    Related,
+    Replies,
+    Search,
+    Self_,
+    Tag,
+    Up,
+    Via,
+    Other(String),
+}
+
+const REGISTERED_RELS: &[(&str, Rel)] = &[
+    ("alternate", Rel::Alternate),
+    ("author", Rel::Author),
+    ("bookmark", Rel::Bookmark),
+    ("canonical", Rel::Canonical),
+    ("describedby", Rel::DescribedBy),
+    ("edit", Rel::Edit),
+    ("enclosure", Rel::Enclosure),
+    ("first", Rel::First),
+    ("help", Rel::Help),
+    ("icon", Rel::Icon),
+    ("last", Rel::Last),
+    ("license", Rel::License),
+    ("me", Rel::Me),
+    ("next", Rel::Next),
+    ("nofollow", Rel::NoFollow),
+    ("noreferrer", Rel::NoReferrer),
+    ("payment", Rel::Payment),
+    ("prev", Rel::Prev),
+    ("preview", Rel::Preview),
+    ("related", Rel::Related),
+    ("replies", Rel::Replies),
+    ("search", Rel::Search),
+    ("self", Rel::Self_),
+    ("tag", Rel::Tag),
+    ("up", Rel::Up),
+    ("via", Rel::Via),
+];
+
+impl Rel {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(rel) => rel,
+            rel => REGISTERED_RELS
+                .iter()
+                .find(|(_, registered)| registered == rel)
+                .map_or("", |(name, _)| name),
+        }
+    }
+
+    /**
+     * Whether the value can be a `rel` of ActivityStreams, which forbids
+     * spaces and commas.
+     *
+     * Reference: https://www.w3.org/TR/activitystreams-vocabulary/#dfn-rel
+     */
+    pub fn is_valid(rel: &str) -> bool {
+        !rel.is_empty() && !rel.contains(|c: char| c.is_ascii_whitespace() || c == ',')
+    }
+}
+
+impl From<&str> for Rel {
+    fn from(value: &str) -> Self {
+        REGISTERED_RELS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(value))
+            .map_or(Self::Other(value.to_string()), |(_, rel)| rel.clone())
+    }
+}
+
+impl From<Rel> for String {
+    fn from(value: Rel) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for Rel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct LinkBuilder {
+    link: Link,
+}
+
+impl LinkBuilder {
+    pub fn rel<S: Into<String>>(mut self, rel: S) -> Self {
+        self.link.rel.push(rel.into());
+        self
+    }
+
+    pub fn media_type<S: Into<String>>(mut self, media_type: S) -> Self {
+        self.link.media_type = vec![media_type.into()];
+        self
+    }
+
+    pub fn dimensions(mut self, width: usize, height: usize) -> Self {
+        self.link.width = Some(width);
+        self.link.height = Some(height);
+        self
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.link.name = vec![name.into()];
+        self
+    }
+
+    pub fn hreflang<S: Into<String>>(mut self, hreflang: S) -> Self {
+        self.link.hreflang = Some(hreflang.into());
+        self
+    }
+
+    pub fn build(self) -> Link {
+        self.link
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ObjectOrLink {
     Link(Link),
     Object(Object),
 }
 
+impl ObjectOrLink {
+    /**
+     * The IRI of the referenced object, i.e. the `id` of an embedded object or
+     * the `href` of a link.
+     */
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Self::Link(link) => Some(&link.href),
+            Self::Object(object) => object.id.as_deref(),
+        }
+    }
+
+    /**
+     * The `href` of a link, or the `url` of an embedded object.
+     */
+    pub fn href(&self) -> Option<&str> {
+        match self {
+            Self::Link(link) => Some(&link.href),
+            Self::Object(object) => object
+                .object_items
+                .url
+                .as_ref()
+                .map(|url| url.href.as_str()),
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Self::Link(_) => None,
+            Self::Object(object) => Some(object),
+        }
+    }
+
+    pub fn as_link(&self) -> Option<&Link> {
+        match self {
+            Self::Link(link) => Some(link),
+            Self::Object(_) => None,
+        }
+    }
+
+    pub fn into_object(self) -> Option<Object> {
+        match self {
+            Self::Link(_) => None,
+            Self::Object(object) => Some(object),
+        }
+    }
+}
+
 /**
  * Reference: https://www.w3.org/ns/activitystreams#Object
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ObjectItems {
     pub attachment: Vec<ObjectOrLink>,
     pub attributed_to: Vec<ObjectOrLink>,
@@ -221,18 +695,25 @@ pub struct ObjectItems {
     pub to: Vec<ObjectOrLink>,
     pub url: Option<Link>,
     pub content: Vec<String>,
-    pub content_map: HashMap<String, String>,
+    pub content_map: Map<String, String>,
     pub name: Vec<String>,
-    pub name_map: HashMap<String, String>,
+    pub name_map: Map<String, String>,
     // TODO: more strict
     pub duration: Option<String>,
     pub media_type: Vec<String>,
+    pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub published: Option<DateTime<Utc>>,
     pub summary: Vec<String>,
-    pub summary_map: HashMap<String, String>,
+    pub summary_map: Map<String, String>,
     pub updated: Option<DateTime<Utc>>,
     pub describes: Option<Box<Object>>,
+    // https://www.w3.org/TR/activitypub/#likes
+    // Range: Collection | Link
+    pub likes: Option<Box<ObjectOrLink>>,
+    // https://www.w3.org/TR/activitypub/#shares
+    // Range: Collection | Link
+    pub shares: Option<Box<ObjectOrLink>>,
 }
 
 impl ObjectItems {
@@ -256,17 +737,20 @@ impl ObjectItems {
             to: vec![],
             url: None,
             content: vec![],
-            content_map: HashMap::new(),
+            content_map: Map::new(),
             name: vec![],
-            name_map: HashMap::new(),
+            name_map: Map::new(),
             duration: None,
             media_type: vec![],
+            start_time: None,
             end_time: None,
             published: None,
             summary: vec![],
-            summary_map: HashMap::new(),
+            summary_map: Map::new(),
             updated: None,
             describes: None,
+            likes: None,
+            shares: None,
         }
     }
 }
@@ -275,19 +759,145 @@ impl ObjectItems {
  * Reference: https://www.w3.org/ns/activitystreams#Actor
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ActorItems {
     pub inbox: String,
     pub outbox: String,
     pub following: String,
     pub followers: String,
     pub preferred_username: Option<String>,
-    pub endpoints: HashMap<String, String>,
+    pub endpoints: Map<String, String>,
+}
+
+// Types of https://www.w3.org/ns/activitystreams which are activities.
+const ACTIVITY_TYPES: &[&str] = &[
+    "Accept",
+    "Activity",
+    "Add",
+    "Announce",
+    "Arrive",
+    "Block",
+    "Create",
+    "Delete",
+    "Dislike",
+    "Flag",
+    "Follow",
+    "Ignore",
+    "IntransitiveActivity",
+    "Invite",
+    "Join",
+    "Leave",
+    "Like",
+    "Listen",
+    "Move",
+    "Offer",
+    "Question",
+    "Read",
+    "Reject",
+    "Remove",
+    "TentativeAccept",
+    "TentativeReject",
+    "Travel",
+    "Undo",
+    "Update",
+    "View",
+];
+
+// Longest `preferredUsername` accepted, the same as the limit of Mastodon.
+pub const MAX_USERNAME_LENGTH: usize = 30;
+
+impl ActorItems {
+    /**
+     * Checks that a `preferredUsername` can be used in WebFinger and
+     * mentions: ASCII letters, digits and `_`, with `.` and `-` allowed
+     * between them, up to `MAX_USERNAME_LENGTH` characters.
+     */
+    pub fn validate_username(username: &str) -> Result<(), Box<dyn Error>> {
+        if username.is_empty() {
+            return Err("username must not be empty".into());
+        }
+        if username.len() > MAX_USERNAME_LENGTH {
+            return Err(format!(
+                "username must not be longer than {} characters",
+                MAX_USERNAME_LENGTH
+            )
+            .into());
+        }
+        if let Some(c) = username
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+        {
+            return Err(format!("username must not contain {:?}", c).into());
+        }
+        if username.starts_with(['.', '-']) || username.ends_with(['.', '-']) {
+            return Err("username must not start or end with . or -".into());
+        }
+        Ok(())
+    }
+
+    /**
+     * The form of a username to compare and look up by, as usernames are
+     * case insensitive: without surrounding whitespace or a leading `@`, in
+     * lowercase.
+     */
+    pub fn normalize_username(username: &str) -> String {
+        let username = username.trim();
+        username
+            .strip_prefix('@')
+            .unwrap_or(username)
+            .to_ascii_lowercase()
+    }
+}
+
+/**
+ * Reference: https://www.w3.org/TR/activitystreams-vocabulary/#actor-types
+ */
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum ActorType {
+    Application,
+    Group,
+    Organization,
+    Person,
+    Service,
+}
+
+impl ActorType {
+    pub const ALL: [ActorType; 5] = [
+        Self::Application,
+        Self::Group,
+        Self::Organization,
+        Self::Person,
+        Self::Service,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Application => "Application",
+            Self::Group => "Group",
+            Self::Organization => "Organization",
+            Self::Person => "Person",
+            Self::Service => "Service",
+        }
+    }
+
+    pub fn from_type(typ: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|actor_type| actor_type.as_str() == typ)
+    }
+}
+
+impl std::fmt::Display for ActorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /**
  * Reference: https://www.w3.org/ns/activitystreams#Activity
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ActivityItems {
     pub actor: Vec<ObjectOrLink>,
     pub instrument: Vec<ObjectOrLink>,
@@ -314,8 +924,9 @@ impl ActivityItems {
  * Reference: https://www.w3.org/ns/activitystreams#Collection
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CollectionItems {
-    pub total_items: Option<usize>,
+    pub total_items: Option<u64>,
     // Range: CollectionPage | Link
     pub current: Option<Box<ObjectOrLink>>,
     // Range: CollectionPage | Link
@@ -341,6 +952,7 @@ impl CollectionItems {
  * Reference: https://www.w3.org/ns/activitystreams#OrderedCollection
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OrderedCollectionItems {
     pub ordered_items: Vec<ObjectOrLink>,
 }
@@ -357,6 +969,7 @@ impl OrderedCollectionItems {
  * Reference: https://www.w3.org/ns/activitystreams#CollectionPage
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CollectionPageItems {
     pub next: Option<Box<ObjectOrLink>>,
     pub prev: Option<Box<ObjectOrLink>>,
@@ -378,8 +991,9 @@ impl CollectionPageItems {
  * Reference: https://www.w3.org/ns/activitystreams#OrderedCollectionPage
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OrderedCollectionPageItems {
-    pub start_index: Option<usize>,
+    pub start_index: Option<u64>,
 }
 
 impl OrderedCollectionPageItems {
@@ -392,6 +1006,7 @@ impl OrderedCollectionPageItems {
  * Reference: https://www.w3.org/ns/activitystreams#Relationship
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RelationshipItems {
     pub subject: Option<Box<ObjectOrLink>>,
     pub relationship: Vec<Object>,
@@ -410,6 +1025,7 @@ impl RelationshipItems {
  * Reference: https://www.w3.org/ns/activitystreams#Tombstone
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TombstoneItems {
     pub former_type: Vec<Object>,
     pub deleted: Option<DateTime<Utc>>,
@@ -427,14 +1043,18 @@ impl TombstoneItems {
 /**
  * Reference: https://www.w3.org/ns/activitystreams#Question
  */
+#[cfg(feature = "question")]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct QuestionItems {
     pub one_of: Vec<ObjectOrLink>,
     pub any_of: Vec<ObjectOrLink>,
     // TODO: more strict
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::arbitrary_optional_value))]
     pub closed: Option<Value>,
 }
 
+#[cfg(feature = "question")]
 impl QuestionItems {
     pub fn empty() -> Self {
         Self {
@@ -448,23 +1068,26 @@ impl QuestionItems {
 /**
  * Reference: https://www.w3.org/ns/activitystreams#Place
  */
+#[cfg(feature = "place")]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PlaceItems {
     pub accuracy: Option<f64>,
     pub altitude: Option<f64>,
-    pub latitute: Option<f64>,
-    pub longitute: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
     pub radius: Option<f64>,
     pub units: Option<String>,
 }
 
+#[cfg(feature = "place")]
 impl PlaceItems {
     pub fn empty() -> Self {
         Self {
             accuracy: None,
             altitude: None,
-            latitute: None,
-            longitute: None,
+            latitude: None,
+            longitude: None,
             radius: None,
             units: None,
         }
@@ -475,6 +1098,7 @@ impl PlaceItems {
  * Reference: https://docs.joinmastodon.org/spec/activitypub/#as
  */
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ActivityStreamExtItems {
     pub manually_approves_followers: Option<bool>,
     pub also_known_as: Vec<String>,
@@ -496,7 +1120,9 @@ impl ActivityStreamExtItems {
 /**
  * Reference: https://docs.joinmastodon.org/spec/activitypub/#toot
  */
+#[cfg(feature = "mastodon")]
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MastodonExtItems {
     // http://joinmastodon.org/ns#featured
     pub featured: Option<String>,
@@ -514,6 +1140,7 @@ pub struct MastodonExtItems {
     pub devices: Option<String>,
 }
 
+#[cfg(feature = "mastodon")]
 impl MastodonExtItems {
     pub fn empty() -> Self {
         Self {
@@ -529,11 +1156,14 @@ impl MastodonExtItems {
 /**
  * Reference: https://w3id.org/security/v1
  */
+#[cfg(feature = "security")]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SecurityItems {
     pub public_key: Option<Key>,
 }
 
+#[cfg(feature = "security")]
 impl SecurityItems {
     pub fn empty() -> Self {
         Self { public_key: None }
@@ -544,6 +1174,7 @@ impl SecurityItems {
  * Reference: https://schema.org/PropertyValue
  */
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PropertyItems {
     pub value: Option<String>,
 }
@@ -554,10 +1185,113 @@ impl PropertyItems {
     }
 }
 
+/**
+ * A value of schema.org which is either text, or a thing with an
+ * identifier and a name as PeerTube writes categories and licences.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum SchemaValue {
+    Text(String),
+    Thing {
+        identifier: Option<String>,
+        name: Option<String>,
+    },
+}
+
+/**
+ * Terms of schema.org which federated software uses beyond
+ * `PropertyValue`. Their terms are added to `@context` on serialization
+ * if it is set.
+ *
+ * Reference: https://schema.org/CreativeWork
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SchemaOrgItems {
+    // https://schema.org/category
+    pub category: Vec<SchemaValue>,
+
+    // https://schema.org/license, which PeerTube calls `licence`.
+    pub license: Option<SchemaValue>,
+
+    // https://schema.org/identifier
+    pub identifier: Option<String>,
+}
+
+impl SchemaOrgItems {
+    pub fn empty() -> Self {
+        Self {
+            category: vec![],
+            license: None,
+            identifier: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::empty()
+    }
+
+    /**
+     * The definitions of the terms which are set.
+     */
+    pub fn terms(&self) -> Vec<(String, Iri)> {
+        let used = [
+            ("category", !self.category.is_empty()),
+            ("license", self.license.is_some()),
+            ("identifier", self.identifier.is_some()),
+        ];
+        let mut dest = vec![];
+        for (term, _) in used.iter().filter(|(_, used)| *used) {
+            if dest.is_empty() {
+                dest.push(("schema".to_string(), Iri::from("http://schema.org#")));
+            }
+            dest.push((term.to_string(), Iri::Direct(format!("schema:{}", term))));
+        }
+        dest
+    }
+}
+
+/**
+ * Bookkeeping of the JSON representation, which is not part of any vocabulary.
+ * The property order does not take part in equality.
+ */
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct JsonItems {
+    // Keys in the order of the original document. Only recorded with the
+    // `preserve_order` feature.
+    pub property_order: Vec<String>,
+    // Keys explicitly set to `null`, as opposed to absent ones. `Update`
+    // activities use them to clear properties.
+    pub null_properties: Vec<String>,
+    // Properties of vocabularies which this model does not know.
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = crate::fuzz::arbitrary_properties))]
+    pub unknown_properties: Map<String, Value>,
+}
+
+impl JsonItems {
+    pub fn empty() -> Self {
+        Self {
+            property_order: vec![],
+            null_properties: vec![],
+            unknown_properties: Map::new(),
+        }
+    }
+}
+
+impl PartialEq for JsonItems {
+    fn eq(&self, other: &Self) -> bool {
+        self.null_properties == other.null_properties
+            && self.unknown_properties == other.unknown_properties
+    }
+}
+
 /**
  * Reference: https://w3c.github.io/vc-data-integrity/vocab/security/vocabulary.html#Key
  */
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Key {
     pub id: String,
     pub owner: String,
@@ -572,12 +1306,15 @@ impl From<&str> for Context {
 
 impl<const N: usize> From<[(&str, Iri); N]> for Context {
     fn from(value: [(&str, Iri); N]) -> Self {
-        let defs = HashMap::from_iter(
+        let terms = Map::from_iter(
             value
                 .into_iter()
                 .map(|entry| (entry.0.to_string(), entry.1)),
         );
-        Self::TermDefs(defs)
+        Self::TermDefs(ContextDefinition {
+            terms,
+            ..Default::default()
+        })
     }
 }
 
@@ -603,6 +1340,7 @@ impl From<String> for Link {
             height: None,
             hreflang: None,
             media_type: vec![],
+            name: vec![],
             rel: vec![],
             width: None,
         }
@@ -0,0 +1,271 @@
+use std::error::Error;
+#[cfg(feature = "http")]
+use std::io::Read;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use url::Url;
+
+#[cfg(feature = "http")]
+use crate::fetch::{self, FetchPolicy, PublicOnly};
+use crate::model::Map;
+
+// The prefix of the `rel` of links to NodeInfo documents, followed by the
+// version of their schema.
+const SCHEMA_PREFIX: &str = "http://nodeinfo.diaspora.software/ns/schema/";
+
+// Limit of a fetched discovery or NodeInfo document.
+#[cfg(feature = "http")]
+const MAX_JSON_LENGTH: u64 = 64 * 1024;
+
+/**
+ * The software a peer runs, from its NodeInfo.
+ *
+ * Reference: https://nodeinfo.diaspora.software/protocol.html
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct NodeInfo {
+    // Lowercased, e.g. `mastodon` or `pleroma`.
+    pub software_name: String,
+    pub software_version: Option<String>,
+    pub protocols: Vec<String>,
+}
+
+impl NodeInfo {
+    /**
+     * Reads a NodeInfo document of any version.
+     */
+    pub fn from_json(document: &Value) -> Result<Self, Box<dyn Error>> {
+        let software = &document["software"];
+        let name = software["name"]
+            .as_str()
+            .ok_or("NodeInfo has no software name")?;
+        Ok(Self {
+            software_name: name.to_ascii_lowercase(),
+            software_version: software["version"].as_str().map(String::from),
+            protocols: document["protocols"]
+                .as_array()
+                .map(|protocols| {
+                    protocols
+                        .iter()
+                        .filter_map(|protocol| protocol.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /**
+     * Whether the peer runs the software, by its name in any case.
+     */
+    pub fn is(&self, software_name: &str) -> bool {
+        self.software_name.eq_ignore_ascii_case(software_name)
+    }
+
+    /**
+     * Whether the version of the software is at least `min`, comparing the
+     * leading numbers of the versions, e.g. `4.2.0+glitch` as `[4, 2, 0]`.
+     * `false` if the version is unknown.
+     */
+    pub fn version_at_least(&self, min: &[u64]) -> bool {
+        let Some(version) = &self.software_version else {
+            return false;
+        };
+        let numbers: Vec<u64> = version
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map_while(|number| number.parse().ok())
+            .collect();
+        numbers.as_slice() >= min
+    }
+
+    /**
+     * Whether the peer federates by ActivityPub, as far as it tells.
+     */
+    pub fn speaks_activity_pub(&self) -> bool {
+        self.protocols
+            .iter()
+            .any(|protocol| protocol.eq_ignore_ascii_case("activitypub"))
+    }
+}
+
+/**
+ * The well-known document of a host listing its NodeInfo documents.
+ */
+pub fn discovery_url(host: &str) -> Result<Url, Box<dyn Error>> {
+    Ok(Url::parse(&format!(
+        "https://{}/.well-known/nodeinfo",
+        host
+    ))?)
+}
+
+/**
+ * The NodeInfo document of the latest schema version in the links of a
+ * discovery document.
+ */
+pub fn document_url(discovery: &Value) -> Option<String> {
+    discovery["links"]
+        .as_array()?
+        .iter()
+        .filter_map(|link| {
+            let version = link["rel"].as_str()?.strip_prefix(SCHEMA_PREFIX)?;
+            let numbers: Vec<u64> = version
+                .split('.')
+                .map(|number| number.parse().ok())
+                .collect::<Option<_>>()?;
+            Some((numbers, link["href"].as_str()?))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, href)| href.to_string())
+}
+
+/**
+ * Finds the NodeInfo of a host, e.g. over HTTPS.
+ */
+pub trait NodeInfoLookup {
+    fn lookup(&self, host: &str) -> Result<NodeInfo, Box<dyn Error>>;
+}
+
+impl<F: Fn(&str) -> Result<NodeInfo, Box<dyn Error>>> NodeInfoLookup for F {
+    fn lookup(&self, host: &str) -> Result<NodeInfo, Box<dyn Error>> {
+        self(host)
+    }
+}
+
+/**
+ * Looks up NodeInfo over HTTPS under a fetch policy.
+ */
+#[cfg(feature = "http")]
+pub struct HttpNodeInfo {
+    pub policy: Box<dyn FetchPolicy>,
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpNodeInfo {
+    fn default() -> Self {
+        Self {
+            policy: Box::new(PublicOnly),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl NodeInfoLookup for HttpNodeInfo {
+    fn lookup(&self, host: &str) -> Result<NodeInfo, Box<dyn Error>> {
+        let url = discovery_url(host)?;
+        let response = fetch::get(url.as_str(), "application/json", self.policy.as_ref())?;
+        let discovery: Value =
+            serde_json::from_reader(response.into_reader().take(MAX_JSON_LENGTH))?;
+        let url = document_url(&discovery).ok_or_else(|| format!("{} has no NodeInfo", host))?;
+        let response = fetch::get(&url, "application/json", self.policy.as_ref())?;
+        NodeInfo::from_json(&serde_json::from_reader(
+            response.into_reader().take(MAX_JSON_LENGTH),
+        )?)
+    }
+}
+
+/**
+ * The NodeInfo of peers, looked up once in `ttl`. Failed lookups are cached
+ * as well for `negative_ttl`, as many servers serve no NodeInfo, so that
+ * delivery does not wait on them every time, while a peer which was down
+ * is looked up again soon.
+ */
+pub struct PeerCache<L> {
+    pub lookup: L,
+    pub ttl: Duration,
+    pub negative_ttl: Duration,
+    // Peers kept at most. Beyond it, expired ones are dropped, then the ones
+    // looked up first.
+    pub max_entries: usize,
+    entries: RwLock<Map<String, Entry>>,
+}
+
+struct Entry {
+    node_info: Option<NodeInfo>,
+    looked_up_at: DateTime<Utc>,
+}
+
+impl<L: NodeInfoLookup> PeerCache<L> {
+    pub fn new(lookup: L) -> Self {
+        Self {
+            lookup,
+            ttl: Duration::days(1),
+            negative_ttl: Duration::hours(1),
+            max_entries: 10_000,
+            entries: RwLock::new(Map::new()),
+        }
+    }
+
+    fn is_fresh(&self, entry: &Entry, now: DateTime<Utc>) -> bool {
+        let ttl = match entry.node_info {
+            Some(_) => self.ttl,
+            None => self.negative_ttl,
+        };
+        entry.looked_up_at + ttl > now
+    }
+
+    /**
+     * The NodeInfo of the host, looked up unless cached within the `ttl`, or
+     * `negative_ttl` for a failed lookup, before `now`. `None` if the host
+     * has none.
+     */
+    pub fn get(&self, host: &str, now: DateTime<Utc>) -> Option<NodeInfo> {
+        let host = host.to_ascii_lowercase();
+        if let Some(entry) = self.entries.read().ok()?.get(&host) {
+            if self.is_fresh(entry, now) {
+                return entry.node_info.clone();
+            }
+        }
+        let node_info = self.lookup.lookup(&host).ok();
+        self.insert(&host, node_info.clone(), now);
+        node_info
+    }
+
+    /**
+     * The NodeInfo of the host if cached, without looking it up.
+     */
+    pub fn cached(&self, host: &str) -> Option<NodeInfo> {
+        let entries = self.entries.read().ok()?;
+        entries.get(&host.to_ascii_lowercase())?.node_info.clone()
+    }
+
+    /**
+     * Caches the NodeInfo of the host, e.g. one known by other means.
+     */
+    pub fn insert(&self, host: &str, node_info: Option<NodeInfo>, now: DateTime<Utc>) {
+        let host = host.to_ascii_lowercase();
+        if let Ok(mut entries) = self.entries.write() {
+            if entries.len() >= self.max_entries && !entries.contains_key(&host) {
+                entries.retain(|_, entry| self.is_fresh(entry, now));
+            }
+            if entries.len() >= self.max_entries && !entries.contains_key(&host) {
+                let first = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.looked_up_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(first) = first {
+                    entries.shift_remove(&first);
+                }
+            }
+            entries.insert(
+                host,
+                Entry {
+                    node_info,
+                    looked_up_at: now,
+                },
+            );
+        }
+    }
+
+    /**
+     * Forgets the host, e.g. after it upgraded, to look it up again.
+     */
+    pub fn invalidate(&self, host: &str) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.shift_remove(&host.to_ascii_lowercase());
+        }
+    }
+}
@@ -0,0 +1,31 @@
+/**
+ * Hooks for the outcomes of network related operations, e.g. to export them
+ * as metrics. All methods do nothing by default.
+ */
+pub trait Observer: Send + Sync {
+    fn delivery_attempted(&self, _inbox: &str) {}
+
+    fn delivery_succeeded(&self, _inbox: &str) {}
+
+    fn delivery_failed(&self, _inbox: &str, _error: &str) {}
+
+    /**
+     * The peer asked to retry a failed delivery after the seconds with
+     * `Retry-After`.
+     */
+    fn delivery_retry_after(&self, _inbox: &str, _seconds: i64) {}
+
+    fn fetch_cache_hit(&self, _iri: &str) {}
+
+    fn fetch_cache_miss(&self, _iri: &str) {}
+
+    fn verification(&self, _key_id: &str, _verified: bool) {}
+}
+
+/**
+ * An observer which ignores everything.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {}
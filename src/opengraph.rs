@@ -0,0 +1,206 @@
+#[cfg(feature = "opengraph")]
+use std::{error::Error, io::Read};
+
+use url::Url;
+
+#[cfg(feature = "opengraph")]
+use crate::fetch::{self, FetchPolicy, PublicOnly};
+use crate::html::{escape, unescape};
+use crate::model::{Link, Map, Object, ObjectOrLink};
+#[cfg(all(feature = "opengraph", feature = "tracing"))]
+use crate::origin::host_of;
+
+// Limit of fetched HTML, as the meta tags are expected in the head.
+#[cfg(feature = "opengraph")]
+const MAX_HTML_LENGTH: u64 = 1024 * 1024;
+
+/**
+ * Fetches a page and builds its preview with `preview_from_html`. Only pages
+ * on public addresses are fetched, as the URLs come from remote posts.
+ */
+#[cfg(feature = "opengraph")]
+pub fn fetch_preview(url: &str) -> Result<Object, Box<dyn Error>> {
+    fetch_preview_with_policy(url, &PublicOnly)
+}
+
+#[cfg(feature = "opengraph")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "info", skip_all, fields(host = host_of(url).unwrap_or_default()), err)
+)]
+pub fn fetch_preview_with_policy(
+    url: &str,
+    policy: &dyn FetchPolicy,
+) -> Result<Object, Box<dyn Error>> {
+    let response = fetch::get(url, "text/html,application/xhtml+xml", policy)?;
+    let mut html = String::new();
+    response
+        .into_reader()
+        .take(MAX_HTML_LENGTH)
+        .read_to_string(&mut html)?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(size = html.len(), "fetched page");
+    Ok(preview_from_html(url, &html))
+}
+
+/**
+ * Builds a `Page` preview from the OpenGraph properties of a page, falling
+ * back to its `<title>` and `description` meta tag. Relative URLs are resolved
+ * against `url`.
+ *
+ * Reference: https://ogp.me/
+ */
+pub fn preview_from_html(url: &str, html: &str) -> Object {
+    let meta = read_meta(html);
+    let resolve = |value: &str| match Url::parse(url).and_then(|base| base.join(value)) {
+        Ok(resolved) => resolved.to_string(),
+        Err(_) => value.to_string(),
+    };
+
+    let mut preview = Object::new(None, vec!["Page".to_string()]);
+    let items = &mut preview.object_items;
+
+    let page_url = meta
+        .get("og:url")
+        .or(meta.get("canonical"))
+        .map(|page_url| resolve(page_url))
+        .unwrap_or(url.to_string());
+    items.url = Some(Link {
+        media_type: vec!["text/html".to_string()],
+        ..Link::from(page_url)
+    });
+    items.name = meta
+        .get("og:title")
+        .or(meta.get("title"))
+        .map(|name| name.trim().to_string())
+        .into_iter()
+        .collect();
+    items.summary = meta
+        .get("og:description")
+        .or(meta.get("description"))
+        .map(|summary| escape(summary.trim()))
+        .into_iter()
+        .collect();
+    if let Some(image) = meta
+        .get("og:image:secure_url")
+        .or(meta.get("og:image"))
+        .or(meta.get("og:image:url"))
+    {
+        items.image = vec![ObjectOrLink::Link(Link {
+            media_type: meta.get("og:image:type").cloned().into_iter().collect(),
+            width: meta
+                .get("og:image:width")
+                .and_then(|width| width.parse().ok()),
+            height: meta
+                .get("og:image:height")
+                .and_then(|height| height.parse().ok()),
+            ..Link::from(resolve(image))
+        })];
+    }
+
+    preview
+}
+
+/**
+ * Reads `<meta>` tags, the canonical `<link>` and the `<title>` of a page.
+ * Only the first value of each property is kept.
+ */
+fn read_meta(html: &str) -> Map<String, String> {
+    let mut dest = Map::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = match rest.find('>') {
+            None => break,
+            Some(end) => end,
+        };
+        let tag = &rest[..end];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "meta" => {
+                let attrs = read_attributes(tag);
+                let key = attrs.get("property").or(attrs.get("name"));
+                if let (Some(key), Some(content)) = (key, attrs.get("content")) {
+                    dest.entry(key.clone()).or_insert(content.clone());
+                }
+            }
+            "link" => {
+                let attrs = read_attributes(tag);
+                let is_canonical = attrs.get("rel").is_some_and(|rel| {
+                    rel.split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("canonical"))
+                });
+                if let (true, Some(href)) = (is_canonical, attrs.get("href")) {
+                    dest.entry("canonical".to_string()).or_insert(href.clone());
+                }
+            }
+            "title" => {
+                let body = &rest[end + 1..];
+                if let Some(title_end) = body.to_ascii_lowercase().find("</title") {
+                    dest.entry("title".to_string())
+                        .or_insert_with(|| unescape(&body[..title_end]));
+                }
+            }
+            // The meta tags are in the head.
+            "body" => break,
+            _ => {}
+        }
+        rest = &rest[end + 1..];
+    }
+    dest
+}
+
+pub(crate) fn read_attributes(tag: &str) -> Map<String, String> {
+    let mut dest = Map::new();
+    let mut chars = tag.char_indices().peekable();
+    // Skip the tag name.
+    while chars.next_if(|(_, c)| !c.is_whitespace()).is_some() {}
+
+    loop {
+        while chars
+            .next_if(|(_, c)| c.is_whitespace() || *c == '/')
+            .is_some()
+        {}
+        let start = match chars.peek() {
+            None => return dest,
+            Some((start, _)) => *start,
+        };
+        while chars
+            .next_if(|(_, c)| !c.is_whitespace() && *c != '=' && *c != '/')
+            .is_some()
+        {}
+        let end = chars.peek().map_or(tag.len(), |(end, _)| *end);
+        let name = tag[start..end].to_ascii_lowercase();
+
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        if chars.next_if(|(_, c)| *c == '=').is_none() {
+            dest.entry(name).or_default();
+            continue;
+        }
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let value = match chars.peek() {
+            None => String::new(),
+            Some((_, quote @ ('"' | '\''))) => {
+                let quote = *quote;
+                chars.next();
+                let start = chars.peek().map_or(tag.len(), |(start, _)| *start);
+                while chars.next_if(|(_, c)| *c != quote).is_some() {}
+                let end = chars.peek().map_or(tag.len(), |(end, _)| *end);
+                chars.next();
+                tag[start..end].to_string()
+            }
+            Some((start, _)) => {
+                let start = *start;
+                while chars.next_if(|(_, c)| !c.is_whitespace()).is_some() {}
+                let end = chars.peek().map_or(tag.len(), |(end, _)| *end);
+                tag[start..end].to_string()
+            }
+        };
+        dest.entry(name).or_insert_with(|| unescape(&value));
+    }
+}
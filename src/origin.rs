@@ -0,0 +1,28 @@
+use url::{Origin, Url};
+
+/**
+ * Returns the origin of an IRI, or `None` if it does not parse or only has an
+ * opaque origin, which is never equal to any other.
+ */
+pub(crate) fn origin_of(iri: &str) -> Option<Origin> {
+    let origin = Url::parse(iri).ok()?.origin();
+    origin.is_tuple().then_some(origin)
+}
+
+/**
+ * Returns whether both IRIs share an origin which is not opaque.
+ */
+pub(crate) fn same_origin(a: &str, b: &str) -> bool {
+    origin_of(a).is_some_and(|origin| origin_of(b) == Some(origin))
+}
+
+/**
+ * Returns the lowercased host of an IRI, or `None` if it does not parse or has
+ * no host.
+ */
+pub(crate) fn host_of(iri: &str) -> Option<String> {
+    Url::parse(iri)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_ascii_lowercase())
+}
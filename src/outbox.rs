@@ -0,0 +1,179 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+
+use crate::id::IdMinter;
+use crate::json::JsonSerde;
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::store::ObjectStore;
+
+// The profile of `application/ld+json` for ActivityStreams documents.
+const ACTIVITY_STREAMS_PROFILE: &str = "https://www.w3.org/ns/activitystreams";
+
+/**
+ * Whether a `Content-Type` is of an ActivityStreams document, i.e.
+ * `application/activity+json` or `application/ld+json` with the
+ * ActivityStreams profile.
+ *
+ * Reference: https://www.w3.org/TR/activitypub/#client-to-server-interactions
+ */
+pub fn is_activity_streams_type(content_type: &str) -> bool {
+    let mut params = content_type.split(';').map(str::trim);
+    let essence = params.next().unwrap_or_default().to_ascii_lowercase();
+    match essence.as_str() {
+        "application/activity+json" => true,
+        "application/ld+json" => params.any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("profile")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_whitespace()
+                        .any(|profile| profile == ACTIVITY_STREAMS_PROFILE)
+            })
+        }),
+        _ => false,
+    }
+}
+
+/**
+ * The response to a POST to an outbox.
+ */
+#[derive(PartialEq, Debug, Clone)]
+pub enum OutboxResponse {
+    // The stored activity, whose id is the `Location`.
+    Created(Box<Object>),
+    UnsupportedMediaType,
+    BadRequest(String),
+}
+
+impl OutboxResponse {
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::Created(_) => 201,
+            Self::UnsupportedMediaType => 415,
+            Self::BadRequest(_) => 400,
+        }
+    }
+
+    pub fn location(&self) -> Option<&str> {
+        match self {
+            Self::Created(activity) => activity.id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Handles a POST to the outbox of `actor`, who is already authenticated and
+ * can be found from the request URL with `Routes::parse`.
+ * An object which is not an activity is wrapped in a `Create` with its
+ * addressing. Ids given by the client are replaced with ones from
+ * `minter`, for the activity and the object of a `Create`, whose
+ * `attributedTo` must be `actor` if given. The activity and the created
+ * object are stored, and the activity is prepended to the
+ * outbox collection if it is in `store`.
+ *
+ * Errors are of `store`, for which a server responds with 500.
+ *
+ * Reference: https://www.w3.org/TR/activitypub/#client-to-server-interactions
+ */
+pub fn post_to_outbox<S: ObjectStore + ?Sized>(
+    actor: &Object,
+    content_type: Option<&str>,
+    body: &[u8],
+    store: &S,
+    mut minter: impl IdMinter,
+    now: DateTime<Utc>,
+) -> Result<OutboxResponse, Box<dyn Error>> {
+    if !content_type.is_some_and(is_activity_streams_type) {
+        return Ok(OutboxResponse::UnsupportedMediaType);
+    }
+    let (actor_id, outbox) = match (&actor.id, &actor.actor_items) {
+        (Some(actor_id), Some(actor_items)) => (actor_id.as_str(), actor_items.outbox.as_str()),
+        _ => return Err("actor has no id or outbox".into()),
+    };
+    let object = match Object::from_json_bytes(body) {
+        Ok(object) => object,
+        Err(err) => return Ok(OutboxResponse::BadRequest(err.to_string())),
+    };
+
+    let mut activity = if object.is_activity() {
+        object
+    } else {
+        wrap_in_create(object, actor_id)
+    };
+    match activity.activity_items.actor.as_slice() {
+        [] => activity.activity_items.actor = vec![ObjectOrLink::Link(Link::from(actor_id))],
+        [owner] if owner.id() == Some(actor_id) => {}
+        _ => {
+            return Ok(OutboxResponse::BadRequest(format!(
+                "activity must be performed by {}",
+                actor_id
+            )))
+        }
+    }
+
+    activity.id = Some(minter.mint(&activity));
+    activity.object_items.published.get_or_insert(now);
+    let is_create = activity.typ.iter().any(|typ| typ == "Create");
+    if is_create {
+        let object = match activity.activity_items.object.as_mut_slice() {
+            [ObjectOrLink::Object(object)] => object,
+            _ => {
+                return Ok(OutboxResponse::BadRequest(
+                    "Create must embed exactly one object".to_string(),
+                ))
+            }
+        };
+        let attributed_to = &object.object_items.attributed_to;
+        if attributed_to
+            .iter()
+            .any(|owner| owner.id() != Some(actor_id))
+        {
+            return Ok(OutboxResponse::BadRequest(format!(
+                "object must be attributed to {}",
+                actor_id
+            )));
+        }
+        object.id = Some(minter.mint(object));
+        object.object_items.published.get_or_insert(now);
+        if attributed_to.is_empty() {
+            object.object_items.attributed_to = vec![ObjectOrLink::Link(Link::from(actor_id))];
+        }
+        store.put(object.clone())?;
+    }
+    store.put(activity.clone())?;
+
+    if let Some(mut collection) = store.get(outbox)? {
+        let id = activity.id.clone().unwrap_or_default();
+        collection
+            .ordered_collection_items
+            .ordered_items
+            .insert(0, ObjectOrLink::Link(Link::from(id)));
+        if let Some(total_items) = &mut collection.collection_items.total_items {
+            *total_items += 1;
+        }
+        store.put(collection)?;
+    }
+    Ok(OutboxResponse::Created(Box::new(activity)))
+}
+
+fn wrap_in_create(mut object: Object, actor_id: &str) -> Object {
+    let schema_context = object.schema_context.take();
+    let items = &object.object_items;
+    let mut activity = Object::new_activity(
+        None,
+        vec!["Create".to_string()],
+        vec![ObjectOrLink::Link(Link::from(actor_id))],
+        vec![],
+    );
+    activity.schema_context = schema_context;
+    activity.object_items.to.clone_from(&items.to);
+    activity.object_items.bto.clone_from(&items.bto);
+    activity.object_items.cc.clone_from(&items.cc);
+    activity.object_items.bcc.clone_from(&items.bcc);
+    activity.object_items.audience.clone_from(&items.audience);
+    activity.activity_items.object = vec![ObjectOrLink::Object(object)];
+    activity
+}
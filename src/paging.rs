@@ -0,0 +1,249 @@
+use std::error::Error;
+
+use url::Url;
+
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::resolver::Resolver;
+
+// Pages of a collection read by `collect_items`.
+const MAX_PAGES: usize = 100;
+
+/**
+ * A window of an ordered collection, newest first, by the ids of its items
+ * as in the pages of Mastodon: `max_id` for items older than it, `since_id`
+ * for the newest items newer than it, and `min_id` for the items just newer
+ * than it, i.e. the page before it.
+ *
+ * Ids are not ordered by themselves, so a cursor missing in the collection,
+ * e.g. deleted, bounds nothing for `min_id` and `since_id`, and leaves
+ * nothing older for `max_id`. Mastodon writes the last page with a `min_id`
+ * of `0` as such.
+ *
+ * Reference: https://docs.joinmastodon.org/api/guidelines/#pagination
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct PageQuery {
+    pub max_id: Option<String>,
+    pub since_id: Option<String>,
+    pub min_id: Option<String>,
+}
+
+impl PageQuery {
+    /**
+     * The window of a page IRI, from its query.
+     */
+    pub fn from_url(url: &Url) -> Self {
+        let mut dest = Self::default();
+        for (name, value) in url.query_pairs() {
+            let field = match name.as_ref() {
+                "max_id" => &mut dest.max_id,
+                "since_id" => &mut dest.since_id,
+                "min_id" => &mut dest.min_id,
+                _ => continue,
+            };
+            *field = Some(value.into_owned());
+        }
+        dest
+    }
+
+    /**
+     * The IRI of the page of the collection in this window, with `page=true`
+     * and the cursors added to its query.
+     */
+    pub fn page_url(&self, collection_id: &str) -> Result<String, Box<dyn Error>> {
+        let mut url = Url::parse(collection_id)?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("page", "true");
+            for (name, value) in [
+                ("max_id", &self.max_id),
+                ("since_id", &self.since_id),
+                ("min_id", &self.min_id),
+            ] {
+                if let Some(value) = value {
+                    query.append_pair(name, value);
+                }
+            }
+        }
+        Ok(url.into())
+    }
+
+    /**
+     * The items of the window, at most `limit` of them, out of all the items
+     * of the collection, newest first.
+     */
+    pub fn window<'a>(&self, items: &'a [ObjectOrLink], limit: usize) -> &'a [ObjectOrLink] {
+        let (start, end) = self.range(items, limit);
+        &items[start..end]
+    }
+
+    fn range(&self, items: &[ObjectOrLink], limit: usize) -> (usize, usize) {
+        let position = |id: &Option<String>| {
+            let id = id.as_deref()?;
+            items.iter().position(|item| item.id() == Some(id))
+        };
+        let start = match (&self.max_id, position(&self.max_id)) {
+            (None, _) => 0,
+            (Some(_), Some(i)) => i + 1,
+            (Some(_), None) => items.len(),
+        };
+        let end = [position(&self.since_id), position(&self.min_id)]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(items.len())
+            .max(start);
+        if self.min_id.is_some() {
+            (end.saturating_sub(limit).max(start), end)
+        } else {
+            (start, end.min(start + limit))
+        }
+    }
+}
+
+/**
+ * An `OrderedCollection` of items newest first, linking its `first`
+ * page and its `last` page as `ordered_page` writes them.
+ */
+pub fn ordered_collection(
+    collection_id: &str,
+    total_items: Option<u64>,
+) -> Result<Object, Box<dyn Error>> {
+    let last = PageQuery {
+        min_id: Some("0".to_string()),
+        ..PageQuery::default()
+    };
+    Ok(Object::new_collection(
+        Some(collection_id.to_string()),
+        vec!["OrderedCollection".to_string()],
+        total_items,
+        None,
+        Some(link_to(PageQuery::default().page_url(collection_id)?)),
+        Some(link_to(last.page_url(collection_id)?)),
+        vec![],
+        vec![],
+    ))
+}
+
+/**
+ * The `OrderedCollectionPage` of the collection in the window of `query`,
+ * with at most `limit` of `items`, newest first. `next` is the page of
+ * older items and `prev` the page of newer items, as Mastodon links them,
+ * and each is left out if there are no such items.
+ */
+pub fn ordered_page(
+    collection_id: &str,
+    items: &[ObjectOrLink],
+    query: &PageQuery,
+    limit: usize,
+) -> Result<Object, Box<dyn Error>> {
+    let (start, end) = query.range(items, limit);
+    let window = &items[start..end];
+    let mut page = Object::new_collection(
+        Some(query.page_url(collection_id)?),
+        vec!["OrderedCollectionPage".to_string()],
+        None,
+        None,
+        None,
+        None,
+        vec![],
+        window.to_vec(),
+    );
+    let page_items = &mut page.collection_page_items;
+    page_items.part_of = Some(link_to(collection_id.to_string()));
+
+    if let Some(last) = window.last().and_then(ObjectOrLink::id) {
+        if end < items.len() {
+            let older = PageQuery {
+                max_id: Some(last.to_string()),
+                ..PageQuery::default()
+            };
+            page_items.next = Some(link_to(older.page_url(collection_id)?));
+        }
+    }
+    if let Some(first) = window.first().and_then(ObjectOrLink::id) {
+        if start > 0 {
+            let newer = PageQuery {
+                min_id: Some(first.to_string()),
+                ..PageQuery::default()
+            };
+            page_items.prev = Some(link_to(newer.page_url(collection_id)?));
+        }
+    }
+    Ok(page)
+}
+
+fn link_to(href: String) -> Box<ObjectOrLink> {
+    Box::new(ObjectOrLink::Link(Link::from(href.as_str())))
+}
+
+/**
+ * The order in which to read an ordered collection.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Direction {
+    // Newest first, from `first` following `next`.
+    Older,
+    // Oldest first, from `last` following `prev`.
+    Newer,
+}
+
+/**
+ * The page following `page` in `direction`: its `next` or `prev`.
+ */
+pub fn page_link(page: &Object, direction: Direction) -> Option<&ObjectOrLink> {
+    let items = &page.collection_page_items;
+    match direction {
+        Direction::Older => items.next.as_deref(),
+        Direction::Newer => items.prev.as_deref(),
+    }
+}
+
+/**
+ * Reads at most `max_items` items of an ordered collection in `direction`,
+ * resolving its pages. Items in pages are taken as newest first, so they
+ * are reversed when reading newer ones. Up to 100 pages are read.
+ */
+pub fn collect_items<R: Resolver + ?Sized>(
+    collection: &Object,
+    resolver: &R,
+    direction: Direction,
+    max_items: usize,
+) -> Result<Vec<ObjectOrLink>, Box<dyn Error>> {
+    let mut dest = vec![];
+    let own_items = &collection.ordered_collection_items.ordered_items;
+    let items = &collection.collection_items;
+    let start = match direction {
+        Direction::Older => items.first.as_deref(),
+        Direction::Newer => items.last.as_deref(),
+    };
+    let Some(start) = start else {
+        dest.extend(own_items.iter().cloned());
+        if direction == Direction::Newer {
+            dest.reverse();
+        }
+        dest.truncate(max_items);
+        return Ok(dest);
+    };
+
+    let mut next = Some(start.clone());
+    let mut pages = 0;
+    while let Some(link) = next {
+        if dest.len() >= max_items || pages >= MAX_PAGES {
+            break;
+        }
+        let page = link.fetch_object(resolver)?;
+        let mut page_items = page.ordered_collection_items.ordered_items.clone();
+        if page_items.is_empty() {
+            break;
+        }
+        if direction == Direction::Newer {
+            page_items.reverse();
+        }
+        dest.extend(page_items);
+        pages += 1;
+        next = page_link(&page, direction).cloned();
+    }
+    dest.truncate(max_items);
+    Ok(dest)
+}
@@ -0,0 +1,203 @@
+use serde_json::Value;
+use url::Url;
+
+use crate::conformance::{check_fixture, FixtureOutcome};
+use crate::delivery::is_public;
+use crate::json::JsonSerde;
+use crate::jsonld::{ActiveContext, ACTIVITY_STREAMS_CONTEXT, SECURITY_V1_CONTEXT};
+use crate::model::{Context, Iri, Object, ObjectOrLink};
+use crate::origin::same_origin;
+
+/**
+ * A problem of the addressing of an activity.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum AddressingProblem {
+    // None of `to`, `cc`, `bto`, `bcc` and `audience` is given.
+    NoRecipients,
+    // An addressee which is neither an absolute http(s) IRI nor public.
+    InvalidAddressee(String),
+    // The public collection in `bto` or `bcc`, which hides nothing.
+    PublicInBlind,
+}
+
+/**
+ * A reason for which the activity can not be signed for delivery, or
+ * which peers verifying the signature reject it for.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum SigningProblem {
+    MissingId,
+    // Not exactly one actor, whose key would sign the delivery.
+    NoSingleActor,
+    // The actor is on another origin than the activity.
+    OriginMismatch { id: String, actor: String },
+    // An embedded actor with a key which is not of the actor.
+    KeyOwnerMismatch { actor: String, owner: String },
+}
+
+/**
+ * The problems found before delivering an activity, by check. Peers are
+ * likely to reject an activity with any of them.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct PreflightReport {
+    // Failures to serialize and parse the activity again.
+    pub conformance: Vec<String>,
+    // Keys which `@context` does not define. Remote contexts other than the
+    // built in ones are not fetched, so nothing is reported with them.
+    pub undefined_terms: Vec<String>,
+    pub addressing: Vec<AddressingProblem>,
+    pub signing: Vec<SigningProblem>,
+}
+
+impl PreflightReport {
+    pub fn is_ok(&self) -> bool {
+        self.conformance.is_empty()
+            && self.undefined_terms.is_empty()
+            && self.addressing.is_empty()
+            && self.signing.is_empty()
+    }
+}
+
+/**
+ * Runs the checks of `PreflightReport` on an activity before it is queued
+ * for delivery.
+ */
+pub fn preflight(activity: &Object) -> PreflightReport {
+    let mut report = PreflightReport::default();
+    match activity.to_json_bytes() {
+        Ok(bytes) => match check_fixture(&bytes) {
+            FixtureOutcome::Passed => {}
+            FixtureOutcome::ParseFailed(err) | FixtureOutcome::RoundTripFailed(err) => {
+                report.conformance.push(err)
+            }
+        },
+        Err(err) => report.conformance.push(err.to_string()),
+    }
+    if activity
+        .schema_context
+        .as_ref()
+        .is_none_or(|context| !has_unknown_remote_context(context))
+    {
+        if let Ok(value) = activity.to_value() {
+            undefined_terms(
+                &value,
+                &activity.active_context(),
+                &mut report.undefined_terms,
+            );
+        }
+    }
+    report.addressing = addressing_problems(activity);
+    report.signing = signing_problems(activity);
+    report
+}
+
+fn has_unknown_remote_context(context: &Context) -> bool {
+    match context {
+        Context::Single(iri) => {
+            let iri = match iri {
+                Iri::Direct(iri) => iri.trim_end_matches('#'),
+                Iri::TypeCoercion { id, .. } => id.as_str(),
+            };
+            iri != ACTIVITY_STREAMS_CONTEXT && iri != SECURITY_V1_CONTEXT
+        }
+        Context::Mix(contexts) => contexts.iter().any(has_unknown_remote_context),
+        Context::TermDefs(_) => false,
+    }
+}
+
+fn undefined_terms(value: &Value, context: &ActiveContext, dest: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                undefined_terms(item, context, dest);
+            }
+        }
+        Value::Object(properties) => {
+            for (key, value) in properties {
+                if key == "@context" {
+                    continue;
+                }
+                if context.expand_iri(key).is_none() && !dest.contains(key) {
+                    dest.push(key.clone());
+                }
+                let is_map = context
+                    .term_definition(key)
+                    .is_some_and(|def| def.container.as_deref() == Some("@language"));
+                if !is_map {
+                    undefined_terms(value, context, dest);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn addressing_problems(activity: &Object) -> Vec<AddressingProblem> {
+    let items = &activity.object_items;
+    let mut dest = vec![];
+    let addressees: Vec<&ObjectOrLink> = items
+        .to
+        .iter()
+        .chain(&items.cc)
+        .chain(&items.bto)
+        .chain(&items.bcc)
+        .chain(&items.audience)
+        .collect();
+    if addressees.is_empty() {
+        dest.push(AddressingProblem::NoRecipients);
+    }
+    for addressee in addressees {
+        let iri = addressee.id().unwrap_or_default();
+        let valid = is_public(iri)
+            || Url::parse(iri).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if !valid {
+            dest.push(AddressingProblem::InvalidAddressee(iri.to_string()));
+        }
+    }
+    if items
+        .bto
+        .iter()
+        .chain(&items.bcc)
+        .filter_map(ObjectOrLink::id)
+        .any(is_public)
+    {
+        dest.push(AddressingProblem::PublicInBlind);
+    }
+    dest
+}
+
+fn signing_problems(activity: &Object) -> Vec<SigningProblem> {
+    let mut dest = vec![];
+    let actors = &activity.activity_items.actor;
+    let actor = match actors.as_slice() {
+        [actor] => actor.id(),
+        _ => None,
+    };
+    if actor.is_none() {
+        dest.push(SigningProblem::NoSingleActor);
+    }
+    match (&activity.id, actor) {
+        (None, _) => dest.push(SigningProblem::MissingId),
+        (Some(id), Some(actor)) if !same_origin(id, actor) => {
+            dest.push(SigningProblem::OriginMismatch {
+                id: id.clone(),
+                actor: actor.to_string(),
+            })
+        }
+        _ => {}
+    }
+    #[cfg(feature = "security")]
+    if let [ObjectOrLink::Object(actor)] = actors.as_slice() {
+        if let (Some(id), Some(key)) = (&actor.id, &actor.security_items.public_key) {
+            if key.owner != *id {
+                dest.push(SigningProblem::KeyOwnerMismatch {
+                    actor: id.clone(),
+                    owner: key.owner.clone(),
+                });
+            }
+        }
+    }
+    dest
+}
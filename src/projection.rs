@@ -0,0 +1,57 @@
+use crate::model::Object;
+use crate::visit::{walk_mut, VisitorMut};
+
+/**
+ * Which parts of an actor are published, for servers which keep private
+ * endpoints or internal properties in the same object as the public ones.
+ * Endpoints and properties of other vocabularies are only published if they
+ * are listed, and `bto` and `bcc` are never published.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Projection {
+    // Keys of `endpoints` to publish.
+    pub endpoints: Vec<String>,
+    // Properties this model does not know to publish, in any object.
+    pub extension_properties: Vec<String>,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self {
+            endpoints: vec!["sharedInbox".to_string()],
+            extension_properties: vec![],
+        }
+    }
+}
+
+impl Projection {
+    /**
+     * The public document of the actor.
+     */
+    pub fn project(&self, actor: &Object) -> Object {
+        let mut dest = actor.clone();
+        if let Some(actor_items) = &mut dest.actor_items {
+            actor_items
+                .endpoints
+                .retain(|key, _| self.endpoints.contains(key));
+        }
+        walk_mut(&mut dest, &mut Stripper(self));
+        dest
+    }
+}
+
+struct Stripper<'a>(&'a Projection);
+
+impl VisitorMut for Stripper<'_> {
+    fn visit_object(&mut self, object: &mut Object) {
+        object.object_items.bto.clear();
+        object.object_items.bcc.clear();
+        let allowed = &self.0.extension_properties;
+        object
+            .json_items
+            .unknown_properties
+            .retain(|key, _| allowed.contains(key));
+        // Explicit nulls may be of the dropped properties too.
+        object.json_items.null_properties.clear();
+    }
+}
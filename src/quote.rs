@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+use crate::model::{Iri, Link, Object, ObjectOrLink};
+
+// The media type of FEP-e232 object links.
+pub const OBJECT_LINK_MEDIA_TYPE: &str =
+    "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"";
+
+// The `rel` of quotes in object links of Misskey.
+pub const MISSKEY_QUOTE_REL: &str = "https://misskey-hub.net/ns#_misskey_quote";
+
+/**
+ * Which representations of a quote are written. Software reads different
+ * ones, so all of them are written by default.
+ *
+ * Reference: https://codeberg.org/fediverse/fep/src/branch/main/fep/e232/fep-e232.md
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct QuoteStyle {
+    // `quoteUrl` of Pleroma and Akkoma.
+    pub quote_url: bool,
+    // `_misskey_quote` of Misskey.
+    pub misskey_quote: bool,
+    // `quoteUri` of Fedibird.
+    pub quote_uri: bool,
+    // A `Link` tag of FEP-e232.
+    pub object_link: bool,
+}
+
+impl Default for QuoteStyle {
+    fn default() -> Self {
+        Self {
+            quote_url: true,
+            misskey_quote: true,
+            quote_uri: true,
+            object_link: true,
+        }
+    }
+}
+
+const QUOTE_PROPERTIES: [&str; 3] = ["quoteUri", "quoteUrl", "_misskey_quote"];
+
+impl Object {
+    /**
+     * The IRI of the object quoted by this one, in any of the
+     * representations of `QuoteStyle`. An object link is preferred, then
+     * the properties in the order `quoteUri`, `quoteUrl`, `_misskey_quote`.
+     */
+    pub fn quoted_object(&self) -> Option<&str> {
+        let link = self.object_items.tag.iter().find_map(quote_link_href);
+        if link.is_some() {
+            return link;
+        }
+        QUOTE_PROPERTIES.iter().find_map(|property| {
+            match self.json_items.unknown_properties.get(*property) {
+                Some(Value::String(iri)) => Some(iri.as_str()),
+                _ => None,
+            }
+        })
+    }
+
+    /**
+     * Makes this object quote the one of `iri`, replacing any quote in the
+     * other representations. The terms of the properties are added to
+     * `@context` unless it is unset.
+     */
+    pub fn set_quoted_object(&mut self, iri: &str, style: &QuoteStyle) {
+        self.remove_quoted_object();
+        let mut terms: Vec<(&str, Iri)> = vec![];
+        for (enabled, property, prefix, term) in [
+            (
+                style.quote_uri,
+                "quoteUri",
+                Some(("fedibird", "http://fedibird.com/ns#")),
+                "fedibird:quoteUri",
+            ),
+            (style.quote_url, "quoteUrl", None, "as:quoteUrl"),
+            (
+                style.misskey_quote,
+                "_misskey_quote",
+                Some(("misskey", "https://misskey-hub.net/ns#")),
+                "misskey:_misskey_quote",
+            ),
+        ] {
+            if !enabled {
+                continue;
+            }
+            self.json_items
+                .unknown_properties
+                .insert(property.to_string(), Value::String(iri.to_string()));
+            if let Some((prefix, namespace)) = prefix {
+                terms.push((prefix, Iri::from(namespace)));
+            }
+            terms.push((property, Iri::from(term)));
+        }
+        if style.object_link {
+            let mut link = Link::builder(iri)
+                .media_type(OBJECT_LINK_MEDIA_TYPE)
+                .rel(MISSKEY_QUOTE_REL)
+                .name(format!("RE: {}", iri))
+                .build();
+            link.typ = vec!["Link".to_string()];
+            self.object_items.tag.push(ObjectOrLink::Link(link));
+        }
+        if let Some(context) = &mut self.schema_context {
+            context.define_terms(terms.into_iter().map(|(term, iri)| (term.to_string(), iri)));
+        }
+    }
+
+    /**
+     * Removes the quote in all the representations.
+     */
+    pub fn remove_quoted_object(&mut self) {
+        for property in QUOTE_PROPERTIES {
+            self.json_items.unknown_properties.shift_remove(property);
+        }
+        self.object_items
+            .tag
+            .retain(|tag| quote_link_href(tag).is_none());
+    }
+}
+
+// The `href` of an object link which quotes, i.e. with the `rel` of
+// Misskey or a name starting with `RE:` as FEP-e232 suggests. Other object
+// links, e.g. to a mentioned object, are not quotes.
+fn quote_link_href(tag: &ObjectOrLink) -> Option<&str> {
+    match tag {
+        ObjectOrLink::Link(link) => {
+            let is_quote = link.rel.iter().any(|rel| rel == MISSKEY_QUOTE_REL)
+                || link.name.iter().any(|name| name.starts_with("RE:"));
+            is_quote.then_some(&link.href)
+        }
+        ObjectOrLink::Object(_) => None,
+    }
+}
@@ -0,0 +1,415 @@
+use std::{error::Error, fmt};
+
+use serde_json::{Map, Value};
+
+use crate::json::JsonSerde;
+use crate::jsonld::{
+    ActiveContext, ACTIVITY_STREAMS_CONTEXT, ACTIVITY_STREAMS_NAMESPACE, XSD_NAMESPACE,
+};
+use crate::model::{Context, Object};
+
+pub mod turtle;
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Term {
+    Iri(String),
+    BlankNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    pub fn literal(value: &str) -> Self {
+        Self::Literal {
+            value: value.to_string(),
+            datatype: None,
+            language: None,
+        }
+    }
+
+    pub fn typed_literal(value: &str, datatype: &str) -> Self {
+        Self::Literal {
+            value: value.to_string(),
+            datatype: Some(datatype.to_string()),
+            language: None,
+        }
+    }
+}
+
+/**
+ * Reference: https://www.w3.org/TR/rdf11-concepts/#section-triples
+ */
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: String,
+    pub object: Term,
+}
+
+/**
+ * Converts an object into RDF triples, following a simplified JSON-LD to RDF
+ * algorithm on top of `jsonld::ActiveContext`. Lists are converted as sets.
+ *
+ * Reference: https://www.w3.org/TR/json-ld11-api/#deserialize-json-ld-to-rdf-algorithm
+ */
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(id = object.id.as_deref()), err)
+)]
+pub fn to_triples(object: &Object) -> Result<Vec<Triple>, Box<dyn Error>> {
+    let context = object.active_context();
+    let value = object.to_value()?;
+    let mut builder = TriplesBuilder {
+        triples: vec![],
+        blank_nodes: 0,
+    };
+    match value {
+        Value::Object(node) => {
+            builder.node(&node, &context)?;
+        }
+        _ => return Err("object must be serialized as a JSON object".into()),
+    }
+    Ok(builder.triples)
+}
+
+/**
+ * Builds the object identified by `subject` from triples. Properties in the
+ * ActivityStreams namespace are compacted to their terms, the others are kept
+ * under their expanded IRIs. Blank nodes are embedded.
+ */
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, err)
+)]
+pub fn from_triples(triples: &[Triple], subject: &Term) -> Result<Object, Box<dyn Error>> {
+    let mut node = node_from_triples(triples, subject, &mut vec![])?;
+    node.insert(
+        "@context".to_string(),
+        Value::String(ACTIVITY_STREAMS_CONTEXT.to_string()),
+    );
+    Object::from_value(&Value::Object(node))
+}
+
+fn node_from_triples(
+    triples: &[Triple],
+    subject: &Term,
+    visited: &mut Vec<Term>,
+) -> Result<Map<String, Value>, Box<dyn Error>> {
+    if visited.contains(subject) {
+        return Err("blank nodes must not be cyclic".into());
+    }
+    visited.push(subject.clone());
+
+    let mut node = Map::new();
+    if let Term::Iri(id) = subject {
+        node.insert("id".to_string(), Value::String(id.clone()));
+    }
+    for triple in triples.iter().filter(|triple| &triple.subject == subject) {
+        let (key, value) = if triple.predicate == RDF_TYPE {
+            let typ = match &triple.object {
+                Term::Iri(typ) => typ,
+                _ => continue,
+            };
+            let typ = typ.strip_prefix(ACTIVITY_STREAMS_NAMESPACE).unwrap_or(typ);
+            ("type".to_string(), Value::String(typ.to_string()))
+        } else {
+            let key = triple
+                .predicate
+                .strip_prefix(ACTIVITY_STREAMS_NAMESPACE)
+                .unwrap_or(&triple.predicate)
+                .to_string();
+            match &triple.object {
+                Term::Iri(iri) => (key, Value::String(iri.clone())),
+                Term::BlankNode(_) => (
+                    key,
+                    Value::Object(node_from_triples(triples, &triple.object, visited)?),
+                ),
+                Term::Literal {
+                    value,
+                    language: Some(language),
+                    ..
+                } => {
+                    let key = format!("{}Map", key);
+                    let map = node
+                        .entry(key.clone())
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Value::Object(map) = map {
+                        map.insert(language.clone(), Value::String(value.clone()));
+                    }
+                    continue;
+                }
+                Term::Literal {
+                    value, datatype, ..
+                } => (key, literal_value(value, datatype.as_deref())),
+            }
+        };
+        match node.get_mut(&key) {
+            None => {
+                node.insert(key, value);
+            }
+            Some(Value::Array(items)) => items.push(value),
+            Some(current) => {
+                let current = current.take();
+                node.insert(key, Value::Array(vec![current, value]));
+            }
+        }
+    }
+
+    visited.pop();
+    Ok(node)
+}
+
+fn literal_value(value: &str, datatype: Option<&str>) -> Value {
+    let datatype = datatype.and_then(|datatype| datatype.strip_prefix(XSD_NAMESPACE));
+    let number = match datatype {
+        Some("boolean") => return value.parse().map(Value::Bool).unwrap_or_default(),
+        Some("integer" | "nonNegativeInteger" | "int" | "long") => {
+            value.parse::<i64>().ok().map(serde_json::Number::from)
+        }
+        Some("decimal" | "double" | "float") => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64),
+        _ => None,
+    };
+    match number {
+        Some(number) => Value::Number(number),
+        None => Value::String(value.to_string()),
+    }
+}
+
+pub fn to_n_triples(triples: &[Triple]) -> String {
+    let mut dest = String::new();
+    for triple in triples {
+        dest.push_str(&format!(
+            "{} <{}> {} .\n",
+            triple.subject,
+            escape_iri(&triple.predicate),
+            triple.object
+        ));
+    }
+    dest
+}
+
+/**
+ * Reference: https://www.w3.org/TR/n-quads/
+ */
+pub fn to_n_quads(triples: &[Triple], graph: Option<&str>) -> String {
+    match graph {
+        None => to_n_triples(triples),
+        Some(graph) => {
+            let mut dest = String::new();
+            for triple in triples {
+                dest.push_str(&format!(
+                    "{} <{}> {} <{}> .\n",
+                    triple.subject,
+                    escape_iri(&triple.predicate),
+                    triple.object,
+                    escape_iri(graph)
+                ));
+            }
+            dest
+        }
+    }
+}
+
+struct TriplesBuilder {
+    triples: Vec<Triple>,
+    blank_nodes: usize,
+}
+
+impl TriplesBuilder {
+    fn node(
+        &mut self,
+        node: &Map<String, Value>,
+        context: &ActiveContext,
+    ) -> Result<Term, Box<dyn Error>> {
+        let context = match node.get("@context") {
+            None => context.clone(),
+            Some(local) => {
+                let mut context = context.clone();
+                context.process(&Context::from_value(local)?);
+                context
+            }
+        };
+
+        let mut subject = None;
+        for (key, value) in node {
+            if let (Some("@id"), Value::String(id)) = (context.expand_iri(key).as_deref(), value) {
+                subject = Some(Term::Iri(context.expand_iri(id).unwrap_or(id.clone())));
+            }
+        }
+        let subject = match subject {
+            Some(subject) => subject,
+            None => self.blank_node(),
+        };
+
+        for (key, value) in node {
+            let property = match context.expand_iri(key) {
+                None => continue,
+                Some(property) => property,
+            };
+            if property == "@type" {
+                for typ in values(value) {
+                    if let Some(typ) = typ.as_str().and_then(|typ| context.expand_iri(typ)) {
+                        self.push(subject.clone(), RDF_TYPE, Term::Iri(typ));
+                    }
+                }
+                continue;
+            }
+            if property.starts_with('@') {
+                continue;
+            }
+
+            let definition = context.term_definition(key);
+            if let (Some("@language"), Value::Object(languages)) =
+                (definition.and_then(|def| def.container.as_deref()), value)
+            {
+                for (language, value) in languages {
+                    if let Value::String(value) = value {
+                        self.push(
+                            subject.clone(),
+                            &property,
+                            Term::Literal {
+                                value: value.clone(),
+                                datatype: Some(RDF_LANG_STRING.to_string()),
+                                language: Some(language.clone()),
+                            },
+                        );
+                    }
+                }
+                continue;
+            }
+
+            let typ = definition
+                .and_then(|def| def.typ.as_deref())
+                .and_then(|typ| context.expand_iri(typ));
+            for value in values(value) {
+                if let Some(object) = self.value(value, typ.as_deref(), &context)? {
+                    self.push(subject.clone(), &property, object);
+                }
+            }
+        }
+
+        Ok(subject)
+    }
+
+    fn value(
+        &mut self,
+        value: &Value,
+        typ: Option<&str>,
+        context: &ActiveContext,
+    ) -> Result<Option<Term>, Box<dyn Error>> {
+        Ok(match value {
+            Value::Null => None,
+            Value::Bool(value) => Some(Term::typed_literal(
+                &value.to_string(),
+                &format!("{}boolean", XSD_NAMESPACE),
+            )),
+            Value::Number(value) => match typ {
+                Some(typ) if typ != "@id" => Some(Term::typed_literal(&value.to_string(), typ)),
+                _ if value.is_f64() => Some(Term::typed_literal(
+                    &format!("{:E}", value.as_f64().unwrap_or_default()),
+                    &format!("{}double", XSD_NAMESPACE),
+                )),
+                _ => Some(Term::typed_literal(
+                    &value.to_string(),
+                    &format!("{}integer", XSD_NAMESPACE),
+                )),
+            },
+            Value::String(value) => match typ {
+                Some("@id") => Some(Term::Iri(
+                    context.expand_iri(value).unwrap_or(value.clone()),
+                )),
+                Some(typ) => Some(Term::typed_literal(value, typ)),
+                None => Some(Term::literal(value)),
+            },
+            Value::Object(node) => match (node.get("@value"), node.get("@language")) {
+                (Some(Value::String(value)), Some(Value::String(language))) => {
+                    Some(Term::Literal {
+                        value: value.clone(),
+                        datatype: Some(RDF_LANG_STRING.to_string()),
+                        language: Some(language.clone()),
+                    })
+                }
+                (Some(value), _) => self.value(value, typ, context)?,
+                (None, _) => Some(self.node(node, context)?),
+            },
+            Value::Array(_) => None,
+        })
+    }
+
+    fn blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::BlankNode(format!("b{}", self.blank_nodes - 1))
+    }
+
+    fn push(&mut self, subject: Term, predicate: &str, object: Term) {
+        self.triples.push(Triple {
+            subject,
+            predicate: predicate.to_string(),
+            object,
+        });
+    }
+}
+
+fn values(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        value => vec![value],
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iri(iri) => write!(f, "<{}>", escape_iri(iri)),
+            Self::BlankNode(label) => write!(f, "_:{}", label),
+            Self::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                write!(f, "\"{}\"", escape_literal(value))?;
+                match (language, datatype) {
+                    (Some(language), _) => write!(f, "@{}", language),
+                    (None, Some(datatype)) => write!(f, "^^<{}>", escape_iri(datatype)),
+                    (None, None) => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+fn escape_iri(iri: &str) -> String {
+    let mut dest = String::with_capacity(iri.len());
+    for c in iri.chars() {
+        match c {
+            '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' | '\u{0}'..='\u{20}' => {
+                dest.push_str(&format!("\\u{:04X}", c as u32))
+            }
+            c => dest.push(c),
+        }
+    }
+    dest
+}
+
+fn escape_literal(value: &str) -> String {
+    let mut dest = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => dest.push_str("\\\""),
+            '\\' => dest.push_str("\\\\"),
+            '\n' => dest.push_str("\\n"),
+            '\r' => dest.push_str("\\r"),
+            c => dest.push(c),
+        }
+    }
+    dest
+}
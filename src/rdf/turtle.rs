@@ -0,0 +1,501 @@
+use std::{collections::HashMap, error::Error};
+
+use url::Url;
+
+use super::{escape_literal, Term, Triple, RDF_LANG_STRING, RDF_TYPE};
+use crate::jsonld::XSD_NAMESPACE;
+
+/**
+ * Serializes triples into Turtle. Triples are grouped by subject in the
+ * order of their first appearance, and IRIs are abbreviated with the given
+ * prefixes where possible.
+ *
+ * Reference: https://www.w3.org/TR/turtle/
+ */
+pub fn to_turtle(triples: &[Triple], prefixes: &[(&str, &str)]) -> String {
+    let mut dest = String::new();
+    for (prefix, namespace) in prefixes {
+        dest.push_str(&format!("@prefix {}: <{}> .\n", prefix, namespace));
+    }
+    if !prefixes.is_empty() {
+        dest.push('\n');
+    }
+
+    let mut subjects: Vec<&Term> = vec![];
+    for triple in triples {
+        if !subjects.contains(&&triple.subject) {
+            subjects.push(&triple.subject);
+        }
+    }
+
+    for subject in subjects {
+        dest.push_str(&write_term(subject, prefixes));
+        let mut first = true;
+        for triple in triples.iter().filter(|triple| &triple.subject == subject) {
+            if !first {
+                dest.push_str(" ;");
+            }
+            first = false;
+            let predicate = if triple.predicate == RDF_TYPE {
+                "a".to_string()
+            } else {
+                write_iri(&triple.predicate, prefixes)
+            };
+            dest.push_str(&format!(
+                "\n    {} {}",
+                predicate,
+                write_term(&triple.object, prefixes)
+            ));
+        }
+        dest.push_str(" .\n");
+    }
+    dest
+}
+
+/**
+ * Parses a Turtle document into triples. Collections are not supported.
+ *
+ * Reference: https://www.w3.org/TR/turtle/
+ */
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(size = source.len()), err)
+)]
+pub fn from_turtle(source: &str) -> Result<Vec<Triple>, Box<dyn Error>> {
+    let mut parser = TurtleParser {
+        chars: source.chars().collect(),
+        pos: 0,
+        base: None,
+        prefixes: HashMap::new(),
+        blank_nodes: 0,
+        triples: vec![],
+    };
+    parser.document()?;
+    Ok(parser.triples)
+}
+
+fn write_term(term: &Term, prefixes: &[(&str, &str)]) -> String {
+    match term {
+        Term::Iri(iri) => write_iri(iri, prefixes),
+        Term::BlankNode(label) => format!("_:{}", label),
+        Term::Literal {
+            value,
+            datatype,
+            language,
+        } => match (language, datatype) {
+            (Some(language), _) => format!("\"{}\"@{}", escape_literal(value), language),
+            (None, Some(datatype)) => format!(
+                "\"{}\"^^{}",
+                escape_literal(value),
+                write_iri(datatype, prefixes)
+            ),
+            (None, None) => format!("\"{}\"", escape_literal(value)),
+        },
+    }
+}
+
+fn write_iri(iri: &str, prefixes: &[(&str, &str)]) -> String {
+    for (prefix, namespace) in prefixes {
+        if let Some(local) = iri.strip_prefix(namespace) {
+            if !local.is_empty()
+                && local
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return format!("{}:{}", prefix, local);
+            }
+        }
+    }
+    format!("<{}>", super::escape_iri(iri))
+}
+
+struct TurtleParser {
+    chars: Vec<char>,
+    pos: usize,
+    base: Option<String>,
+    prefixes: HashMap<String, String>,
+    blank_nodes: usize,
+    triples: Vec<Triple>,
+}
+
+impl TurtleParser {
+    fn document(&mut self) -> Result<(), Box<dyn Error>> {
+        loop {
+            self.skip_whitespace();
+            if self.pos >= self.chars.len() {
+                return Ok(());
+            }
+            if self.eat_keyword("@prefix") {
+                self.prefix_directive()?;
+                self.expect('.')?;
+            } else if self.eat_keyword("@base") {
+                self.base_directive()?;
+                self.expect('.')?;
+            } else if self.eat_keyword("PREFIX") {
+                self.prefix_directive()?;
+            } else if self.eat_keyword("BASE") {
+                self.base_directive()?;
+            } else {
+                self.triples_statement()?;
+                self.expect('.')?;
+            }
+        }
+    }
+
+    fn prefix_directive(&mut self) -> Result<(), Box<dyn Error>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != ':' && !c.is_whitespace()) {
+            self.pos += 1;
+        }
+        let prefix: String = self.chars[start..self.pos].iter().collect();
+        self.expect(':')?;
+        let namespace = self.iri_ref()?;
+        self.prefixes.insert(prefix, namespace);
+        Ok(())
+    }
+
+    fn base_directive(&mut self) -> Result<(), Box<dyn Error>> {
+        let base = self.iri_ref()?;
+        self.base = Some(base);
+        Ok(())
+    }
+
+    fn triples_statement(&mut self) -> Result<(), Box<dyn Error>> {
+        self.skip_whitespace();
+        if self.peek() == Some('[') {
+            let subject = self.blank_node_property_list()?;
+            self.skip_whitespace();
+            if self.peek() != Some('.') {
+                self.predicate_object_list(&subject)?;
+            }
+            return Ok(());
+        }
+        let subject = self.subject()?;
+        self.predicate_object_list(&subject)
+    }
+
+    fn subject(&mut self) -> Result<Term, Box<dyn Error>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('_') => self.blank_node_label(),
+            _ => Ok(Term::Iri(self.iri()?)),
+        }
+    }
+
+    fn predicate_object_list(&mut self, subject: &Term) -> Result<(), Box<dyn Error>> {
+        loop {
+            self.skip_whitespace();
+            let predicate = if self.eat_keyword("a") {
+                RDF_TYPE.to_string()
+            } else {
+                self.iri()?
+            };
+            loop {
+                let object = self.object()?;
+                self.triples.push(Triple {
+                    subject: subject.clone(),
+                    predicate: predicate.clone(),
+                    object,
+                });
+                self.skip_whitespace();
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.pos += 1;
+            }
+            self.skip_whitespace();
+            if self.peek() != Some(';') {
+                return Ok(());
+            }
+            while self.peek() == Some(';') {
+                self.pos += 1;
+                self.skip_whitespace();
+            }
+            if matches!(self.peek(), Some('.') | Some(']')) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Term, Box<dyn Error>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('[') => self.blank_node_property_list(),
+            Some('_') => self.blank_node_label(),
+            Some('"') | Some('\'') => self.literal(),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' => self.numeric_literal(),
+            Some('(') => Err("Turtle collections are not supported".into()),
+            _ if self.eat_keyword("true") => Ok(Term::typed_literal(
+                "true",
+                &format!("{}boolean", XSD_NAMESPACE),
+            )),
+            _ if self.eat_keyword("false") => Ok(Term::typed_literal(
+                "false",
+                &format!("{}boolean", XSD_NAMESPACE),
+            )),
+            _ => Ok(Term::Iri(self.iri()?)),
+        }
+    }
+
+    fn blank_node_property_list(&mut self) -> Result<Term, Box<dyn Error>> {
+        self.expect('[')?;
+        let node = self.fresh_blank_node();
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            self.predicate_object_list(&node)?;
+        }
+        self.expect(']')?;
+        Ok(node)
+    }
+
+    fn blank_node_label(&mut self) -> Result<Term, Box<dyn Error>> {
+        self.expect('_')?;
+        if self.peek() != Some(':') {
+            return Err(self.error("expected a blank node label"));
+        }
+        self.pos += 1;
+        Ok(Term::BlankNode(self.name()))
+    }
+
+    fn literal(&mut self) -> Result<Term, Box<dyn Error>> {
+        let value = self.string()?;
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                let language = self.name();
+                Ok(Term::Literal {
+                    value,
+                    datatype: Some(RDF_LANG_STRING.to_string()),
+                    language: Some(language),
+                })
+            }
+            Some('^') => {
+                self.expect('^')?;
+                self.expect('^')?;
+                let datatype = self.iri()?;
+                Ok(Term::typed_literal(&value, &datatype))
+            }
+            _ => Ok(Term::literal(&value)),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error>> {
+        let quote = match self.peek() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.error("expected a string")),
+        };
+        let long = self.chars[self.pos..].starts_with(&[quote, quote, quote]);
+        self.pos += if long { 3 } else { 1 };
+
+        let mut dest = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(c) if c == quote => {
+                    if !long {
+                        self.pos += 1;
+                        return Ok(dest);
+                    }
+                    if self.chars[self.pos..].starts_with(&[quote, quote, quote]) {
+                        self.pos += 3;
+                        return Ok(dest);
+                    }
+                    dest.push(c);
+                    self.pos += 1;
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    dest.push(self.escape()?);
+                }
+                Some('\n') | Some('\r') if !long => {
+                    return Err(self.error("line break in a short string"))
+                }
+                Some(c) => {
+                    dest.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, Box<dyn Error>> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("unterminated escape"))?;
+        self.pos += 1;
+        Ok(match c {
+            't' => '\t',
+            'b' => '\u{8}',
+            'n' => '\n',
+            'r' => '\r',
+            'f' => '\u{c}',
+            'u' => self.unicode_escape(4)?,
+            'U' => self.unicode_escape(8)?,
+            c => c,
+        })
+    }
+
+    fn unicode_escape(&mut self, len: usize) -> Result<char, Box<dyn Error>> {
+        if self.pos + len > self.chars.len() {
+            return Err(self.error("invalid unicode escape"));
+        }
+        let hex: String = self.chars[self.pos..self.pos + len].iter().collect();
+        self.pos += len;
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn numeric_literal(&mut self) -> Result<Term, Box<dyn Error>> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('+') | Some('-')) {
+            self.pos += 1;
+        }
+        let mut datatype = "integer";
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='9' => {}
+                // A trailing dot terminates the statement.
+                '.' if self
+                    .chars
+                    .get(self.pos + 1)
+                    .is_some_and(|c| c.is_ascii_digit()) =>
+                {
+                    if datatype == "integer" {
+                        datatype = "decimal";
+                    }
+                }
+                'e' | 'E' => {
+                    datatype = "double";
+                    if matches!(self.chars.get(self.pos + 1), Some('+') | Some('-')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let value: String = self.chars[start..self.pos].iter().collect();
+        Ok(Term::typed_literal(
+            &value,
+            &format!("{}{}", XSD_NAMESPACE, datatype),
+        ))
+    }
+
+    fn iri(&mut self) -> Result<String, Box<dyn Error>> {
+        self.skip_whitespace();
+        if self.peek() == Some('<') {
+            return self.iri_ref();
+        }
+        let name = self.name();
+        match name.split_once(':') {
+            None => Err(self.error("expected an IRI")),
+            Some((prefix, local)) => match self.prefixes.get(prefix) {
+                None => Err(self.error(&format!("undefined prefix {}", prefix))),
+                Some(namespace) => Ok(format!("{}{}", namespace, local)),
+            },
+        }
+    }
+
+    fn iri_ref(&mut self) -> Result<String, Box<dyn Error>> {
+        self.expect('<')?;
+        let mut iri = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated IRI")),
+                Some('>') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    iri.push(self.escape()?);
+                }
+                Some(c) => {
+                    iri.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        match &self.base {
+            Some(base) if !iri.contains(':') => Ok(Url::parse(base)?.join(&iri)?.to_string()),
+            _ => Ok(iri),
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.' | '%'))
+        {
+            self.pos += 1;
+        }
+        while self.pos > start && self.chars[self.pos - 1] == '.' {
+            self.pos -= 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn fresh_blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::BlankNode(format!("anon{}", self.blank_nodes - 1))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let len = keyword.chars().count();
+        if self.pos + len > self.chars.len() {
+            return false;
+        }
+        let candidate: String = self.chars[self.pos..self.pos + len].iter().collect();
+        let boundary = self
+            .chars
+            .get(self.pos + len)
+            .is_none_or(|c| !c.is_alphanumeric() && *c != ':' && *c != '_');
+        if candidate == keyword && boundary {
+            self.pos += len;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Box<dyn Error>> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c.is_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> Box<dyn Error> {
+        let line = self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count()
+            + 1;
+        format!("line {}: {}", line, message).into()
+    }
+}
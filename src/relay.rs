@@ -0,0 +1,164 @@
+use std::error::Error;
+
+use crate::delivery::PUBLIC;
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::origin::same_origin;
+use crate::resolver::Resolver;
+
+/**
+ * How a relay is subscribed to. Mastodon relays, e.g. pub-relay, take a
+ * `Follow` of the public collection and forward activities as they are
+ * received; LitePub relays, e.g. the Pleroma relay, take a `Follow` of the
+ * relay actor and `Announce` the objects they receive.
+ *
+ * Reference: https://docs.joinmastodon.org/admin/relays/
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RelayKind {
+    Mastodon,
+    LitePub,
+}
+
+/**
+ * A relay which the server subscribes to, usually with its instance actor.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Relay {
+    // The actor of the relay, e.g. `https://relay.example/actor`.
+    pub actor: String,
+    pub inbox: String,
+    pub kind: RelayKind,
+}
+
+impl Relay {
+    /**
+     * The `Follow` subscribing `local_actor` to the relay, to be delivered
+     * to its inbox. The relay answers with an `Accept`.
+     */
+    pub fn new_subscribe(&self, id: Option<String>, local_actor: &str) -> Object {
+        let object = match self.kind {
+            RelayKind::Mastodon => PUBLIC,
+            RelayKind::LitePub => self.actor.as_str(),
+        };
+        let mut follow = Object::new_activity(
+            id,
+            vec!["Follow".to_string()],
+            vec![ObjectOrLink::Link(Link::from(local_actor))],
+            vec![ObjectOrLink::Link(Link::from(object))],
+        );
+        follow.object_items.to = vec![ObjectOrLink::Link(Link::from(self.actor.as_str()))];
+        follow
+    }
+
+    /**
+     * The `Undo` of the `Follow` of `new_subscribe`, unsubscribing from the
+     * relay.
+     */
+    pub fn new_unsubscribe(
+        &self,
+        id: Option<String>,
+        follow: &Object,
+    ) -> Result<Object, Box<dyn Error>> {
+        if !follow.typ.iter().any(|typ| typ == "Follow") {
+            return Err("activity is not a Follow".into());
+        }
+        let mut undo = Object::new_activity(
+            id,
+            vec!["Undo".to_string()],
+            follow.activity_items.actor.clone(),
+            vec![ObjectOrLink::Object(follow.clone())],
+        );
+        undo.object_items.to = vec![ObjectOrLink::Link(Link::from(self.actor.as_str()))];
+        Ok(undo)
+    }
+
+    /**
+     * Whether the activity is the relay accepting a subscription.
+     */
+    pub fn is_accept(&self, activity: &Object) -> bool {
+        activity.typ.iter().any(|typ| typ == "Accept")
+            && activity
+                .activity_items
+                .actor
+                .iter()
+                .any(|actor| actor.id() == Some(self.actor.as_str()))
+    }
+
+    /**
+     * The activity or object which an activity delivered by the relay
+     * carries, to be handled as if delivered by its author. `signer` is the
+     * actor whose key signed the request.
+     *
+     * A relay can vouch only for itself, so what it carries is fetched again
+     * from its origin with `resolver`: the object of an `Announce` by the
+     * relay, or an activity forwarded as is. Activities which cannot be
+     * fetched, e.g. most `Delete`s, are rejected.
+     */
+    pub fn unwrap<R: Resolver + ?Sized>(
+        &self,
+        signer: &str,
+        activity: &Object,
+        resolver: &R,
+    ) -> Result<Object, Box<dyn Error>> {
+        if signer != self.actor {
+            return Err(format!("{} is not signed by the relay", signer).into());
+        }
+        let is_by_relay = activity
+            .activity_items
+            .actor
+            .iter()
+            .any(|actor| actor.id() == Some(self.actor.as_str()));
+        let iri = if is_by_relay {
+            if !activity.typ.iter().any(|typ| typ == "Announce") {
+                return Err("activity of the relay is not an Announce".into());
+            }
+            match activity.activity_items.object.as_slice() {
+                [object] => object.id().ok_or("announced object has no id")?,
+                _ => return Err("Announce must have exactly one object".into()),
+            }
+        } else {
+            activity.id.as_deref().ok_or("relayed activity has no id")?
+        };
+        fetch_from_origin(iri, resolver)
+    }
+}
+
+/**
+ * What an activity delivered by one of the relays carries, as
+ * `Relay::unwrap`, or `None` if `signer` is none of them, i.e. the activity
+ * is to be handled as is.
+ */
+pub fn unwrap_relayed<R: Resolver + ?Sized>(
+    relays: &[Relay],
+    signer: &str,
+    activity: &Object,
+    resolver: &R,
+) -> Option<Result<Object, Box<dyn Error>>> {
+    relays
+        .iter()
+        .find(|relay| relay.actor == signer)
+        .map(|relay| relay.unwrap(signer, activity, resolver))
+}
+
+fn fetch_from_origin<R: Resolver + ?Sized>(
+    iri: &str,
+    resolver: &R,
+) -> Result<Object, Box<dyn Error>> {
+    let object = resolver.resolve(iri)?;
+    let id = object.id.as_deref().ok_or("fetched object has no id")?;
+    if !same_origin(id, iri) {
+        return Err(format!("{} is not on the origin of {}", id, iri).into());
+    }
+    let actors = object
+        .activity_items
+        .actor
+        .iter()
+        .chain(&object.object_items.attributed_to)
+        .filter_map(ObjectOrLink::id);
+    for actor in actors {
+        if !same_origin(actor, id) {
+            return Err(format!("{} is not on the origin of {}", id, actor).into());
+        }
+    }
+    Ok(object)
+}
@@ -0,0 +1,109 @@
+use std::error::Error;
+
+use crate::model::{Map, Object, ObjectOrLink};
+
+/**
+ * Looks up objects by IRI, e.g. by fetching them or reading them from a
+ * store.
+ */
+pub trait Resolver {
+    fn resolve(&self, iri: &str) -> Result<Object, Box<dyn Error>>;
+
+    /**
+     * The object of the IRI with its validators, or `NotModified` if it has
+     * not changed since `validators` were given for it. With empty
+     * validators, the object is always resolved. By default the object is
+     * resolved with `resolve` and has no validators, for resolvers which do
+     * not fetch it over HTTP.
+     *
+     * Reference: https://www.rfc-editor.org/rfc/rfc9110#section-13.1
+     */
+    fn resolve_if_modified(
+        &self,
+        iri: &str,
+        _validators: &Validators,
+    ) -> Result<Revalidated, Box<dyn Error>> {
+        Ok(Revalidated::Modified(
+            self.resolve(iri)?,
+            Validators::default(),
+        ))
+    }
+}
+
+impl<F: Fn(&str) -> Result<Object, Box<dyn Error>>> Resolver for F {
+    fn resolve(&self, iri: &str) -> Result<Object, Box<dyn Error>> {
+        self(iri)
+    }
+}
+
+/**
+ * The validators of a fetched object, given back on a later fetch to get it
+ * only if it has changed.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Validators {
+    // The `ETag` header, given back as `If-None-Match`.
+    pub etag: Option<String>,
+    // The `Last-Modified` header, given back as `If-Modified-Since`.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/**
+ * The outcome of `Resolver::resolve_if_modified`.
+ */
+#[derive(PartialEq, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Revalidated {
+    // The object has not changed, e.g. with a `304 Not Modified`.
+    NotModified,
+    Modified(Object, Validators),
+}
+
+/**
+ * Resolves the objects in the map by their IRI.
+ */
+impl Resolver for Map<String, Object> {
+    fn resolve(&self, iri: &str) -> Result<Object, Box<dyn Error>> {
+        self.get(iri)
+            .cloned()
+            .ok_or_else(|| format!("{} is not found", iri).into())
+    }
+}
+
+impl ObjectOrLink {
+    /**
+     * The embedded object, or the object which the link refers to.
+     */
+    pub fn fetch_object<R: Resolver + ?Sized>(
+        &self,
+        resolver: &R,
+    ) -> Result<Object, Box<dyn Error>> {
+        match self {
+            Self::Object(object) => Ok(object.clone()),
+            Self::Link(link) => resolver.resolve(&link.href),
+        }
+    }
+
+    /**
+     * Same as `fetch_object`, but also replaces the link with the fetched
+     * object.
+     */
+    pub fn fetch_object_in_place<R: Resolver + ?Sized>(
+        &mut self,
+        resolver: &R,
+    ) -> Result<&mut Object, Box<dyn Error>> {
+        if let Self::Link(link) = self {
+            *self = Self::Object(resolver.resolve(&link.href)?);
+        }
+        match self {
+            Self::Object(object) => Ok(object),
+            Self::Link(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use std::error::Error;
+
+use url::Url;
+
+use crate::model::{ActorItems, ActorType, Map, Object};
+
+/**
+ * Which IRIs are owned by the server, e.g. to decide which objects are
+ * read from the store and which side effects apply. A server hosting
+ * several tenants owns the IRIs under any of their base URLs.
+ */
+pub trait Locality {
+    /**
+     * The base URL which the IRI is under, if it is local.
+     */
+    fn base_of(&self, iri: &str) -> Option<&Url>;
+
+    fn is_local(&self, iri: &str) -> bool {
+        self.base_of(iri).is_some()
+    }
+}
+
+impl Locality for Url {
+    fn base_of(&self, iri: &str) -> Option<&Url> {
+        is_under(self, iri).then_some(self)
+    }
+}
+
+impl Locality for Routes {
+    fn base_of(&self, iri: &str) -> Option<&Url> {
+        self.base_url.base_of(iri)
+    }
+}
+
+// Whether the IRI is on the origin of the base URL and under its path.
+fn is_under(base: &Url, iri: &str) -> bool {
+    let url = match Url::parse(iri) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    if url.origin() != base.origin() || !url.origin().is_tuple() {
+        return false;
+    }
+    let base_path = base.path().trim_end_matches('/');
+    url.path()
+        .strip_prefix(base_path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/**
+ * The layouts of the tenants of a multi-tenant server, each with its own
+ * base URL, which may share a host under different paths.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Tenants {
+    pub tenants: Vec<Routes>,
+}
+
+impl Tenants {
+    pub fn new(tenants: Vec<Routes>) -> Self {
+        Self { tenants }
+    }
+
+    /**
+     * The tenant owning the IRI, the one with the longest base path if
+     * several do.
+     */
+    pub fn routes_of(&self, iri: &str) -> Option<&Routes> {
+        self.tenants
+            .iter()
+            .filter(|routes| is_under(&routes.base_url, iri))
+            .max_by_key(|routes| routes.base_url.path().trim_end_matches('/').len())
+    }
+
+    /**
+     * The route of an IRI in the layout of its tenant.
+     */
+    pub fn parse(&self, iri: &str) -> Option<Route> {
+        self.routes_of(iri)?.parse(iri)
+    }
+}
+
+impl Locality for Tenants {
+    fn base_of(&self, iri: &str) -> Option<&Url> {
+        self.routes_of(iri).map(|routes| &routes.base_url)
+    }
+}
+
+/**
+ * A path of the instance, with the value which filled its template.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Route {
+    Actor(String),
+    Inbox(String),
+    Outbox(String),
+    Followers(String),
+    Following(String),
+    Object(String),
+    Activity(String),
+    SharedInbox,
+}
+
+/**
+ * The URL layout of an instance. Templates are paths relative to
+ * `base_url` with one placeholder in braces, e.g. `/users/{name}`, except
+ * `shared_inbox` which has none. Values filled in are not escaped, so they
+ * must not contain `/`, `?` or `#`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Routes {
+    pub base_url: Url,
+    pub actor: String,
+    pub inbox: String,
+    pub outbox: String,
+    pub followers: String,
+    pub following: String,
+    pub object: String,
+    pub activity: String,
+    pub shared_inbox: String,
+    // Pages of hashtags, which are not ActivityPub objects.
+    pub tag: String,
+}
+
+impl Routes {
+    /**
+     * The layout of Mastodon, with `/users/{name}` for actors.
+     */
+    pub fn new(base_url: &str) -> Result<Self, Box<dyn Error>> {
+        let base_url = Url::parse(base_url)?;
+        if base_url.cannot_be_a_base() {
+            return Err(format!("{} cannot be a base", base_url).into());
+        }
+        Ok(Self {
+            base_url,
+            actor: "/users/{name}".to_string(),
+            inbox: "/users/{name}/inbox".to_string(),
+            outbox: "/users/{name}/outbox".to_string(),
+            followers: "/users/{name}/followers".to_string(),
+            following: "/users/{name}/following".to_string(),
+            object: "/objects/{id}".to_string(),
+            activity: "/activities/{id}".to_string(),
+            shared_inbox: "/inbox".to_string(),
+            tag: "/tags/{name}".to_string(),
+        })
+    }
+
+    fn url(&self, template: &str, value: &str) -> String {
+        let path = match template.split_once('{') {
+            Some((prefix, rest)) => {
+                let suffix = rest.split_once('}').map_or("", |(_, suffix)| suffix);
+                format!("{}{}{}", prefix, value, suffix)
+            }
+            None => template.to_string(),
+        };
+        let mut url = self.base_url.clone();
+        url.set_path(&format!(
+            "{}{}",
+            self.base_url.path().trim_end_matches('/'),
+            path
+        ));
+        url.to_string()
+    }
+
+    pub fn actor_id(&self, name: &str) -> String {
+        self.url(&self.actor, name)
+    }
+
+    pub fn object_id(&self, id: &str) -> String {
+        self.url(&self.object, id)
+    }
+
+    pub fn activity_id(&self, id: &str) -> String {
+        self.url(&self.activity, id)
+    }
+
+    pub fn shared_inbox_id(&self) -> String {
+        self.url(&self.shared_inbox, "")
+    }
+
+    /**
+     * The page of a hashtag, named without `#`. The name is percent-encoded
+     * as a path.
+     */
+    pub fn tag_url(&self, name: &str) -> String {
+        self.url(&self.tag, name)
+    }
+
+    /**
+     * The collections and endpoints of the actor named `name`.
+     */
+    pub fn actor_items(&self, name: &str) -> ActorItems {
+        let mut endpoints = Map::new();
+        endpoints.insert("sharedInbox".to_string(), self.shared_inbox_id());
+        ActorItems {
+            inbox: self.url(&self.inbox, name),
+            outbox: self.url(&self.outbox, name),
+            following: self.url(&self.following, name),
+            followers: self.url(&self.followers, name),
+            preferred_username: Some(name.to_string()),
+            endpoints,
+        }
+    }
+
+    pub fn new_actor(&self, name: &str, actor_type: ActorType) -> Object {
+        Object::new_actor(self.actor_id(name), actor_type, self.actor_items(name))
+    }
+
+    /**
+     * The route of an IRI of the instance, or `None` if the IRI is not of
+     * the instance or matches no template. Templates are tried from the
+     * shared inbox to the object, so the more specific ones win.
+     */
+    pub fn parse(&self, iri: &str) -> Option<Route> {
+        let url = Url::parse(iri).ok()?;
+        if !is_under(&self.base_url, iri) || url.fragment().is_some() {
+            return None;
+        }
+        let path = url
+            .path()
+            .strip_prefix(self.base_url.path().trim_end_matches('/'))?;
+        let templates = [
+            (&self.inbox, Route::Inbox as fn(String) -> Route),
+            (&self.outbox, Route::Outbox),
+            (&self.followers, Route::Followers),
+            (&self.following, Route::Following),
+            (&self.actor, Route::Actor),
+            (&self.activity, Route::Activity),
+            (&self.object, Route::Object),
+        ];
+        if path == self.shared_inbox {
+            return Some(Route::SharedInbox);
+        }
+        templates
+            .into_iter()
+            .find_map(|(template, route)| match_template(template, path).map(route))
+    }
+}
+
+fn match_template(template: &str, path: &str) -> Option<String> {
+    let (prefix, rest) = template.split_once('{')?;
+    let (_, suffix) = rest.split_once('}')?;
+    let value = path.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    (!value.is_empty() && !value.contains('/')).then(|| value.to_string())
+}
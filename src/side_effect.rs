@@ -0,0 +1,104 @@
+use std::error::Error;
+
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::routes::Locality;
+use crate::store::ObjectStore;
+
+/**
+ * A `replies` collection which a reply was added to.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct RepliesUpdate {
+    // The object replied to.
+    pub object: String,
+    pub total_items: u64,
+}
+
+/**
+ * Adds the reply to the `replies` collection of each object in its
+ * `inReplyTo` which is local and found in `store`.
+ * A collection referenced by its id is read from and written back to
+ * `store`, and an object without `replies` gets an embedded `Collection`.
+ * Returns the collections which changed with their new `totalItems`, e.g.
+ * to notify clients; a reply already in a collection does not change it.
+ */
+pub fn add_reply<S: ObjectStore + ?Sized, L: Locality + ?Sized>(
+    store: &S,
+    local: &L,
+    reply: &Object,
+) -> Result<Vec<RepliesUpdate>, Box<dyn Error>> {
+    let reply_id = reply.id.as_deref().ok_or("reply has no id")?;
+    let mut dest = vec![];
+    for id in reply
+        .object_items
+        .in_reply_to
+        .iter()
+        .filter_map(ObjectOrLink::id)
+        .filter(|id| local.is_local(id))
+    {
+        let mut object = match store.get(id)? {
+            None => continue,
+            Some(object) => object,
+        };
+        let replies = object.object_items.replies.get_or_insert_with(|| {
+            Box::new(Object {
+                schema_context: None,
+                ..Object::new_collection(
+                    None,
+                    vec!["Collection".to_string()],
+                    Some(0),
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    vec![],
+                )
+            })
+        });
+        let total_items = match replies.id.clone().filter(|_| replies.typ.is_empty()) {
+            Some(replies_id) => {
+                let mut replies = store
+                    .get(&replies_id)?
+                    .ok_or_else(|| format!("{} is not found", replies_id))?;
+                let total_items = append(&mut replies, reply_id);
+                if total_items.is_some() {
+                    store.put(replies)?;
+                }
+                total_items
+            }
+            None => {
+                let total_items = append(replies, reply_id);
+                if total_items.is_some() {
+                    store.put(object)?;
+                }
+                total_items
+            }
+        };
+        if let Some(total_items) = total_items {
+            dest.push(RepliesUpdate {
+                object: id.to_string(),
+                total_items,
+            });
+        }
+    }
+    Ok(dest)
+}
+
+// Appends the item unless present, and returns the new `totalItems`.
+fn append(collection: &mut Object, id: &str) -> Option<u64> {
+    let items = if collection.typ.iter().any(|typ| typ == "OrderedCollection") {
+        &mut collection.ordered_collection_items.ordered_items
+    } else {
+        &mut collection.collection_items.items
+    };
+    if items.iter().any(|item| item.id() == Some(id)) {
+        return None;
+    }
+    items.push(ObjectOrLink::Link(Link::from(id)));
+    let total_items = collection
+        .collection_items
+        .total_items
+        .map_or(items.len() as u64, |total_items| total_items + 1);
+    collection.collection_items.total_items = Some(total_items);
+    Some(total_items)
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::sync::RwLock;
+
+use crate::model::Map;
+
+#[cfg(feature = "signing")]
+pub mod request;
+
+/**
+ * The algorithm identifiers of HTTP signatures to sign deliveries with.
+ * Servers differ in which they verify, so the ones to try are configured
+ * with `SignatureConfig`.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum SignatureAlgorithm {
+    // `rsa-sha256` of the draft HTTP signatures, which most servers verify.
+    RsaSha256,
+    // `hs2019` of the draft HTTP signatures, leaving the algorithm to the key.
+    Hs2019,
+    // `rsa-v1_5-sha256` of HTTP Message Signatures.
+    Rfc9421,
+}
+
+impl SignatureAlgorithm {
+    /**
+     * The identifier written in the `algorithm` or `alg` parameter.
+     *
+     * Reference: https://www.rfc-editor.org/rfc/rfc9421#section-6.2.2
+     */
+    pub fn identifier(self) -> &'static str {
+        match self {
+            Self::RsaSha256 => "rsa-sha256",
+            Self::Hs2019 => "hs2019",
+            Self::Rfc9421 => "rsa-v1_5-sha256",
+        }
+    }
+}
+
+/**
+ * Which signature algorithms deliveries are signed with. The `preferred`
+ * ones are tried in order, downgrading to the next one when a peer rejects
+ * a signature, and the one a host accepted is remembered to be tried first
+ * next time. `host_overrides` replace `preferred` for particular hosts.
+ */
+#[derive(Debug)]
+pub struct SignatureConfig {
+    pub preferred: Vec<SignatureAlgorithm>,
+    pub host_overrides: Map<String, Vec<SignatureAlgorithm>>,
+    accepted: RwLock<Map<String, SignatureAlgorithm>>,
+}
+
+impl Default for SignatureConfig {
+    fn default() -> Self {
+        Self::new(vec![SignatureAlgorithm::RsaSha256])
+    }
+}
+
+impl SignatureConfig {
+    pub fn new(preferred: Vec<SignatureAlgorithm>) -> Self {
+        Self {
+            preferred,
+            host_overrides: Map::new(),
+            accepted: RwLock::new(Map::new()),
+        }
+    }
+
+    /**
+     * The algorithms to try for the host in order: the one it accepted
+     * before, then the configured ones.
+     */
+    pub fn algorithms_for(&self, host: &str) -> Vec<SignatureAlgorithm> {
+        let configured = self.host_overrides.get(host).unwrap_or(&self.preferred);
+        let accepted = self
+            .accepted
+            .read()
+            .ok()
+            .and_then(|accepted| accepted.get(host).copied())
+            .filter(|algorithm| configured.contains(algorithm));
+        let mut dest: Vec<SignatureAlgorithm> = accepted.into_iter().collect();
+        for algorithm in configured {
+            if !dest.contains(algorithm) {
+                dest.push(*algorithm);
+            }
+        }
+        dest
+    }
+
+    /**
+     * Remembers that the host accepted a signature of the algorithm.
+     */
+    pub fn accept(&self, host: &str, algorithm: SignatureAlgorithm) {
+        if let Ok(mut accepted) = self.accepted.write() {
+            accepted.insert(host.to_string(), algorithm);
+        }
+    }
+
+    /**
+     * Sends a delivery to the host with `send`, which signs it with the
+     * given algorithm and returns the status of the response. A rejected
+     * signature, i.e. `401` or `403`, is retried with the next algorithm,
+     * and the status of the last attempt is returned.
+     */
+    pub fn negotiate<F>(&self, host: &str, mut send: F) -> Result<u16, Box<dyn Error>>
+    where
+        F: FnMut(SignatureAlgorithm) -> Result<u16, Box<dyn Error>>,
+    {
+        let mut status = None;
+        for algorithm in self.algorithms_for(host) {
+            let sent = send(algorithm)?;
+            status = Some(sent);
+            if (200..300).contains(&sent) {
+                self.accept(host, algorithm);
+            }
+            if !is_signature_rejected(sent) {
+                break;
+            }
+        }
+        status.ok_or_else(|| format!("no signature algorithm for {}", host).into())
+    }
+}
+
+/**
+ * Whether the status of a response to a signed request means that the
+ * signature was not accepted, which servers tell by `401`, or `403` for
+ * some.
+ */
+pub fn is_signature_rejected(status: u16) -> bool {
+    matches!(status, 401 | 403)
+}
@@ -0,0 +1,312 @@
+use std::error::Error;
+use std::fmt;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::digest::{digest, SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use url::Url;
+use zeroize::Zeroizing;
+
+use super::SignatureAlgorithm;
+use crate::clock::Clock;
+use crate::json::{JsonSerde, SerializeOptions};
+use crate::model::Object;
+
+/**
+ * Signs requests with the RSA key of an actor. The key is kept as DER in a
+ * buffer which is zeroed on drop, and is left out of `Debug`. ring does not
+ * zero the keys it parses, so the key is parsed for each signature and the
+ * parsed key dropped right after, which costs a parse per request.
+ */
+pub struct Signer {
+    // The id of the public key, e.g. `https://example.com/users/alice#main-key`.
+    pub key_id: String,
+    format: KeyFormat,
+    der: Zeroizing<Vec<u8>>,
+    // The PKCS#1 DER of the public key.
+    public_key: Vec<u8>,
+}
+
+// The encoding of the DER of a private key.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum KeyFormat {
+    Pkcs8,
+    Pkcs1,
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/**
+ * Signers are equal if they have the same key id and public key.
+ */
+impl PartialEq for Signer {
+    fn eq(&self, other: &Self) -> bool {
+        self.key_id == other.key_id && self.public_key == other.public_key
+    }
+}
+
+impl Eq for Signer {}
+
+impl Signer {
+    /**
+     * A signer of a PEM encoded RSA private key, either PKCS#8
+     * (`PRIVATE KEY`) or PKCS#1 (`RSA PRIVATE KEY`). The caller's PEM string
+     * is left as it is.
+     */
+    pub fn from_pem(key_id: &str, private_key_pem: &str) -> Result<Self, Box<dyn Error>> {
+        let pem = Pem::decode(private_key_pem)?;
+        let format = match pem.label.as_str() {
+            "PRIVATE KEY" => KeyFormat::Pkcs8,
+            "RSA PRIVATE KEY" => KeyFormat::Pkcs1,
+            label => return Err(format!("{} is not an RSA private key", label).into()),
+        };
+        let public_key = parse_key(format, &pem.der)?.public().as_ref().to_vec();
+        Ok(Self {
+            key_id: key_id.to_string(),
+            format,
+            der: pem.der,
+            public_key,
+        })
+    }
+
+    /**
+     * Whether the PEM encoded public key, either SPKI (`PUBLIC KEY`) or
+     * PKCS#1 (`RSA PUBLIC KEY`), is the one of the private key. A PEM which
+     * does not parse as either never matches.
+     */
+    pub fn matches_public_key_pem(&self, public_key_pem: &str) -> bool {
+        let pem = match Pem::decode(public_key_pem) {
+            Ok(pem) => pem,
+            Err(_) => return false,
+        };
+        let der = match pem.label.as_str() {
+            "PUBLIC KEY" => spki_rsa_public_key(&pem.der),
+            "RSA PUBLIC KEY" => Some(pem.der.as_slice()),
+            _ => None,
+        };
+        der == Some(self.public_key.as_slice())
+    }
+
+    /**
+     * The RSASSA-PKCS1-v1_5 signature of the message with SHA-256, which
+     * all the algorithms of `SignatureAlgorithm` sign with for RSA keys.
+     */
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let key_pair = parse_key(self.format, &self.der)?;
+        let mut signature = vec![0; key_pair.public().modulus_len()];
+        key_pair
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &SystemRandom::new(),
+                message,
+                &mut signature,
+            )
+            .map_err(|err| format!("failed to sign: {}", err))?;
+        Ok(signature)
+    }
+}
+
+fn parse_key(format: KeyFormat, der: &[u8]) -> Result<RsaKeyPair, Box<dyn Error>> {
+    match format {
+        KeyFormat::Pkcs8 => RsaKeyPair::from_pkcs8(der),
+        KeyFormat::Pkcs1 => RsaKeyPair::from_der(der),
+    }
+    .map_err(|err| format!("invalid private key: {}", err).into())
+}
+
+// The DER object identifier of `rsaEncryption`, 1.2.840.113549.1.1.1.
+const RSA_ENCRYPTION: &[u8] = &[
+    0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01,
+];
+
+// The PKCS#1 `RSAPublicKey` in the bit string of an RSA
+// `SubjectPublicKeyInfo`.
+fn spki_rsa_public_key(der: &[u8]) -> Option<&[u8]> {
+    let (0x30, spki, []) = der_element(der)? else {
+        return None;
+    };
+    let (0x30, algorithm, rest) = der_element(spki)? else {
+        return None;
+    };
+    if !algorithm.starts_with(RSA_ENCRYPTION) {
+        return None;
+    }
+    match der_element(rest)? {
+        (0x03, [0, key @ ..], []) => Some(key),
+        _ => None,
+    }
+}
+
+// The tag, contents and rest of the first DER element.
+fn der_element(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = der.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+            return None;
+        }
+        let (bytes, rest) = rest.split_at(count);
+        let len = bytes
+            .iter()
+            .fold(0usize, |len, &byte| (len << 8) | byte as usize);
+        (len, rest)
+    };
+    (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+// A decoded PEM document, zeroed when dropped.
+struct Pem {
+    label: String,
+    der: Zeroizing<Vec<u8>>,
+}
+
+impl Pem {
+    fn decode(pem: &str) -> Result<Self, Box<dyn Error>> {
+        let pem = pem.trim();
+        let label = pem
+            .strip_prefix("-----BEGIN ")
+            .and_then(|rest| rest.split_once("-----"))
+            .map(|(label, _)| label.to_string())
+            .ok_or("not a PEM document")?;
+        let body: Zeroizing<String> = Zeroizing::new(
+            pem.lines()
+                .filter(|line| !line.starts_with("-----"))
+                .map(str::trim)
+                .collect(),
+        );
+        let der = Zeroizing::new(STANDARD.decode(body.as_bytes())?);
+        Ok(Self { label, der })
+    }
+}
+
+/**
+ * A signed request, to be sent as is with any HTTP client.
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SignedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl SignedRequest {
+    /**
+     * The value of a header, by its name in any case.
+     */
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/**
+ * A `POST` of the activity to the inbox, written with `options`, e.g. the
+ * ones of the peer, and signed with the algorithm, at the time of `clock`.
+ */
+pub fn signed_delivery(
+    signer: &Signer,
+    algorithm: SignatureAlgorithm,
+    inbox: &str,
+    activity: &Object,
+    options: &SerializeOptions,
+    clock: &dyn Clock,
+) -> Result<SignedRequest, Box<dyn Error>> {
+    let body = activity.to_json_bytes_with(options)?;
+    signed_post(signer, algorithm, inbox, body, clock)
+}
+
+/**
+ * A `POST` of an ActivityStreams document to the URL, signed with the
+ * algorithm. The draft HTTP signatures sign `(request-target)`, `host`,
+ * `date` and `digest` as Mastodon expects, and HTTP Message Signatures the
+ * method, the target URI, `content-digest` and `content-type`.
+ *
+ * Reference: https://docs.joinmastodon.org/spec/security/#http
+ * Reference: https://www.rfc-editor.org/rfc/rfc9421
+ */
+pub fn signed_post(
+    signer: &Signer,
+    algorithm: SignatureAlgorithm,
+    url: &str,
+    body: Vec<u8>,
+    clock: &dyn Clock,
+) -> Result<SignedRequest, Box<dyn Error>> {
+    let url = Url::parse(url)?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(format!("{} has no host", url).into()),
+    };
+    let now = clock.now();
+    let date = now.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let content_type = "application/activity+json";
+    let hash = STANDARD.encode(digest(&SHA256, &body));
+    let mut headers = vec![
+        ("Host".to_string(), host.clone()),
+        ("Date".to_string(), date.clone()),
+        ("Content-Type".to_string(), content_type.to_string()),
+    ];
+
+    match algorithm {
+        SignatureAlgorithm::RsaSha256 | SignatureAlgorithm::Hs2019 => {
+            let digest = format!("SHA-256={}", hash);
+            let mut target = url.path().to_string();
+            if let Some(query) = url.query() {
+                target = format!("{}?{}", target, query);
+            }
+            let signing_string = format!(
+                "(request-target): post {}\nhost: {}\ndate: {}\ndigest: {}",
+                target, host, date, digest
+            );
+            let signature = STANDARD.encode(signer.sign(signing_string.as_bytes())?);
+            headers.push(("Digest".to_string(), digest));
+            headers.push((
+                "Signature".to_string(),
+                format!(
+                    r#"keyId="{}",algorithm="{}",headers="(request-target) host date digest",signature="{}""#,
+                    signer.key_id,
+                    algorithm.identifier(),
+                    signature
+                ),
+            ));
+        }
+        SignatureAlgorithm::Rfc9421 => {
+            let content_digest = format!("sha-256=:{}:", hash);
+            let params = format!(
+                r#"("@method" "@target-uri" "content-digest" "content-type");created={};keyid="{}";alg="{}""#,
+                now.timestamp(),
+                signer.key_id,
+                algorithm.identifier()
+            );
+            let signature_base = format!(
+                "\"@method\": POST\n\"@target-uri\": {}\n\"content-digest\": {}\n\"content-type\": {}\n\"@signature-params\": {}",
+                url, content_digest, content_type, params
+            );
+            let signature = STANDARD.encode(signer.sign(signature_base.as_bytes())?);
+            headers.push(("Content-Digest".to_string(), content_digest));
+            headers.push(("Signature-Input".to_string(), format!("sig1={}", params)));
+            headers.push(("Signature".to_string(), format!("sig1=:{}:", signature)));
+        }
+    }
+
+    Ok(SignedRequest {
+        method: "POST".to_string(),
+        url: url.into(),
+        headers,
+        body,
+    })
+}
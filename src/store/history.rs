@@ -0,0 +1,208 @@
+use std::error::Error;
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+
+use crate::model::{Map, Object, ObjectOrLink};
+use crate::store::ObjectStore;
+
+/**
+ * Storage of the prior revisions of objects, oldest first.
+ */
+pub trait HistoryStore: Send + Sync {
+    fn push(&self, id: &str, revision: Object) -> Result<(), Box<dyn Error>>;
+
+    fn revisions(&self, id: &str) -> Result<Vec<Object>, Box<dyn Error>>;
+
+    fn clear(&self, id: &str) -> Result<(), Box<dyn Error>>;
+}
+
+/**
+ * A history in memory, for tests and small tools.
+ */
+#[derive(Debug, Default)]
+pub struct MemoryHistory {
+    revisions: RwLock<Map<String, Vec<Object>>>,
+}
+
+impl MemoryHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl HistoryStore for MemoryHistory {
+    fn push(&self, id: &str, revision: Object) -> Result<(), Box<dyn Error>> {
+        let mut revisions = self.revisions.write().map_err(|err| err.to_string())?;
+        revisions.entry(id.to_string()).or_default().push(revision);
+        Ok(())
+    }
+
+    fn revisions(&self, id: &str) -> Result<Vec<Object>, Box<dyn Error>> {
+        let revisions = self.revisions.read().map_err(|err| err.to_string())?;
+        Ok(revisions.get(id).cloned().unwrap_or_default())
+    }
+
+    fn clear(&self, id: &str) -> Result<(), Box<dyn Error>> {
+        let mut revisions = self.revisions.write().map_err(|err| err.to_string())?;
+        revisions.shift_remove(id);
+        Ok(())
+    }
+}
+
+/**
+ * One version of an object in its edit history, as Mastodon shows them.
+ *
+ * Reference: https://docs.joinmastodon.org/entities/StatusEdit/
+ */
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Edit {
+    pub content: Option<String>,
+    pub summary: Option<String>,
+    pub sensitive: bool,
+    // `updated` of the version, or `published` of the first one.
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Edit {
+    fn of(object: &Object) -> Self {
+        let items = &object.object_items;
+        Self {
+            content: items.content.first().cloned(),
+            summary: items.summary.first().cloned(),
+            sensitive: object.activity_streams_ext_items.sensitive == Some(true),
+            created_at: items.updated.or(items.published),
+        }
+    }
+}
+
+// The properties which the author changes by editing an object. Others,
+// e.g. `replies` or the votes on poll options, change without an edit.
+#[derive(PartialEq)]
+struct Edited<'a> {
+    content: &'a [String],
+    content_map: &'a Map<String, String>,
+    summary: &'a [String],
+    summary_map: &'a Map<String, String>,
+    name: &'a [String],
+    name_map: &'a Map<String, String>,
+    sensitive: bool,
+    attachment: &'a [ObjectOrLink],
+    tag: &'a [ObjectOrLink],
+    // The names of the poll options, in `oneOf` then `anyOf`.
+    #[cfg(feature = "question")]
+    options: Vec<&'a [String]>,
+}
+
+impl<'a> Edited<'a> {
+    fn of(object: &'a Object) -> Self {
+        let items = &object.object_items;
+        Self {
+            content: &items.content,
+            content_map: &items.content_map,
+            summary: &items.summary,
+            summary_map: &items.summary_map,
+            name: &items.name,
+            name_map: &items.name_map,
+            sensitive: object.activity_streams_ext_items.sensitive == Some(true),
+            attachment: &items.attachment,
+            tag: &items.tag,
+            #[cfg(feature = "question")]
+            options: object
+                .question_items
+                .one_of
+                .iter()
+                .chain(&object.question_items.any_of)
+                .map(|option| match option {
+                    ObjectOrLink::Object(option) => option.object_items.name.as_slice(),
+                    ObjectOrLink::Link(_) => &[],
+                })
+                .collect(),
+        }
+    }
+}
+
+/**
+ * A store which keeps the prior revisions of objects replaced by `put` in
+ * a history store. Only edits make a revision: changes to the content,
+ * summary or name in any language, the sensitivity, attachments, tags or
+ * poll options. So e.g. adding a reply to the `replies` of an object or a
+ * vote to its poll is not taken as an edit of it. Deleting an object drops
+ * its history.
+ */
+#[derive(Debug, Default)]
+pub struct VersionedStore<S, H> {
+    pub store: S,
+    pub history: H,
+}
+
+impl<S: ObjectStore, H: HistoryStore> VersionedStore<S, H> {
+    pub fn new(store: S, history: H) -> Self {
+        Self { store, history }
+    }
+
+    /**
+     * Stores the object embedded in an `Update`, and returns it.
+     */
+    pub fn apply_update(&self, activity: &Object) -> Result<Object, Box<dyn Error>> {
+        if !activity.typ.iter().any(|typ| typ == "Update") {
+            return Err("activity is not an Update".into());
+        }
+        let object = match activity.activity_items.object.as_slice() {
+            [ObjectOrLink::Object(object)] => object.clone(),
+            _ => return Err("Update must embed exactly one object".into()),
+        };
+        self.put(object.clone())?;
+        Ok(object)
+    }
+
+    /**
+     * The revisions of the object, oldest first and ending with the current
+     * one.
+     */
+    pub fn history(&self, id: &str) -> Result<Vec<Object>, Box<dyn Error>> {
+        let mut dest = self.history.revisions(id)?;
+        dest.extend(self.store.get(id)?);
+        Ok(dest)
+    }
+
+    /**
+     * The edit history of the object, oldest first. An object never edited
+     * has no history.
+     */
+    pub fn edits(&self, id: &str) -> Result<Vec<Edit>, Box<dyn Error>> {
+        let history = self.history(id)?;
+        if history.len() < 2 {
+            return Ok(vec![]);
+        }
+        Ok(history.iter().map(Edit::of).collect())
+    }
+}
+
+impl<S: ObjectStore, H: HistoryStore> ObjectStore for VersionedStore<S, H> {
+    fn get(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>> {
+        self.store.get(id)
+    }
+
+    fn put(&self, object: Object) -> Result<(), Box<dyn Error>> {
+        let id = object.id.clone().ok_or("object has no id")?;
+        if let Some(current) = self.store.get(&id)? {
+            if current == object {
+                return Ok(());
+            }
+            if Edited::of(&current) != Edited::of(&object) {
+                self.history.push(&id, current)?;
+            }
+        }
+        self.store.put(object)
+    }
+
+    fn delete(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>> {
+        self.history.clear(id)?;
+        self.store.delete(id)
+    }
+
+    fn ids_by_actor(&self, actor: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        self.store.ids_by_actor(actor)
+    }
+}
@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::sync::RwLock;
+
+use crate::model::{Map, Object};
+
+pub mod history;
+
+/**
+ * Storage of the objects owned or cached by the server, keyed by their id.
+ * Methods take `&self`, so implementations manage their own locking, e.g.
+ * with a connection pool of a database.
+ */
+pub trait ObjectStore: Send + Sync {
+    fn get(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>>;
+
+    /**
+     * Inserts or replaces the object with the same id.
+     */
+    fn put(&self, object: Object) -> Result<(), Box<dyn Error>>;
+
+    /**
+     * Removes the object and returns it, if any.
+     */
+    fn delete(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>>;
+
+    /**
+     * The ids of the objects by the actor, i.e. with it in `attributedTo`
+     * or `actor`. Stores which can not look them up return an error.
+     */
+    fn ids_by_actor(&self, actor: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        Err(format!("objects by {} can not be looked up", actor).into())
+    }
+}
+
+/**
+ * A store in memory, for tests and small tools.
+ */
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    objects: RwLock<Map<String, Object>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.read().map_or(0, |objects| objects.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ObjectStore for MemoryStore {
+    fn get(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>> {
+        let objects = self.objects.read().map_err(|err| err.to_string())?;
+        Ok(objects.get(id).cloned())
+    }
+
+    fn put(&self, object: Object) -> Result<(), Box<dyn Error>> {
+        let id = object.id.clone().ok_or("object has no id")?;
+        let mut objects = self.objects.write().map_err(|err| err.to_string())?;
+        objects.insert(id, object);
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<Option<Object>, Box<dyn Error>> {
+        let mut objects = self.objects.write().map_err(|err| err.to_string())?;
+        Ok(objects.shift_remove(id))
+    }
+    fn ids_by_actor(&self, actor: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let objects = self.objects.read().map_err(|err| err.to_string())?;
+        Ok(objects
+            .iter()
+            .filter(|(_, object)| {
+                object
+                    .object_items
+                    .attributed_to
+                    .iter()
+                    .chain(&object.activity_items.actor)
+                    .any(|by| by.id() == Some(actor))
+            })
+            .map(|(id, _)| id.clone())
+            .collect())
+    }
+}
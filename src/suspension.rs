@@ -0,0 +1,152 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+#[cfg(not(feature = "mastodon"))]
+use serde_json::Value;
+
+use crate::delivery::PUBLIC;
+use crate::model::{Link, Object, ObjectOrLink};
+use crate::store::ObjectStore;
+
+/**
+ * How a remote actor went away.
+ */
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ActorRemoval {
+    // Suspended by its server, which may lift it later.
+    Suspended,
+    Deleted,
+}
+
+/**
+ * Whether the actor is marked as suspended with `toot:suspended`.
+ *
+ * Reference: https://docs.joinmastodon.org/spec/activitypub/#toot
+ */
+pub fn is_suspended(actor: &Object) -> bool {
+    #[cfg(feature = "mastodon")]
+    return actor.mastodon_ext_items.suspended == Some(true);
+    #[cfg(not(feature = "mastodon"))]
+    return actor.json_items.unknown_properties.get("suspended") == Some(&Value::Bool(true));
+}
+
+/**
+ * Marks a local actor as suspended, setting `updated` to `now` unless it
+ * already was. Returns whether it changed, i.e. an `Update` of it from
+ * `new_suspension_update` is to be delivered.
+ */
+#[cfg(feature = "mastodon")]
+pub fn suspend(actor: &mut Object, now: DateTime<Utc>) -> bool {
+    actor.modify(now, |actor| actor.mastodon_ext_items.suspended = Some(true))
+}
+
+/**
+ * Lifts the suspension of a local actor, as `suspend`.
+ */
+#[cfg(feature = "mastodon")]
+pub fn unsuspend(actor: &mut Object, now: DateTime<Utc>) -> bool {
+    actor.modify(now, |actor| actor.mastodon_ext_items.suspended = None)
+}
+
+/**
+ * An `Update` of the suspended actor by itself, addressed to the public, as
+ * Mastodon federates suspensions.
+ */
+pub fn new_suspension_update(id: Option<String>, actor: &Object) -> Result<Object, Box<dyn Error>> {
+    let actor_id = actor.id.as_deref().ok_or("actor has no id")?;
+    let mut activity = Object::new_update(id, actor_id, actor)?;
+    activity.object_items.to = vec![ObjectOrLink::Link(Link::from(PUBLIC))];
+    Ok(activity)
+}
+
+/**
+ * A `Delete` of the actor by itself, addressed to the public, which peers
+ * take as the removal of the actor and all of its objects.
+ */
+pub fn new_actor_delete(id: Option<String>, actor: &str) -> Object {
+    let mut activity = Object::new_activity(
+        id,
+        vec!["Delete".to_string()],
+        vec![ObjectOrLink::Link(Link::from(actor))],
+        vec![ObjectOrLink::Link(Link::from(actor))],
+    );
+    activity.object_items.to = vec![ObjectOrLink::Link(Link::from(PUBLIC))];
+    activity
+}
+
+/**
+ * A `Tombstone` in place of the object, deleted at `now`. Only the id and
+ * `@context` are kept.
+ */
+pub fn tombstone(object: &Object, now: DateTime<Utc>) -> Object {
+    let mut dest = Object::new(object.id.clone(), vec!["Tombstone".to_string()]);
+    dest.schema_context.clone_from(&object.schema_context);
+    dest.tombstone_items.deleted = Some(now);
+    dest
+}
+
+/**
+ * The actor which the activity removes and how: a `Delete` of its own
+ * actor, or an `Update` of its own actor marking it suspended. Checking
+ * that the activity is really by the actor, e.g. by its signature, is up to
+ * the caller.
+ */
+pub fn actor_removal(activity: &Object) -> Option<(&str, ActorRemoval)> {
+    let items = &activity.activity_items;
+    let actor = match items.actor.as_slice() {
+        [actor] => actor.id()?,
+        _ => return None,
+    };
+    let object = match items.object.as_slice() {
+        [object] if object.id() == Some(actor) => object,
+        _ => return None,
+    };
+    let has_type = |name: &str| activity.typ.iter().any(|typ| typ == name);
+    if has_type("Delete") {
+        return Some((actor, ActorRemoval::Deleted));
+    }
+    match object {
+        ObjectOrLink::Object(object) if has_type("Update") && is_suspended(object) => {
+            Some((actor, ActorRemoval::Suspended))
+        }
+        _ => None,
+    }
+}
+
+/**
+ * Applies an incoming suspension or deletion of a remote actor, found by
+ * `actor_removal`, to `store`. The objects by the actor are replaced with
+ * tombstones, removing their revisions, and so is the actor if it is
+ * deleted; a suspended actor is stored as in the `Update`. Returns the ids
+ * of the objects tombstoned, none for other activities.
+ */
+pub fn apply_actor_removal<S: ObjectStore + ?Sized>(
+    store: &S,
+    activity: &Object,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let (actor, removal) = match actor_removal(activity) {
+        Some(removal) => removal,
+        None => return Ok(vec![]),
+    };
+    let mut ids = store.ids_by_actor(actor)?;
+    match removal {
+        ActorRemoval::Deleted => ids.push(actor.to_string()),
+        ActorRemoval::Suspended => {
+            if let Some(ObjectOrLink::Object(object)) = activity.activity_items.object.first() {
+                store.put(object.clone())?;
+            }
+        }
+    }
+    let mut dest = vec![];
+    for id in ids {
+        let object = match store.get(&id)? {
+            Some(object) if !object.typ.iter().any(|typ| typ == "Tombstone") => object,
+            _ => continue,
+        };
+        store.delete(&id)?;
+        store.put(tombstone(&object, now))?;
+        dest.push(id);
+    }
+    Ok(dest)
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::{collection, option, prelude::*, sample};
+
+pub use crate::delivery::PUBLIC;
+use crate::model::{ActorItems, ActorType, Link, Map, Object, ObjectOrLink};
+
+/**
+ * IRIs of resources on a few hosts under `example`.
+ */
+pub fn iri() -> impl Strategy<Value = String> {
+    "https://(a|b|c)\\.example/[a-z0-9]{1,12}"
+}
+
+/**
+ * Dates between 1970 and 2100, with seconds precision as most servers write.
+ */
+pub fn datetime() -> impl Strategy<Value = DateTime<Utc>> {
+    (0_i64..4_102_444_800).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+/**
+ * Actors with the properties required by ActivityPub.
+ *
+ * Reference: https://www.w3.org/TR/activitypub/#actor-objects
+ */
+pub fn actor() -> impl Strategy<Value = Object> {
+    (
+        iri(),
+        sample::select(ActorType::ALL.to_vec()),
+        "[a-z][a-z0-9_]{0,15}",
+        option::of("[A-Za-z][A-Za-z ]{0,31}"),
+    )
+        .prop_map(|(id, actor_type, username, name)| {
+            let mut actor = Object::new_actor(
+                id.clone(),
+                actor_type,
+                ActorItems {
+                    inbox: format!("{}/inbox", id),
+                    outbox: format!("{}/outbox", id),
+                    following: format!("{}/following", id),
+                    followers: format!("{}/followers", id),
+                    preferred_username: Some(username),
+                    endpoints: Map::new(),
+                },
+            );
+            actor.object_items.name = name.into_iter().collect();
+            actor
+        })
+}
+
+/**
+ * Public notes, possibly in reply to another object.
+ */
+pub fn note() -> impl Strategy<Value = Object> {
+    (
+        iri(),
+        iri(),
+        "[A-Za-z0-9 .,!?]{1,64}",
+        datetime(),
+        option::of(iri()),
+    )
+        .prop_map(|(id, author, text, published, in_reply_to)| {
+            let mut note = Object::new(Some(id), vec!["Note".to_string()]);
+            let items = &mut note.object_items;
+            items.attributed_to = vec![reference(author.clone())];
+            items.content = vec![format!("<p>{}</p>", text)];
+            items.published = Some(published);
+            items.to = vec![reference(PUBLIC.to_string())];
+            items.cc = vec![reference(format!("{}/followers", author))];
+            items.in_reply_to = in_reply_to.into_iter().map(reference).collect();
+            note
+        })
+}
+
+/**
+ * Activities by an actor. `Create` and `Update` embed a note, the other
+ * types refer to their object by IRI.
+ */
+pub fn activity() -> impl Strategy<Value = Object> {
+    (
+        iri(),
+        iri(),
+        sample::select(vec![
+            "Create", "Update", "Delete", "Announce", "Like", "Follow", "Undo",
+        ]),
+        note(),
+        datetime(),
+    )
+        .prop_map(|(id, actor, typ, note, published)| {
+            let mut activity = Object::new(Some(id), vec![typ.to_string()]);
+            activity.activity_items.actor = vec![reference(actor)];
+            activity.object_items.published = Some(published);
+            activity.activity_items.object = match typ {
+                "Create" | "Update" => {
+                    vec![ObjectOrLink::Object(note.clone_without_schema_context())]
+                }
+                _ => vec![reference(note.id.unwrap_or_default())],
+            };
+            activity
+        })
+}
+
+/**
+ * Ordered collections of IRIs with a consistent `totalItems`.
+ */
+pub fn collection() -> impl Strategy<Value = Object> {
+    (iri(), collection::vec(iri(), 0..8)).prop_map(|(id, items)| {
+        let mut collection = Object::new(Some(id), vec!["OrderedCollection".to_string()]);
+        collection.collection_items.total_items = Some(items.len() as u64);
+        collection.ordered_collection_items.ordered_items =
+            items.into_iter().map(reference).collect();
+        collection
+    })
+}
+
+/**
+ * Any of the actors, notes, activities and collections above.
+ */
+pub fn object() -> impl Strategy<Value = Object> {
+    prop_oneof![actor(), note(), activity(), collection()]
+}
+
+fn reference(iri: String) -> ObjectOrLink {
+    ObjectOrLink::Link(Link::from(iri))
+}
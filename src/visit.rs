@@ -0,0 +1,195 @@
+use crate::model::{Link, Object, ObjectOrLink};
+
+/**
+ * Callbacks of `walk`. Objects are visited before the ones embedded in them.
+ * IRIs are the `id` of objects, the `href` of links and the other properties
+ * whose values are IRIs, e.g. `inbox`.
+ */
+pub trait Visitor {
+    fn visit_object(&mut self, _object: &Object) {}
+    fn visit_link(&mut self, _link: &Link) {}
+    fn visit_iri(&mut self, _iri: &str) {}
+}
+
+/**
+ * Callbacks of `walk_mut`, which can modify the tree, e.g. to rewrite ids.
+ * Changes made in `visit_object` are seen when the fields of the object are
+ * walked.
+ */
+pub trait VisitorMut {
+    fn visit_object(&mut self, _object: &mut Object) {}
+    fn visit_link(&mut self, _link: &mut Link) {}
+    fn visit_iri(&mut self, _iri: &mut String) {}
+}
+
+macro_rules! fields {
+    ($values:ident, $objects:ident, $($mut:tt)?) => {
+        /**
+         * Values of the properties which hold objects or links, with the
+         * names of the properties.
+         */
+        pub fn $values(
+            object: &$($mut)? Object,
+        ) -> impl Iterator<Item = (&'static str, &$($mut)? ObjectOrLink)> {
+            let object_items = &$($mut)? object.object_items;
+            let activity_items = &$($mut)? object.activity_items;
+            let collection_items = &$($mut)? object.collection_items;
+            let collection_page_items = &$($mut)? object.collection_page_items;
+            let values = [
+                ("attachment", &$($mut)? object_items.attachment),
+                ("attributedTo", &$($mut)? object_items.attributed_to),
+                ("audience", &$($mut)? object_items.audience),
+                ("bcc", &$($mut)? object_items.bcc),
+                ("bto", &$($mut)? object_items.bto),
+                ("cc", &$($mut)? object_items.cc),
+                ("context", &$($mut)? object_items.context),
+                ("generator", &$($mut)? object_items.generator),
+                ("icon", &$($mut)? object_items.icon),
+                ("image", &$($mut)? object_items.image),
+                ("inReplyTo", &$($mut)? object_items.in_reply_to),
+                ("location", &$($mut)? object_items.location),
+                ("preview", &$($mut)? object_items.preview),
+                ("tag", &$($mut)? object_items.tag),
+                ("to", &$($mut)? object_items.to),
+                ("actor", &$($mut)? activity_items.actor),
+                ("instrument", &$($mut)? activity_items.instrument),
+                ("origin", &$($mut)? activity_items.origin),
+                ("object", &$($mut)? activity_items.object),
+                ("result", &$($mut)? activity_items.result),
+                ("target", &$($mut)? activity_items.target),
+                ("items", &$($mut)? collection_items.items),
+                ("orderedItems", &$($mut)? object.ordered_collection_items.ordered_items),
+                #[cfg(feature = "question")]
+                ("oneOf", &$($mut)? object.question_items.one_of),
+                #[cfg(feature = "question")]
+                ("anyOf", &$($mut)? object.question_items.any_of),
+            ];
+            let boxed_values = [
+                ("likes", &$($mut)? object_items.likes),
+                ("shares", &$($mut)? object_items.shares),
+                ("current", &$($mut)? collection_items.current),
+                ("first", &$($mut)? collection_items.first),
+                ("last", &$($mut)? collection_items.last),
+                ("next", &$($mut)? collection_page_items.next),
+                ("prev", &$($mut)? collection_page_items.prev),
+                ("partOf", &$($mut)? collection_page_items.part_of),
+                ("subject", &$($mut)? object.relationship_items.subject),
+            ];
+            values
+                .into_iter()
+                .flat_map(|(name, values)| values.into_iter().map(move |value| (name, value)))
+                .chain(boxed_values.into_iter().flat_map(|(name, value)| {
+                    value.into_iter().map(move |value| (name, &$($mut)? **value))
+                }))
+        }
+
+        /**
+         * Objects of the properties which only hold objects, with the names
+         * of the properties.
+         */
+        pub fn $objects(
+            object: &$($mut)? Object,
+        ) -> impl Iterator<Item = (&'static str, &$($mut)? Object)> {
+            let object_items = &$($mut)? object.object_items;
+            let boxed_objects = [
+                ("replies", &$($mut)? object_items.replies),
+                ("describes", &$($mut)? object_items.describes),
+            ];
+            let objects = [
+                ("relationship", &$($mut)? object.relationship_items.relationship),
+                ("formerType", &$($mut)? object.tombstone_items.former_type),
+            ];
+            boxed_objects
+                .into_iter()
+                .flat_map(|(name, value)| {
+                    value.into_iter().map(move |value| (name, &$($mut)? **value))
+                })
+                .chain(
+                    objects
+                        .into_iter()
+                        .flat_map(|(name, values)| values.into_iter().map(move |value| (name, value))),
+                )
+        }
+    };
+}
+
+fields!(values, objects,);
+fields!(values_mut, objects_mut, mut);
+
+macro_rules! walker {
+    ($walk_object:ident, $walk_link:ident, $walk_value:ident, $values:ident, $objects:ident, $visitor:ident, $($mut:tt)?) => {
+        pub fn $walk_object<V: $visitor + ?Sized>(object: &$($mut)? Object, visitor: &mut V) {
+            visitor.visit_object(&$($mut)? *object);
+
+            if let Some(id) = &$($mut)? object.id {
+                visitor.visit_iri(id);
+            }
+            for (_, value) in $values(&$($mut)? *object) {
+                $walk_value(value, visitor);
+            }
+            if let Some(url) = &$($mut)? object.object_items.url {
+                $walk_link(url, visitor);
+            }
+            for (_, embedded) in $objects(&$($mut)? *object) {
+                $walk_object(embedded, visitor);
+            }
+
+            if let Some(actor_items) = &$($mut)? object.actor_items {
+                visitor.visit_iri(&$($mut)? actor_items.inbox);
+                visitor.visit_iri(&$($mut)? actor_items.outbox);
+                visitor.visit_iri(&$($mut)? actor_items.following);
+                visitor.visit_iri(&$($mut)? actor_items.followers);
+                for (_, endpoint) in &$($mut)? actor_items.endpoints {
+                    visitor.visit_iri(endpoint);
+                }
+            }
+            let ext_items = &$($mut)? object.activity_streams_ext_items;
+            for iri in &$($mut)? ext_items.also_known_as {
+                visitor.visit_iri(iri);
+            }
+            let iris = [
+                &$($mut)? ext_items.moved_to,
+                #[cfg(feature = "mastodon")]
+                &$($mut)? object.mastodon_ext_items.featured,
+                #[cfg(feature = "mastodon")]
+                &$($mut)? object.mastodon_ext_items.featured_tags,
+                #[cfg(feature = "mastodon")]
+                &$($mut)? object.mastodon_ext_items.devices,
+            ];
+            for iri in iris.into_iter().flatten() {
+                visitor.visit_iri(iri);
+            }
+            #[cfg(feature = "security")]
+            if let Some(key) = &$($mut)? object.security_items.public_key {
+                visitor.visit_iri(&$($mut)? key.id);
+                visitor.visit_iri(&$($mut)? key.owner);
+            }
+        }
+
+        pub fn $walk_link<V: $visitor + ?Sized>(link: &$($mut)? Link, visitor: &mut V) {
+            visitor.visit_link(&$($mut)? *link);
+            if let Some(id) = &$($mut)? link.id {
+                visitor.visit_iri(id);
+            }
+            visitor.visit_iri(&$($mut)? link.href);
+        }
+
+        fn $walk_value<V: $visitor + ?Sized>(value: &$($mut)? ObjectOrLink, visitor: &mut V) {
+            match value {
+                ObjectOrLink::Link(link) => $walk_link(link, visitor),
+                ObjectOrLink::Object(object) => $walk_object(object, visitor),
+            }
+        }
+    };
+}
+
+walker!(walk, walk_link, walk_value, values, objects, Visitor,);
+walker!(
+    walk_mut,
+    walk_link_mut,
+    walk_value_mut,
+    values_mut,
+    objects_mut,
+    VisitorMut,
+    mut
+);
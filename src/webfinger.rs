@@ -0,0 +1,80 @@
+use std::error::Error;
+#[cfg(feature = "http")]
+use std::io::Read;
+
+use serde_json::Value;
+use url::Url;
+
+use crate::outbox::is_activity_streams_type;
+
+#[cfg(feature = "http")]
+use crate::fetch::{self, FetchPolicy, PublicOnly};
+
+// Limit of a fetched JRD, which lists a few links.
+#[cfg(feature = "http")]
+const MAX_JSON_LENGTH: u64 = 64 * 1024;
+
+/**
+ * Finds the actor of an account `user@host`, e.g. by WebFinger or from a
+ * cache of earlier lookups.
+ */
+pub trait WebFinger {
+    fn lookup(&self, user: &str, host: &str) -> Result<String, Box<dyn Error>>;
+}
+
+impl<F: Fn(&str, &str) -> Result<String, Box<dyn Error>>> WebFinger for F {
+    fn lookup(&self, user: &str, host: &str) -> Result<String, Box<dyn Error>> {
+        self(user, host)
+    }
+}
+
+/**
+ * The WebFinger query for the `acct:` URI of the account.
+ *
+ * Reference: https://www.rfc-editor.org/rfc/rfc7033#section-4
+ */
+pub fn query_url(user: &str, host: &str) -> Result<Url, Box<dyn Error>> {
+    let mut url = Url::parse(&format!("https://{}/.well-known/webfinger", host))?;
+    url.query_pairs_mut()
+        .append_pair("resource", &format!("acct:{}@{}", user, host));
+    Ok(url)
+}
+
+/**
+ * The actor IRI in a JRD, i.e. the `href` of its `self` link of an
+ * ActivityStreams media type.
+ */
+pub fn actor_of(jrd: &Value) -> Option<String> {
+    jrd["links"].as_array()?.iter().find(|link| {
+        link["rel"] == "self" && link["type"].as_str().is_some_and(is_activity_streams_type)
+    })?["href"]
+        .as_str()
+        .map(String::from)
+}
+
+/**
+ * Looks up accounts over HTTPS under a fetch policy.
+ */
+#[cfg(feature = "http")]
+pub struct HttpWebFinger {
+    pub policy: Box<dyn FetchPolicy>,
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpWebFinger {
+    fn default() -> Self {
+        Self {
+            policy: Box::new(PublicOnly),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl WebFinger for HttpWebFinger {
+    fn lookup(&self, user: &str, host: &str) -> Result<String, Box<dyn Error>> {
+        let url = query_url(user, host)?;
+        let response = fetch::get(url.as_str(), "application/jrd+json", self.policy.as_ref())?;
+        let jrd: Value = serde_json::from_reader(response.into_reader().take(MAX_JSON_LENGTH))?;
+        actor_of(&jrd).ok_or_else(|| format!("{}@{} has no actor", user, host).into())
+    }
+}
@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::io::Read;
+
+use url::Url;
+
+use crate::fetch::{self, FetchPolicy, PublicOnly};
+use crate::model::{Map, Object, ObjectOrLink};
+use crate::opengraph::read_attributes;
+use crate::outbox::is_activity_streams_type;
+
+// Limit of fetched HTML to find the endpoint in.
+const MAX_HTML_LENGTH: u64 = 1024 * 1024;
+
+/**
+ * The URLs an object refers to which may take webmentions: its `inReplyTo`
+ * and the links in its `content`, other than mentions and hashtags. Whether
+ * they are pages of ActivityPub servers is left to `discover_endpoint`.
+ */
+pub fn targets(object: &Object) -> Vec<String> {
+    let items = &object.object_items;
+    let mut dest: Vec<String> = vec![];
+    let mut push = |target: &str| {
+        let is_http = Url::parse(target).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if is_http && object.id.as_deref() != Some(target) && !dest.iter().any(|t| t == target) {
+            dest.push(target.to_string());
+        }
+    };
+    for reply in &items.in_reply_to {
+        let target = match reply {
+            ObjectOrLink::Object(object) => object
+                .object_items
+                .url
+                .as_ref()
+                .map(|url| url.href.as_str())
+                .or(object.id.as_deref()),
+            link => link.href(),
+        };
+        if let Some(target) = target {
+            push(target);
+        }
+    }
+    let is_tag = |rel: &str| rel.split_whitespace().any(|rel| rel == "tag");
+    let is_mention = |class: &str| {
+        class
+            .split_whitespace()
+            .any(|class| matches!(class, "mention" | "hashtag"))
+    };
+    for content in &items.content {
+        for (name, attrs) in tags(content) {
+            if name != "a"
+                || attrs.get("rel").is_some_and(|rel| is_tag(rel))
+                || attrs.get("class").is_some_and(|class| is_mention(class))
+            {
+                continue;
+            }
+            if let Some(href) = attrs.get("href") {
+                push(href);
+            }
+        }
+    }
+    dest
+}
+
+/**
+ * The webmention endpoint in the `Link` headers of the target, resolved
+ * against its URL.
+ *
+ * Reference: https://www.w3.org/TR/webmention/#sender-discovers-receiver-webmention-endpoint
+ */
+pub fn endpoint_from_link_headers<'a, I: IntoIterator<Item = &'a str>>(
+    target: &Url,
+    headers: I,
+) -> Option<String> {
+    headers
+        .into_iter()
+        .flat_map(|header| header.split(','))
+        .find_map(|link| {
+            let (href, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+            let is_webmention = params.split(';').any(|param| {
+                param.split_once('=').is_some_and(|(name, value)| {
+                    name.trim().eq_ignore_ascii_case("rel")
+                        && has_webmention_rel(value.trim().trim_matches('"'))
+                })
+            });
+            is_webmention.then(|| target.join(href).ok()).flatten()
+        })
+        .map(String::from)
+}
+
+/**
+ * The webmention endpoint of the first `<link>` or `<a>` element with
+ * `rel="webmention"` in the page of the target, resolved against its URL.
+ * An empty `href` is the target itself.
+ */
+pub fn endpoint_from_html(target: &Url, html: &str) -> Option<String> {
+    tags(html)
+        .find_map(|(name, attrs)| {
+            let is_webmention = matches!(name.as_str(), "link" | "a")
+                && attrs.get("rel").is_some_and(|rel| has_webmention_rel(rel));
+            let href = attrs.get("href").filter(|_| is_webmention)?;
+            target.join(href).ok()
+        })
+        .map(String::from)
+}
+
+/**
+ * Whether the page has an ActivityPub representation, linked as an
+ * alternate, so that its server takes replies by federation instead.
+ */
+pub fn has_activity_pub_alternate(html: &str) -> bool {
+    tags(html).any(|(name, attrs)| {
+        name == "link"
+            && attrs.get("rel").is_some_and(|rel| {
+                rel.split_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+            })
+            && attrs
+                .get("type")
+                .is_some_and(|typ| is_activity_streams_type(typ))
+    })
+}
+
+fn has_webmention_rel(rel: &str) -> bool {
+    rel.split_whitespace()
+        .any(|rel| rel.eq_ignore_ascii_case("webmention"))
+}
+
+// The start tags of the HTML, with their lowercased names and attributes.
+fn tags(html: &str) -> impl Iterator<Item = (String, Map<String, String>)> + '_ {
+    html.split('<').skip(1).filter_map(|rest| {
+        let tag = &rest[..rest.find('>')?];
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        (!name.is_empty()).then(|| (name, read_attributes(tag)))
+    })
+}
+
+/**
+ * Fetches the target and discovers its webmention endpoint. `None` if it
+ * has none, or it is served by an ActivityPub server, as an ActivityStreams
+ * document or a page with one as an alternate.
+ */
+pub fn discover_endpoint(
+    target: &str,
+    policy: &dyn FetchPolicy,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let response = fetch::get(target, "text/html", policy)?;
+    let url = Url::parse(response.get_url())?;
+    if is_activity_streams_type(response.content_type()) {
+        return Ok(None);
+    }
+    if let Some(endpoint) = endpoint_from_link_headers(&url, response.all("Link")) {
+        return Ok(Some(endpoint));
+    }
+    let mut html = String::new();
+    response
+        .into_reader()
+        .take(MAX_HTML_LENGTH)
+        .read_to_string(&mut html)?;
+    if has_activity_pub_alternate(&html) {
+        return Ok(None);
+    }
+    Ok(endpoint_from_html(&url, &html))
+}
+
+/**
+ * Notifies the target that the source links to it, if it takes
+ * webmentions. Returns the endpoint notified. Only public addresses are
+ * fetched, as the targets come from posts.
+ *
+ * Reference: https://www.w3.org/TR/webmention/#sender-notifies-receiver
+ */
+pub fn send(source: &str, target: &str) -> Result<Option<String>, Box<dyn Error>> {
+    send_with_policy(source, target, &PublicOnly)
+}
+
+pub fn send_with_policy(
+    source: &str,
+    target: &str,
+    policy: &dyn FetchPolicy,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let endpoint = match discover_endpoint(target, policy)? {
+        Some(endpoint) => endpoint,
+        None => return Ok(None),
+    };
+    fetch::post_form(&endpoint, &[("source", source), ("target", target)], policy)?;
+    Ok(Some(endpoint))
+}
+
+/**
+ * The webmention sent, or not, to a target by `send_for_object`.
+ */
+#[derive(Debug)]
+pub struct Attempt {
+    pub target: String,
+    // The endpoint notified, `None` if the target takes no webmentions.
+    pub result: Result<Option<String>, Box<dyn Error>>,
+}
+
+/**
+ * Sends webmentions for each of the `targets` of a local object, from its
+ * `url`, or its id without one.
+ */
+pub fn send_for_object(object: &Object, policy: &dyn FetchPolicy) -> Vec<Attempt> {
+    let source = object
+        .object_items
+        .url
+        .as_ref()
+        .map(|url| url.href.as_str())
+        .or(object.id.as_deref());
+    let Some(source) = source else {
+        return vec![];
+    };
+    targets(object)
+        .into_iter()
+        .map(|target| Attempt {
+            result: send_with_policy(source, &target, policy),
+            target,
+        })
+        .collect()
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use activitist::archive::{MastodonArchive, MediaEntry};
+use activitist::model as ap_model;
+
+fn fixture_root() -> &'static Path {
+    Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/mastodon_archive"
+    ))
+}
+
+#[test]
+fn read_mastodon_archive() {
+    let archive = MastodonArchive::open(fixture_root()).unwrap();
+
+    let actor = archive.actor().unwrap();
+    assert_eq!(
+        actor.id,
+        Some("https://mastodon.example/users/alice".to_string())
+    );
+    assert_eq!(
+        actor.actor_items.unwrap().preferred_username,
+        Some("alice".to_string())
+    );
+
+    let activities = archive.activities().unwrap();
+    assert_eq!(activities.len(), 2);
+    match &activities[0] {
+        ap_model::ObjectOrLink::Object(activity) => {
+            assert_eq!(activity.typ, vec!["Create".to_string()]);
+        }
+        _ => panic!("expected an embedded activity"),
+    }
+
+    assert_eq!(
+        archive
+            .likes()
+            .unwrap()
+            .ordered_collection_items
+            .ordered_items,
+        vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+            "https://remote.example/users/bob/statuses/1"
+        ))],
+    );
+
+    assert_eq!(
+        archive.media().unwrap(),
+        vec![
+            MediaEntry {
+                url: "avatar.png".to_string(),
+                media_type: Some("image/png".to_string()),
+                path: fixture_root().join("avatar.png"),
+            },
+            MediaEntry {
+                url: "/media_attachments/files/110/000/000/original/photo.png".to_string(),
+                media_type: Some("image/png".to_string()),
+                path: fixture_root().join("media_attachments/files/110/000/000/original/photo.png"),
+            },
+        ],
+    );
+}
+
+#[test]
+fn reject_non_archive() {
+    assert!(MastodonArchive::open(Path::new(env!("CARGO_MANIFEST_DIR"))).is_err());
+}
+
+#[test]
+fn skip_media_outside_archive() {
+    let root = Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/mastodon_archive_escape/archive"
+    ));
+    assert!(root.join("../secret.png").is_file());
+
+    let archive = MastodonArchive::open(root).unwrap();
+    assert_eq!(archive.media().unwrap(), vec![]);
+}
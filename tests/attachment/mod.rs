@@ -0,0 +1,81 @@
+use activitist::attachment::{AttachmentPolicy, Violation};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+fn note() -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "attachment": [
+            {
+                "type": "Document",
+                "mediaType": "image/png",
+                "url": "https://example.com/1.png",
+                "width": 640,
+                "height": 480
+            },
+            {
+                "type": "Document",
+                "mediaType": "text/html; charset=utf-8",
+                "url": "https://example.com/2.html"
+            },
+            {
+                "type": "Document",
+                "mediaType": "image/jpeg",
+                "url": "https://example.com/3.jpg",
+                "width": 100000,
+                "height": 10
+            },
+            {
+                "type": "Document",
+                "url": "https://example.com/4"
+            }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn report_attachment_violations() {
+    let policy = AttachmentPolicy {
+        max_count: 3,
+        ..AttachmentPolicy::default()
+    };
+    let violations = policy.check(&note());
+    assert_eq!(
+        violations,
+        vec![
+            Violation::TooMany { count: 4, max: 3 },
+            Violation::MediaTypeNotAllowed {
+                index: 1,
+                media_type: "text/html; charset=utf-8".to_string(),
+            },
+            Violation::TooLarge {
+                index: 2,
+                width: 100000,
+                height: 10,
+            },
+        ]
+    );
+    assert_eq!(
+        violations[1].to_string(),
+        "attachment 1 has media type text/html; charset=utf-8, which is not allowed"
+    );
+    assert!(AttachmentPolicy::default().allows_media_type("Video/MP4"));
+}
+
+#[test]
+fn drop_violating_attachments() {
+    let policy = AttachmentPolicy {
+        max_count: 1,
+        ..AttachmentPolicy::default()
+    };
+    let mut note = note();
+    assert_eq!(policy.enforce(&mut note).len(), 3);
+    assert_eq!(
+        note.to_value().unwrap()["attachment"]["url"],
+        json!("https://example.com/1.png")
+    );
+    assert!(policy.check(&note).is_empty());
+}
@@ -0,0 +1,60 @@
+use activitist::audit::{Audit, Finding};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+#[test]
+fn audit_suspicious_document() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://remote.example/activities/1",
+        "actor": "https://other.example/users/mallory",
+        "object": {
+            "type": "Note",
+            "id": "https://example.com/notes/1",
+            "icon": "data:image/png;base64,iVBORw0KGgo=",
+            "replies": {
+                "type": "Collection",
+                "id": "http://example.com/notes/1/replies",
+                "totalItems": 1000000000000u64
+            }
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        Audit::default().audit(&activity),
+        vec![
+            Finding::OriginMismatch {
+                id: "https://remote.example/activities/1".to_string(),
+                actor: "https://other.example/users/mallory".to_string(),
+            },
+            Finding::ForeignEmbedded("https://example.com/notes/1".to_string()),
+            Finding::DataUrl("image/png;base64".to_string()),
+            Finding::ForeignEmbedded("http://example.com/notes/1/replies".to_string()),
+            Finding::NonHttpsId("http://example.com/notes/1/replies".to_string()),
+            Finding::LargeCollection {
+                id: Some("http://example.com/notes/1/replies".to_string()),
+                total_items: 1000000000000,
+            },
+        ]
+    );
+}
+
+#[test]
+fn audit_clean_document() {
+    let mut note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "tag": {
+            "type": "Mention",
+            "href": "https://remote.example/users/bob"
+        }
+    }))
+    .unwrap();
+    note.object_items.attributed_to = vec![ap_model::ObjectOrLink::Link(ap_model::Link::simple(
+        "https://example.com/users/alice",
+    ))];
+
+    assert!(Audit::default().audit(&note).is_empty());
+}
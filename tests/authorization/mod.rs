@@ -0,0 +1,105 @@
+use activitist::authorization::{authorize, authorize_local};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::routes::{Routes, Tenants};
+use serde_json::{json, Value};
+
+fn object(value: Value) -> ap_model::Object {
+    ap_model::Object::from_value(&value).unwrap()
+}
+
+fn note() -> ap_model::Object {
+    let mut note = object(json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "audience": "https://example.com/c/rust"
+    }));
+    note.object_items.attributed_to = vec![ap_model::ObjectOrLink::Link(ap_model::Link::simple(
+        "https://example.com/users/alice",
+    ))];
+    note
+}
+
+fn delete_by(actor: &str) -> ap_model::Object {
+    object(json!({
+        "type": "Delete",
+        "actor": actor,
+        "object": "https://example.com/notes/1"
+    }))
+}
+
+#[test]
+fn authorize_owner() {
+    let target = note();
+
+    let no_moderators = |_: &str, _: &str| false;
+    assert!(authorize(
+        &delete_by("https://example.com/users/alice"),
+        &target,
+        no_moderators
+    )
+    .is_ok());
+    assert_eq!(
+        authorize(
+            &delete_by("https://example.com/users/bob"),
+            &target,
+            no_moderators
+        )
+        .unwrap_err()
+        .to_string(),
+        "https://example.com/users/bob is not allowed to modify https://example.com/notes/1",
+    );
+}
+
+#[test]
+fn authorize_moderator() {
+    let target = note();
+
+    let is_moderator = |actor: &str, group: &str| {
+        actor == "https://other.example/users/mod" && group == "https://example.com/c/rust"
+    };
+    assert!(authorize(
+        &delete_by("https://other.example/users/mod"),
+        &target,
+        is_moderator
+    )
+    .is_ok());
+    assert!(authorize(
+        &delete_by("https://other.example/users/eve"),
+        &target,
+        is_moderator
+    )
+    .is_err());
+}
+
+#[test]
+fn authorize_within_tenant() {
+    let tenants = Tenants::new(vec![
+        Routes::new("https://example.com/a/").unwrap(),
+        Routes::new("https://example.com/b/").unwrap(),
+    ]);
+    // Names no author, so the tenant decides.
+    let target = object(json!({
+        "type": "Note",
+        "id": "https://example.com/a/notes/1"
+    }));
+    let delete_by = |actor: &str| {
+        object(json!({
+            "type": "Delete",
+            "actor": actor,
+            "object": "https://example.com/a/notes/1"
+        }))
+    };
+    let no_moderators = |_: &str, _: &str| false;
+
+    let other_tenant = delete_by("https://example.com/b/users/bob");
+    assert!(authorize(&other_tenant, &target, no_moderators).is_ok());
+    assert!(authorize_local(&other_tenant, &target, &tenants, no_moderators).is_err());
+    assert!(authorize_local(
+        &delete_by("https://example.com/a/users/alice"),
+        &target,
+        &tenants,
+        no_moderators
+    )
+    .is_ok());
+}
@@ -0,0 +1,143 @@
+use std::cell::Cell;
+use std::error::Error;
+
+use activitist::cache::CachingResolver;
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::resolver::{Resolver, Revalidated, Validators};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+fn object(iri: &str) -> Value {
+    match iri {
+        "https://remote.example/users/bob" => json!({
+            "type": "Person",
+            "id": iri,
+            "inbox": "https://remote.example/users/bob/inbox",
+            "outbox": "https://remote.example/users/bob/outbox",
+            "following": "https://remote.example/users/bob/following",
+            "followers": "https://remote.example/users/bob/followers"
+        }),
+        _ => json!({
+            "type": "Note",
+            "id": iri,
+            "attributedTo": "https://remote.example/users/bob",
+            "content": "Hello"
+        }),
+    }
+}
+
+#[test]
+fn expire_by_type() {
+    let lookups = Cell::new(0);
+    let resolver = |iri: &str| -> Result<ap_model::Object, Box<dyn Error>> {
+        lookups.set(lookups.get() + 1);
+        ap_model::Object::from_value(&object(iri))
+    };
+    let cache = CachingResolver::new(resolver);
+    let actor = "https://remote.example/users/bob";
+    let note = "https://remote.example/notes/1";
+
+    cache.resolve_at(actor, now()).unwrap();
+    cache.resolve_at(note, now()).unwrap();
+    cache.resolve_at(note, now()).unwrap();
+    assert_eq!(lookups.get(), 2);
+
+    let later = now() + Duration::hours(1);
+    cache.resolve_at(actor, later).unwrap();
+    assert_eq!(lookups.get(), 2);
+    cache.resolve_at(note, later).unwrap();
+    assert_eq!(lookups.get(), 3);
+}
+
+#[test]
+fn invalidate_by_activities() {
+    let resolver = |iri: &str| -> Result<ap_model::Object, Box<dyn Error>> {
+        ap_model::Object::from_value(&object(iri))
+    };
+    let cache = CachingResolver::new(resolver);
+    for iri in [
+        "https://remote.example/users/bob",
+        "https://remote.example/notes/1",
+        "https://remote.example/notes/2",
+    ] {
+        cache.resolve_at(iri, now()).unwrap();
+    }
+
+    let update = ap_model::Object::from_value(&json!({
+        "type": "Update",
+        "actor": "https://remote.example/users/bob",
+        "object": object("https://remote.example/notes/1")
+    }))
+    .unwrap();
+    assert_eq!(
+        cache.invalidate_for(&update),
+        vec!["https://remote.example/notes/1"]
+    );
+    let like = ap_model::Object::from_value(&json!({
+        "type": "Like",
+        "actor": "https://remote.example/users/bob",
+        "object": "https://remote.example/notes/2"
+    }))
+    .unwrap();
+    assert!(cache.invalidate_for(&like).is_empty());
+    assert_eq!(cache.len(), 2);
+
+    let delete = ap_model::Object::from_value(&json!({
+        "type": "Delete",
+        "actor": "https://remote.example/users/bob",
+        "object": "https://remote.example/users/bob"
+    }))
+    .unwrap();
+    assert_eq!(cache.invalidate_for(&delete).len(), 2);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn revalidate_expired_objects() {
+    struct Conditional {
+        lookups: Cell<usize>,
+    }
+
+    impl Resolver for Conditional {
+        fn resolve(&self, iri: &str) -> Result<ap_model::Object, Box<dyn Error>> {
+            ap_model::Object::from_value(&object(iri))
+        }
+
+        fn resolve_if_modified(
+            &self,
+            iri: &str,
+            validators: &Validators,
+        ) -> Result<Revalidated, Box<dyn Error>> {
+            self.lookups.set(self.lookups.get() + 1);
+            if validators.etag.as_deref() == Some("\"1\"") {
+                return Ok(Revalidated::NotModified);
+            }
+            let validators = Validators {
+                etag: Some("\"1\"".to_string()),
+                last_modified: None,
+            };
+            Ok(Revalidated::Modified(self.resolve(iri)?, validators))
+        }
+    }
+
+    let cache = CachingResolver::new(Conditional {
+        lookups: Cell::new(0),
+    });
+    let note = "https://remote.example/notes/1";
+
+    let fetched = cache.resolve_at(note, now()).unwrap();
+    assert_eq!(cache.resolver.lookups.get(), 1);
+
+    let later = now() + Duration::hours(1);
+    assert_eq!(cache.resolve_at(note, later).unwrap(), fetched);
+    assert_eq!(cache.resolver.lookups.get(), 2);
+    cache
+        .resolve_at(note, later + Duration::minutes(5))
+        .unwrap();
+    assert_eq!(cache.resolver.lookups.get(), 2);
+}
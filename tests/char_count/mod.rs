@@ -0,0 +1,25 @@
+use activitist::char_count::{self, URL_LENGTH};
+
+#[test]
+fn count_like_mastodon() {
+    assert_eq!(char_count::char_count("Hello, 世界"), 9);
+    assert_eq!(
+        char_count::char_count("see https://example.com/a/very/long/path?query=1."),
+        4 + URL_LENGTH + 1
+    );
+    assert_eq!(
+        char_count::char_count("@alice@example.com and @bob hi"),
+        "@alice and @bob hi".len()
+    );
+    assert_eq!(char_count::char_count("mail@example.com"), 16);
+    assert_eq!(
+        char_count::status_char_count(Some("cw"), "https://example.com"),
+        2 + URL_LENGTH
+    );
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn count_grapheme_clusters() {
+    assert_eq!(char_count::char_count("👨‍👩‍👧 e\u{301}"), 3);
+}
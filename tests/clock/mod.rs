@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use activitist::cache::CachingResolver;
+use activitist::clock::{Clock, ManualClock};
+use activitist::id::{IdMinter, SnowflakeMinter};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::resolver::Resolver;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+#[test]
+fn mint_ids_at_fixed_time() {
+    let note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    let mint = || {
+        SnowflakeMinter::new("https://example.com/objects/", 1)
+            .with_clock(now())
+            .mint(&note)
+    };
+    assert_eq!(mint(), mint());
+}
+
+#[test]
+fn expire_cache_by_clock() {
+    let clock = Arc::new(ManualClock::new(now()));
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let resolver = {
+        let lookups = lookups.clone();
+        move |iri: &str| -> Result<ap_model::Object, Box<dyn Error>> {
+            lookups.fetch_add(1, Ordering::SeqCst);
+            ap_model::Object::from_value(&json!({ "type": "Note", "id": iri }))
+        }
+    };
+    let mut cache = CachingResolver::new(resolver);
+    cache.clock = Box::new({
+        let clock = clock.clone();
+        move || clock.now()
+    });
+
+    let iri = "https://remote.example/notes/1";
+    cache.resolve(iri).unwrap();
+    clock.advance(Duration::minutes(5));
+    cache.resolve(iri).unwrap();
+    assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    clock.set(now() + Duration::hours(1));
+    cache.resolve(iri).unwrap();
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+}
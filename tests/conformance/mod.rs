@@ -0,0 +1,46 @@
+use activitist::conformance::{self, FixtureOutcome};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+
+const FIXTURE_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/conformance");
+
+#[test]
+fn check_fixtures() {
+    let reports = conformance::check_fixtures(FIXTURE_ROOT).unwrap();
+    let names: Vec<_> = reports
+        .iter()
+        .map(|report| report.path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "lemmy_page.json",
+            "mastodon_note.json",
+            "misskey_note.json",
+            "peertube_video.json",
+            "pleroma_note.json",
+        ],
+    );
+    for report in reports {
+        assert!(report.is_passed(), "{:?}", report);
+    }
+}
+
+#[test]
+fn read_url_of_renditions() {
+    let video = ap_model::Object::from_json_bytes(
+        &std::fs::read(format!("{}/peertube_video.json", FIXTURE_ROOT)).unwrap(),
+    )
+    .unwrap();
+    let url = video.object_items.url.unwrap();
+    assert_eq!(url.href, "https://peertube.example/w/abcdef");
+    assert_eq!(url.media_type, vec!["text/html".to_string()]);
+}
+
+#[test]
+fn report_parse_failure() {
+    assert!(matches!(
+        conformance::check_fixture(br#"{"type": "Note", "published": "yesterday"}"#),
+        FixtureOutcome::ParseFailed(_),
+    ));
+}
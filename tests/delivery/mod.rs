@@ -0,0 +1,358 @@
+pub mod queue;
+
+use activitist::delivery::{self, Targeting};
+use activitist::federation::{AcceptAll, DomainList};
+use activitist::json::JsonSerde;
+use activitist::model::{self as ap_model, Map};
+use activitist::store::{MemoryStore, ObjectStore};
+use serde_json::{json, Value};
+use url::Url;
+
+fn actor(id: &str, shared_inbox: Option<&str>) -> Value {
+    let mut actor = json!({
+        "type": "Person",
+        "id": id,
+        "inbox": format!("{}/inbox", id),
+        "outbox": format!("{}/outbox", id),
+        "following": format!("{}/following", id),
+        "followers": format!("{}/followers", id)
+    });
+    if let Some(shared_inbox) = shared_inbox {
+        actor["endpoints"] = json!({ "sharedInbox": shared_inbox });
+    }
+    actor
+}
+
+fn remote() -> Map<String, ap_model::Object> {
+    let mut remote = Map::new();
+    for value in [
+        actor(
+            "https://remote.example/users/bob",
+            Some("https://remote.example/inbox"),
+        ),
+        actor(
+            "https://remote.example/users/erin",
+            Some("https://remote.example/inbox"),
+        ),
+        actor("https://other.example/users/carol", None),
+    ] {
+        let object = ap_model::Object::from_value(&value).unwrap();
+        remote.insert(object.id.clone().unwrap(), object);
+    }
+    remote
+}
+
+fn local() -> MemoryStore {
+    let store = MemoryStore::new();
+    for value in [
+        actor("https://example.com/users/dave", None),
+        json!({
+            "type": "OrderedCollection",
+            "id": "https://example.com/users/alice/followers",
+            "orderedItems": [
+                "https://remote.example/users/erin",
+                "https://other.example/users/carol",
+                "https://example.com/users/dave"
+            ]
+        }),
+        json!({
+            "type": "Collection",
+            "id": "https://example.com/users/alice/lists/1",
+            "items": ["https://example.com/users/alice/followers"]
+        }),
+    ] {
+        store
+            .put(ap_model::Object::from_value(&value).unwrap())
+            .unwrap();
+    }
+    store
+}
+
+#[test]
+fn strip_blind_recipients_for_delivery() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "to": "https://example.com/users/bob",
+        "bto": "https://example.com/users/carol",
+        "object": {
+            "type": "Note",
+            "id": "https://example.com/notes/1",
+            "bcc": "https://example.com/users/dave"
+        }
+    }))
+    .unwrap();
+
+    let delivered = activity.prepare_for_delivery().to_value().unwrap();
+    assert!(delivered.get("bto").is_none());
+    assert!(delivered["object"].get("bcc").is_none());
+    assert_eq!(delivered["to"], json!("https://example.com/users/bob"));
+
+    assert_eq!(activity.object_items.bto.len(), 1);
+}
+
+#[test]
+fn compute_delivery_targets() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "to": [
+            "https://www.w3.org/ns/activitystreams#Public",
+            "https://example.com/users/alice/followers"
+        ],
+        "cc": [
+            "https://remote.example/users/bob",
+            "https://example.com/users/alice",
+            "https://unknown.example/users/frank"
+        ],
+        "object": "https://example.com/notes/1"
+    }))
+    .unwrap();
+    let (local, remote) = (local(), remote());
+    let base = Url::parse("https://example.com/").unwrap();
+
+    assert_eq!(
+        delivery::delivery_targets(
+            &activity,
+            &base,
+            &local,
+            &remote,
+            &AcceptAll,
+            &Targeting::default()
+        ),
+        vec![
+            "https://remote.example/inbox".to_string(),
+            "https://other.example/users/carol/inbox".to_string(),
+        ],
+    );
+
+    let targeting = Targeting {
+        use_shared_inbox: false,
+        local_collection_depth: 0,
+        ..Targeting::default()
+    };
+    assert_eq!(
+        delivery::delivery_targets(&activity, &base, &local, &remote, &AcceptAll, &targeting),
+        vec!["https://remote.example/users/bob/inbox".to_string()],
+    );
+}
+
+#[test]
+fn skip_rejected_recipients() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "to": "https://example.com/users/alice/followers",
+        "cc": "https://remote.example/users/bob",
+        "object": "https://example.com/notes/1"
+    }))
+    .unwrap();
+    let local = local();
+    let base = Url::parse("https://example.com/").unwrap();
+    let policy = DomainList {
+        rejected: vec!["remote.example".to_string()],
+        ..DomainList::default()
+    };
+    let resolved = std::cell::RefCell::new(vec![]);
+    let resolver = |iri: &str| {
+        resolved.borrow_mut().push(iri.to_string());
+        remote()
+            .get(iri)
+            .cloned()
+            .ok_or_else(|| format!("{} is not found", iri).into())
+    };
+
+    assert_eq!(
+        delivery::delivery_targets(
+            &activity,
+            &base,
+            &local,
+            &resolver,
+            &policy,
+            &Targeting::default()
+        ),
+        vec!["https://other.example/users/carol/inbox".to_string()],
+    );
+    assert!(resolved
+        .borrow()
+        .iter()
+        .all(|iri| !iri.starts_with("https://remote.example/")));
+}
+
+#[test]
+fn expand_nested_local_collections() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/2",
+        "actor": "https://example.com/users/alice",
+        "to": "https://example.com/users/alice/lists/1",
+        "object": "https://example.com/notes/2"
+    }))
+    .unwrap();
+    let (local, remote) = (local(), remote());
+    let base = Url::parse("https://example.com/").unwrap();
+
+    assert_eq!(
+        delivery::delivery_targets(
+            &activity,
+            &base,
+            &local,
+            &remote,
+            &AcceptAll,
+            &Targeting::default()
+        ),
+        vec![
+            "https://remote.example/inbox".to_string(),
+            "https://other.example/users/carol/inbox".to_string(),
+        ],
+    );
+
+    let targeting = Targeting {
+        local_collection_depth: 1,
+        ..Targeting::default()
+    };
+    assert!(
+        delivery::delivery_targets(&activity, &base, &local, &remote, &AcceptAll, &targeting)
+            .is_empty()
+    );
+}
+
+#[test]
+fn deliver_to_other_tenants() {
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/a/activities/1",
+        "actor": "https://example.com/a/users/alice",
+        "to": [
+            "https://example.com/a/users/dave",
+            "https://example.com/b/users/gina"
+        ],
+        "object": "https://example.com/a/notes/1"
+    }))
+    .unwrap();
+    let local = MemoryStore::new();
+    local
+        .put(
+            ap_model::Object::from_value(&actor("https://example.com/a/users/dave", None)).unwrap(),
+        )
+        .unwrap();
+    let mut remote = Map::new();
+    let gina =
+        ap_model::Object::from_value(&actor("https://example.com/b/users/gina", None)).unwrap();
+    remote.insert(gina.id.clone().unwrap(), gina);
+    let base = Url::parse("https://example.com/a/").unwrap();
+
+    assert_eq!(
+        delivery::delivery_targets(
+            &activity,
+            &base,
+            &local,
+            &remote,
+            &AcceptAll,
+            &Targeting::default()
+        ),
+        vec!["https://example.com/b/users/gina/inbox".to_string()],
+    );
+}
+
+#[test]
+fn group_inboxes_by_host() {
+    let remote = remote();
+    let recipients: Vec<ap_model::Object> = [
+        "https://remote.example/users/bob",
+        "https://remote.example/users/erin",
+        "https://other.example/users/carol",
+    ]
+    .into_iter()
+    .map(|id| remote[id].clone())
+    .collect();
+
+    let public = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "cc": "https://remote.example/users/bob"
+    }))
+    .unwrap();
+    assert_eq!(
+        delivery::inboxes_by_host(&public, &recipients),
+        vec![
+            delivery::HostInboxes {
+                host: "remote.example".to_string(),
+                inboxes: vec!["https://remote.example/inbox".to_string()],
+            },
+            delivery::HostInboxes {
+                host: "other.example".to_string(),
+                inboxes: vec!["https://other.example/users/carol/inbox".to_string()],
+            },
+        ],
+    );
+
+    let direct = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "to": ["https://remote.example/users/bob", "https://remote.example/users/erin"]
+    }))
+    .unwrap();
+    assert!(!delivery::uses_shared_inbox(&direct, &recipients));
+    assert_eq!(
+        delivery::inboxes_by_host(&direct, &recipients[..2])[0].inboxes,
+        vec![
+            "https://remote.example/users/bob/inbox".to_string(),
+            "https://remote.example/users/erin/inbox".to_string(),
+        ],
+    );
+}
+
+#[test]
+fn compute_forwarding_targets() {
+    let reply = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://remote.example/activities/1",
+        "actor": "https://remote.example/users/bob",
+        "to": "https://example.com/users/alice/followers",
+        "object": {
+            "type": "Note",
+            "id": "https://remote.example/notes/1",
+            "inReplyTo": "https://example.com/notes/1"
+        }
+    }))
+    .unwrap();
+    let (local, remote) = (local(), remote());
+    let base = Url::parse("https://example.com/").unwrap();
+
+    assert_eq!(
+        delivery::forwarding_targets(
+            &reply,
+            &base,
+            &local,
+            &remote,
+            &AcceptAll,
+            &Targeting::default()
+        ),
+        vec!["https://other.example/users/carol/inbox".to_string()],
+    );
+
+    let unrelated = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://remote.example/activities/2",
+        "actor": "https://remote.example/users/bob",
+        "to": "https://example.com/users/alice/followers",
+        "object": {
+            "type": "Note",
+            "id": "https://remote.example/notes/2",
+            "inReplyTo": "https://other.example/notes/1"
+        }
+    }))
+    .unwrap();
+    assert!(delivery::forwarding_targets(
+        &unrelated,
+        &base,
+        &local,
+        &remote,
+        &AcceptAll,
+        &Targeting::default()
+    )
+    .is_empty());
+}
@@ -0,0 +1,308 @@
+use std::error::Error;
+
+use activitist::delivery::queue::{
+    self, DeliveryError, DeliveryJob, DeliveryQueue, HostLimits, JobStore, MemoryJobStore, Priority,
+};
+use activitist::federation::DomainList;
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn activity() -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "object": "https://example.com/notes/1",
+        "to": ["https://remote.example/users/bob"],
+        "bcc": ["https://other.example/users/carol"]
+    }))
+    .unwrap()
+}
+
+fn job(inbox: &str, not_before: DateTime<Utc>) -> DeliveryJob {
+    DeliveryJob {
+        inbox: inbox.to_string(),
+        activity: activity(),
+        priority: Priority::Normal,
+        attempts: 0,
+        not_before,
+    }
+}
+
+#[test]
+fn lease_jobs_from_memory_store() {
+    let store = MemoryJobStore::new();
+    let first = store
+        .enqueue(job("https://remote.example/inbox", now()))
+        .unwrap();
+    let later = store
+        .enqueue(job(
+            "https://other.example/inbox",
+            now() + Duration::hours(1),
+        ))
+        .unwrap();
+
+    let leased = store.lease(now(), Duration::minutes(5), 10).unwrap();
+    assert_eq!(
+        leased.iter().map(|leased| leased.id).collect::<Vec<_>>(),
+        vec![first]
+    );
+    // Leased jobs are not leased again until the lease expires.
+    assert!(store
+        .lease(now() + Duration::minutes(1), Duration::minutes(5), 10)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        store
+            .lease(now() + Duration::minutes(5), Duration::minutes(5), 10)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    store.fail(first, Some(now() + Duration::hours(2))).unwrap();
+    let leased = store
+        .lease(now() + Duration::hours(1), Duration::minutes(5), 10)
+        .unwrap();
+    assert_eq!(
+        leased.iter().map(|leased| leased.id).collect::<Vec<_>>(),
+        vec![later]
+    );
+    store.ack(later).unwrap();
+    assert!(store.ack(later).is_err());
+
+    let leased = store
+        .lease(now() + Duration::hours(2), Duration::minutes(5), 10)
+        .unwrap();
+    assert_eq!(leased.len(), 1);
+    assert_eq!(leased[0].job.attempts, 1);
+    store.fail(first, None).unwrap();
+    assert!(store.is_empty());
+}
+
+#[test]
+fn retry_failed_deliveries() {
+    let mut queue = DeliveryQueue::new(MemoryJobStore::new());
+    queue.max_attempts = 2;
+    queue
+        .enqueue(
+            &activity(),
+            &[
+                "https://remote.example/inbox".to_string(),
+                "https://other.example/inbox".to_string(),
+            ],
+            now(),
+        )
+        .unwrap();
+
+    let mut sent = vec![];
+    let attempted = queue
+        .run_once(now(), 10, |job| -> Result<(), Box<dyn Error>> {
+            assert!(job.activity.object_items.bcc.is_empty());
+            sent.push(job.inbox.clone());
+            if job.inbox.starts_with("https://other.example/") {
+                Err("connection refused".into())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+    assert_eq!(attempted, 2);
+    assert_eq!(queue.store.len(), 1);
+
+    // The failed job waits for the retry delay.
+    assert_eq!(queue.run_once(now(), 10, |_| Ok(())).unwrap(), 0);
+    let attempted = queue
+        .run_once(now() + Duration::minutes(1), 10, |_| {
+            Err("connection refused".into())
+        })
+        .unwrap();
+    assert_eq!(attempted, 1);
+    // It is dropped after `max_attempts` attempts.
+    assert!(queue.store.is_empty());
+    assert_eq!(
+        sent,
+        vec![
+            "https://remote.example/inbox".to_string(),
+            "https://other.example/inbox".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn drop_rejected_hosts() {
+    let mut queue = DeliveryQueue::new(MemoryJobStore::new());
+    queue
+        .enqueue(
+            &activity(),
+            &["https://other.example/inbox".to_string()],
+            now(),
+        )
+        .unwrap();
+    queue.policy = Box::new(DomainList {
+        rejected: vec!["remote.example".to_string(), "other.example".to_string()],
+        ..DomainList::default()
+    });
+
+    let ids = queue
+        .enqueue(
+            &activity(),
+            &["https://remote.example/inbox".to_string()],
+            now(),
+        )
+        .unwrap();
+    assert!(ids.is_empty());
+    // The job enqueued before the domain was rejected is dropped unsent.
+    let attempted = queue
+        .run_once(now(), 10, |job| -> Result<(), Box<dyn Error>> {
+            panic!("{} is sent", job.inbox)
+        })
+        .unwrap();
+    assert_eq!(attempted, 0);
+    assert!(queue.store.is_empty());
+}
+
+#[test]
+fn retry_after_requested_time() {
+    assert_eq!(
+        queue::parse_retry_after("120", now()),
+        Some(now() + Duration::minutes(2))
+    );
+    assert_eq!(
+        queue::parse_retry_after("Mon, 01 Jan 2024 01:00:00 GMT", now()),
+        Some(now() + Duration::hours(1))
+    );
+    assert_eq!(queue::parse_retry_after("soon", now()), None);
+
+    let queue = DeliveryQueue::new(MemoryJobStore::new());
+    queue
+        .enqueue(
+            &activity(),
+            &["https://remote.example/inbox".to_string()],
+            now(),
+        )
+        .unwrap();
+    queue
+        .run_once(now(), 10, |_| {
+            Err(Box::new(DeliveryError {
+                status: 429,
+                retry_after: Some("3600".to_string()),
+            }))
+        })
+        .unwrap();
+
+    // Not retried after the usual delay of a minute, but after the hour
+    // asked for.
+    assert_eq!(
+        queue
+            .run_once(now() + Duration::minutes(59), 10, |_| Ok(()))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        queue
+            .run_once(now() + Duration::hours(1), 10, |_| Ok(()))
+            .unwrap(),
+        1
+    );
+    assert!(queue.store.is_empty());
+}
+
+#[test]
+fn lease_high_priority_first() {
+    let queue = DeliveryQueue::new(MemoryJobStore::new());
+    let inboxes = ["https://remote.example/inbox".to_string()];
+    let mut delete = activity();
+    delete.typ = vec!["Delete".to_string()];
+    let mut accept = activity();
+    accept.typ = vec!["Accept".to_string()];
+    queue.enqueue(&delete, &inboxes, now()).unwrap();
+    queue.enqueue(&activity(), &inboxes, now()).unwrap();
+    queue
+        .enqueue(&accept, &inboxes, now() + Duration::seconds(1))
+        .unwrap();
+
+    let leased = queue
+        .store
+        .lease(now() + Duration::seconds(1), Duration::minutes(5), 10)
+        .unwrap();
+    assert_eq!(
+        leased
+            .iter()
+            .map(|leased| leased.job.priority)
+            .collect::<Vec<_>>(),
+        vec![Priority::High, Priority::Normal, Priority::Low]
+    );
+}
+
+#[test]
+fn limit_deliveries_per_host() {
+    let mut queue = DeliveryQueue::new(MemoryJobStore::new());
+    queue.host_overrides.insert(
+        "remote.example".to_string(),
+        HostLimits {
+            max_concurrent: 1,
+            min_interval: Duration::seconds(10),
+        },
+    );
+    queue.max_pending = 3;
+    queue
+        .enqueue(
+            &activity(),
+            &[
+                "https://remote.example/inbox".to_string(),
+                "https://remote.example/users/bob/inbox".to_string(),
+                "https://other.example/inbox".to_string(),
+            ],
+            now(),
+        )
+        .unwrap();
+    assert!(queue.is_saturated().unwrap());
+    assert!(queue
+        .enqueue(
+            &activity(),
+            &["https://more.example/inbox".to_string()],
+            now()
+        )
+        .is_err());
+
+    let mut sent = vec![];
+    let attempted = queue
+        .run_once(now(), 10, |job| {
+            sent.push(job.inbox.clone());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(attempted, 2);
+    // The deferred job waits for the interval of the host.
+    assert_eq!(
+        queue
+            .run_once(now() + Duration::seconds(5), 10, |_| Ok(()))
+            .unwrap(),
+        0
+    );
+    let attempted = queue
+        .run_once(now() + Duration::seconds(10), 10, |job| {
+            sent.push(job.inbox.clone());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(attempted, 1);
+    assert_eq!(
+        sent,
+        vec![
+            "https://remote.example/inbox".to_string(),
+            "https://other.example/inbox".to_string(),
+            "https://remote.example/users/bob/inbox".to_string(),
+        ]
+    );
+    assert!(queue.store.is_empty());
+}
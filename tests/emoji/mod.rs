@@ -0,0 +1,53 @@
+use activitist::emoji::{self, CustomEmoji};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+fn blobcat() -> CustomEmoji {
+    CustomEmoji {
+        shortcode: "blobcat".to_string(),
+        id: Some("https://example.com/emojis/1".to_string()),
+        icon_url: "https://example.com/emojis/blobcat.png".to_string(),
+        media_type: Some("image/png".to_string()),
+    }
+}
+
+#[test]
+fn scan_shortcodes() {
+    assert_eq!(
+        emoji::shortcodes("<p>:blobcat: hi :blobcat:, :x: a:nope: :ok_2:</p>"),
+        vec!["blobcat", "ok_2"]
+    );
+    assert!(emoji::shortcodes("https://example.com:8080/ 12:30:45").is_empty());
+}
+
+#[test]
+fn correlate_and_compose_emoji_tags() {
+    let note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "content": "<p>:blobcat: :unknown:</p>",
+        "tag": [{
+            "id": "https://example.com/emojis/1",
+            "type": "Emoji",
+            "name": ":blobcat:",
+            "icon": {
+                "type": "Image",
+                "mediaType": "image/png",
+                "url": "https://example.com/emojis/blobcat.png"
+            }
+        }]
+    }))
+    .unwrap();
+    assert_eq!(emoji::used_emojis(&note), vec![blobcat()]);
+
+    let mut composed = ap_model::Object::new(None, vec!["Note".to_string()]);
+    composed.object_items.content = vec!["Hello :blobcat: :blobcat: :unknown:".to_string()];
+    emoji::add_emoji_tags(&mut composed, |shortcode| {
+        (shortcode == "blobcat").then(blobcat)
+    });
+    assert_eq!(composed.object_items.tag.len(), 1);
+    assert_eq!(
+        composed.to_value().unwrap()["tag"],
+        note.to_value().unwrap()["tag"]
+    );
+}
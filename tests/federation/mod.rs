@@ -0,0 +1,66 @@
+use activitist::federation::{DomainList, FederationPolicy, PolicyResolver, Verdict};
+use activitist::json::JsonSerde;
+use activitist::model::{self as ap_model, Map};
+use activitist::resolver::Resolver;
+use serde_json::json;
+
+fn policy() -> DomainList {
+    DomainList {
+        rejected: vec!["spam.example".to_string()],
+        silenced: vec!["noisy.example".to_string()],
+        rejected_actors: vec!["https://example.com/users/troll".to_string()],
+    }
+}
+
+#[test]
+fn verdicts_by_domain_and_actor() {
+    let policy = policy();
+
+    assert_eq!(policy.domain_verdict("spam.example"), Verdict::Reject);
+    assert_eq!(policy.domain_verdict("a.spam.example"), Verdict::Reject);
+    assert_eq!(policy.domain_verdict("notspam.example"), Verdict::Accept);
+    assert_eq!(
+        policy.actor_verdict("https://noisy.example/users/alice"),
+        Verdict::Silence,
+    );
+    assert_eq!(
+        policy.actor_verdict("https://example.com/users/troll"),
+        Verdict::Reject,
+    );
+
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Announce",
+        "id": "https://example.com/activities/1",
+        "actor": "https://noisy.example/users/alice",
+        "object": "https://example.com/notes/1"
+    }))
+    .unwrap();
+    assert_eq!(policy.activity_verdict(&activity), Verdict::Silence);
+}
+
+#[test]
+fn resolve_under_policy() {
+    let mut store = Map::new();
+    for id in [
+        "https://example.com/notes/1",
+        "https://spam.example/notes/1",
+    ] {
+        store.insert(
+            id.to_string(),
+            ap_model::Object::new(Some(id.to_string()), vec!["Note".to_string()]),
+        );
+    }
+    let resolver = PolicyResolver {
+        policy: policy(),
+        resolver: store,
+    };
+
+    assert!(resolver.resolve("https://example.com/notes/1").is_ok());
+    assert_eq!(
+        resolver
+            .resolve("https://spam.example/notes/1")
+            .unwrap_err()
+            .to_string(),
+        "https://spam.example/notes/1 is rejected by the federation policy",
+    );
+}
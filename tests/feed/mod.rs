@@ -0,0 +1,221 @@
+use activitist::archive::MastodonArchive;
+use activitist::feed::{self, atom, icalendar, json_feed};
+use activitist::json::{JsonSerde, SerdeJsonValue};
+use activitist::model::Object;
+use serde_json::json;
+
+const ARCHIVE_ROOT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/mastodon_archive"
+);
+
+#[test]
+fn collect_posts() {
+    let archive = MastodonArchive::open(ARCHIVE_ROOT).unwrap();
+    let outbox = archive.outbox().unwrap();
+    let posts = feed::posts(&outbox);
+
+    assert_eq!(posts.len(), 1);
+    assert_eq!(
+        posts[0].id,
+        Some("https://mastodon.example/users/alice/statuses/110000000000000001".to_string()),
+    );
+}
+
+#[test]
+fn outbox_to_atom() {
+    let archive = MastodonArchive::open(ARCHIVE_ROOT).unwrap();
+    let actor = archive.actor().unwrap();
+    let outbox = archive.outbox().unwrap();
+
+    assert_eq!(
+        atom::to_atom(&actor, &outbox).unwrap(),
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>https://mastodon.example/users/alice</id>
+  <title>Alice</title>
+  <updated>2023-04-15T11:22:33Z</updated>
+  <subtitle type="html">&lt;p&gt;Hello&lt;/p&gt;</subtitle>
+  <link rel="alternate" href="https://mastodon.example/@alice"/>
+  <author>
+    <name>Alice</name>
+    <uri>https://mastodon.example/@alice</uri>
+  </author>
+  <entry>
+    <id>https://mastodon.example/users/alice/statuses/110000000000000001</id>
+    <title>Hello, world</title>
+    <updated>2023-04-15T11:22:33Z</updated>
+    <published>2023-04-15T11:22:33Z</published>
+    <link rel="alternate" href="https://mastodon.example/@alice/110000000000000001"/>
+    <content type="html">&lt;p&gt;Hello, world&lt;/p&gt;</content>
+  </entry>
+</feed>
+"#,
+    );
+}
+
+#[test]
+fn skip_unidentified_atom_entries() {
+    let actor = Object::from_value(&json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "name": "Alice"
+    }))
+    .unwrap();
+    let outbox = Object::from_value(&json!({
+        "type": "OrderedCollection",
+        "orderedItems": [{
+            "type": "Create",
+            "object": {"type": "Note", "id": "notes/1", "content": "Relative"}
+        }, {
+            "type": "Create",
+            "object": {
+                "type": "Note",
+                "id": "https://example.com/notes/2",
+                "content": "Undated"
+            }
+        }, {
+            "type": "Create",
+            "object": {
+                "type": "Note",
+                "id": "https://example.com/notes/3",
+                "content": "Dated",
+                "published": "2024-01-01T00:00:00Z"
+            }
+        }]
+    }))
+    .unwrap();
+
+    let feed = atom::to_atom(&actor, &outbox).unwrap();
+    assert!(feed.contains("<id>https://example.com/users/alice</id>"));
+    assert!(!feed.contains("Relative"));
+    assert!(!feed.contains("1970"));
+    assert_eq!(
+        feed.matches("<updated>2024-01-01T00:00:00Z</updated>")
+            .count(),
+        3
+    );
+
+    let empty = Object::from_value(&json!({"type": "OrderedCollection"})).unwrap();
+    assert!(atom::to_atom(&actor, &empty).is_err());
+    let anonymous = Object::from_value(&json!({"type": "Person"})).unwrap();
+    assert!(atom::to_atom(&anonymous, &outbox).is_err());
+}
+
+#[test]
+fn outbox_to_json_feed() {
+    let archive = MastodonArchive::open(ARCHIVE_ROOT).unwrap();
+    let actor = archive.actor().unwrap();
+    let outbox = archive.outbox().unwrap();
+    let feed = SerdeJsonValue::new(json_feed::to_json_feed(&actor, &outbox));
+
+    assert_eq!(
+        feed.to_value().unwrap(),
+        json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "Alice",
+            "home_page_url": "https://mastodon.example/@alice",
+            "description": "Hello",
+            "icon": "avatar.png",
+            "authors": [{
+                "name": "Alice",
+                "url": "https://mastodon.example/@alice",
+                "avatar": "avatar.png"
+            }],
+            "items": [{
+                "id": "https://mastodon.example/users/alice/statuses/110000000000000001",
+                "url": "https://mastodon.example/@alice/110000000000000001",
+                "content_html": "<p>Hello, world</p>",
+                "date_published": "2023-04-15T11:22:33Z",
+                "attachments": [{
+                    "url": "/media_attachments/files/110/000/000/original/photo.png",
+                    "mime_type": "image/png"
+                }]
+            }]
+        }),
+    );
+}
+
+#[test]
+#[cfg(feature = "place")]
+fn events_to_icalendar() {
+    let group_outbox = Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "OrderedCollection",
+        "orderedItems": [{
+            "type": "Create",
+            "actor": "https://mobilizon.example/@group",
+            "object": {
+                "id": "https://mobilizon.example/events/1",
+                "type": "Event",
+                "name": "Meetup; bring snacks",
+                "content": "<p>Talks, then dinner</p>",
+                "startTime": "2024-05-01T18:00:00+09:00",
+                "endTime": "2024-05-01T21:00:00+09:00",
+                "updated": "2024-04-01T00:00:00Z",
+                "url": "https://mobilizon.example/events/1",
+                "location": {
+                    "type": "Place",
+                    "name": "Community Hall",
+                    "latitude": 35.5,
+                    "longitude": 139.75
+                }
+            }
+        }, {
+            "type": "Create",
+            "actor": "https://mobilizon.example/@group",
+            "object": {
+                "id": "https://mobilizon.example/notes/2",
+                "type": "Note",
+                "content": "Not an event"
+            }
+        }]
+    }))
+    .unwrap();
+    let events = icalendar::events(&group_outbox);
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        icalendar::to_icalendar(&events),
+        [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//activitist//NONSGML activitist//EN",
+            "BEGIN:VEVENT",
+            "UID:https://mobilizon.example/events/1",
+            "DTSTAMP:20240401T000000Z",
+            "DTSTART:20240501T090000Z",
+            "DTEND:20240501T120000Z",
+            "SUMMARY:Meetup\\; bring snacks",
+            "DESCRIPTION:Talks\\, then dinner",
+            "LOCATION:Community Hall",
+            "GEO:35.5;139.75",
+            "URL:https://mobilizon.example/events/1",
+            "END:VEVENT",
+            "END:VCALENDAR",
+            "",
+        ]
+        .join("\r\n"),
+    );
+}
+
+#[test]
+fn fold_long_vevent_lines() {
+    let mut event = Object::new(
+        Some("https://mobilizon.example/events/1".to_string()),
+        vec!["Event".to_string()],
+    );
+    event.object_items.start_time = Some("2024-05-01T09:00:00Z".parse().unwrap());
+    event.object_items.name = vec!["あ".repeat(30)];
+    let vevent = icalendar::to_vevent(&event).unwrap();
+
+    assert!(vevent.lines().all(|line| line.trim_end().len() <= 75));
+    assert!(vevent.contains(&format!(
+        "SUMMARY:{}\r\n {}\r\n",
+        "あ".repeat(22),
+        "あ".repeat(8)
+    )));
+
+    event.object_items.start_time = None;
+    assert_eq!(icalendar::to_vevent(&event), None);
+}
@@ -0,0 +1,190 @@
+use std::net::IpAddr;
+
+use activitist::federation::DomainList;
+use activitist::fetch::{self, AllowHosts, Federated, FetchPolicy, Proxied, Proxy, PublicOnly};
+use url::Url;
+
+#[test]
+fn classify_addresses() {
+    for ip in [
+        "93.184.216.34",
+        "2606:2800:220:1:248:1893:25c8:1946",
+        "64:ff9b::93.184.216.34",
+        "2002:5db8:d822::1",
+    ] {
+        assert!(fetch::is_public_address(ip.parse().unwrap()), "{}", ip);
+    }
+    for ip in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "fd00::1",
+        "fe80::1",
+        "::ffff:127.0.0.1",
+        "64:ff9b::127.0.0.1",
+        "64:ff9b::a9fe:a9fe",
+        "64:ff9b:1::1",
+        "2002:7f00:1::",
+        "2002:a9fe:a9fe::1",
+        "::127.0.0.1",
+        "::10.0.0.1",
+        "fec0::1",
+        "198.18.0.1",
+        "198.19.255.255",
+        "192.0.0.8",
+    ] {
+        assert!(!fetch::is_public_address(ip.parse().unwrap()), "{}", ip);
+    }
+}
+
+#[test]
+fn check_url_with_policy() {
+    let url = Url::parse("http://127.0.0.1:8080/admin").unwrap();
+    assert!(fetch::check_url(&url, &PublicOnly).is_err());
+
+    let policy = AllowHosts(vec!["127.0.0.1".to_string()]);
+    assert!(policy.allows_address("127.0.0.1", "127.0.0.1".parse::<IpAddr>().unwrap()));
+    assert_eq!(fetch::check_url(&url, &policy).unwrap().len(), 1);
+
+    let url = Url::parse("file:///etc/passwd").unwrap();
+    assert_eq!(
+        fetch::check_url(&url, &PublicOnly).unwrap_err().to_string(),
+        "file:///etc/passwd is not allowed to fetch",
+    );
+}
+
+#[test]
+fn refuse_rejected_domains() {
+    let policy = Federated {
+        policy: PublicOnly,
+        federation: DomainList {
+            rejected: vec!["spam.example".to_string()],
+            ..DomainList::default()
+        },
+    };
+    let url = Url::parse("https://a.spam.example/users/eve").unwrap();
+    assert!(!policy.allows_url(&url));
+    assert_eq!(
+        fetch::check_url(&url, &policy).unwrap_err().to_string(),
+        "https://a.spam.example/users/eve is not allowed to fetch",
+    );
+    assert!(policy.allows_url(&Url::parse("https://example.com/users/alice").unwrap()));
+}
+
+#[test]
+fn route_through_proxy() {
+    let policy = Proxied {
+        policy: PublicOnly,
+        proxy: Proxy {
+            url: "http://proxy.internal:3128".to_string(),
+            no_proxy: vec![".example.com".to_string(), "10.0.0.1".to_string()],
+        },
+    };
+    let proxy_of = |url: &str| policy.proxy(&Url::parse(url).unwrap()).cloned();
+
+    assert_eq!(
+        proxy_of("https://remote.example/users/bob"),
+        Some(policy.proxy.clone())
+    );
+    assert_eq!(proxy_of("https://media.example.com/a.png"), None);
+    assert_eq!(proxy_of("https://EXAMPLE.com/"), None);
+    assert_eq!(proxy_of("http://10.0.0.1/"), None);
+    assert_eq!(
+        proxy_of("https://notexample.com/"),
+        Some(policy.proxy.clone())
+    );
+    assert!(Proxy {
+        no_proxy: vec!["*".to_string()],
+        ..Proxy::new("http://proxy.internal:3128")
+    }
+    .bypasses("remote.example"));
+
+    // Names which do not resolve locally are left to the proxy, but local
+    // names and literal addresses are checked.
+    let url = Url::parse("https://unresolvable.invalid/").unwrap();
+    assert!(fetch::check_proxied_url(&url, &policy).is_ok());
+    for url in [
+        "http://localhost/",
+        "http://LOCALHOST./admin",
+        "http://api.localhost/",
+        "http://metadata/computeMetadata/v1/",
+    ] {
+        let url = Url::parse(url).unwrap();
+        assert!(fetch::check_proxied_url(&url, &policy).is_err(), "{}", url);
+    }
+    let allowed = Proxied {
+        policy: AllowHosts(vec!["localhost".to_string()]),
+        proxy: policy.proxy.clone(),
+    };
+    let url = Url::parse("http://localhost/").unwrap();
+    assert!(fetch::check_proxied_url(&url, &allowed).is_ok());
+    let url = Url::parse("http://[::1]:8080/admin").unwrap();
+    assert_eq!(
+        fetch::check_proxied_url(&url, &policy)
+            .unwrap_err()
+            .to_string(),
+        "::1 is a forbidden address",
+    );
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn fetch_conditionally() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    use activitist::fetch::HttpResolver;
+    use activitist::resolver::{Resolver, Revalidated, Validators};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/notes/1", listener.local_addr().unwrap());
+    let body = format!(r#"{{"type":"Note","id":"{}","content":"Hello"}}"#, url);
+    let server = std::thread::spawn(move || {
+        let mut requests = vec![];
+        for stream in listener.incoming().take(2) {
+            let mut stream = stream.unwrap();
+            let headers: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+            let response = if headers.iter().any(|line| line == "If-None-Match: \"1\"") {
+                "HTTP/1.1 304 Not Modified\r\nETag: \"1\"\r\nContent-Length: 0\r\n\r\n".to_string()
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/activity+json\r\nETag: \"1\"\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            };
+            stream.write_all(response.as_bytes()).unwrap();
+            requests.push(headers);
+        }
+        requests
+    });
+    let resolver = HttpResolver::new(AllowHosts(vec!["127.0.0.1".to_string()]));
+
+    let validators = match resolver.resolve_if_modified(&url, &Validators::default()) {
+        Ok(Revalidated::Modified(object, validators)) => {
+            assert_eq!(object.id.as_deref(), Some(url.as_str()));
+            validators
+        }
+        other => panic!("unexpected {:?}", other),
+    };
+    assert_eq!(validators.etag.as_deref(), Some("\"1\""));
+    assert!(matches!(
+        resolver.resolve_if_modified(&url, &validators),
+        Ok(Revalidated::NotModified)
+    ));
+
+    let requests = server.join().unwrap();
+    assert!(!requests[0]
+        .iter()
+        .any(|line| line.starts_with("If-None-Match")));
+    assert!(requests[1].contains(&"If-None-Match: \"1\"".to_string()));
+}
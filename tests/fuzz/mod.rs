@@ -0,0 +1,27 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use activitist::fuzz;
+
+    const ARCHIVE_ROOT: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/mastodon_archive"
+    );
+
+    #[test]
+    fn round_trip_archive() {
+        for name in ["actor.json", "outbox.json", "likes.json", "bookmarks.json"] {
+            let data = std::fs::read(format!("{}/{}", ARCHIVE_ROOT, name)).unwrap();
+            fuzz::round_trip_json(&data);
+        }
+    }
+
+    #[test]
+    fn serialize_arbitrary_objects() {
+        let data: Vec<u8> = (0..4096_u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        for start in (0..data.len()).step_by(64) {
+            fuzz::serialize_arbitrary(&data[start..]);
+        }
+    }
+}
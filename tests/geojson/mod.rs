@@ -0,0 +1,78 @@
+use activitist::geojson;
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+#[test]
+fn convert_place_to_geojson() {
+    let mut place = ap_model::Object::new(
+        Some("https://example.com/places/1".to_string()),
+        vec!["Place".to_string()],
+    );
+    place.object_items.name = vec!["Fresno Area".to_string()];
+    place.place_items.latitude = Some(36.75);
+    place.place_items.longitude = Some(119.7667);
+    place.place_items.altitude = Some(0.5);
+    place.place_items.radius = Some(15.0);
+    place.place_items.units = Some("km".to_string());
+
+    assert_eq!(
+        geojson::to_point(&place),
+        Some(json!({"type": "Point", "coordinates": [119.7667, 36.75, 500.0]}))
+    );
+    let feature = geojson::to_feature(&place).unwrap();
+    assert_eq!(
+        feature,
+        json!({
+            "type": "Feature",
+            "id": "https://example.com/places/1",
+            "geometry": {"type": "Point", "coordinates": [119.7667, 36.75, 500.0]},
+            "properties": {"name": "Fresno Area", "radius": 15.0, "units": "km"}
+        })
+    );
+    assert_eq!(
+        geojson::from_geojson(&feature).unwrap().to_value().unwrap(),
+        place.to_value().unwrap()
+    );
+
+    let unknown = ap_model::Object::new(None, vec!["Place".to_string()]);
+    assert_eq!(geojson::to_point(&unknown), None);
+}
+
+#[test]
+fn reject_other_geometries() {
+    let point =
+        geojson::from_geojson(&json!({"type": "Point", "coordinates": [1.0, 2.0]})).unwrap();
+    assert_eq!(point.place_items.latitude, Some(2.0));
+    assert!(geojson::from_geojson(&json!({
+        "type": "Feature",
+        "geometry": {"type": "LineString", "coordinates": [[1.0, 2.0], [3.0, 4.0]]},
+        "properties": null
+    }))
+    .is_err());
+}
+
+#[test]
+fn convert_activity_streams_place() {
+    // From the Activity Vocabulary, with the misspelled names accepted too.
+    for (latitude, longitude) in [("latitude", "longitude"), ("latitute", "longitute")] {
+        let mut value = json!({
+            "type": "Place",
+            "name": "Fresno Area",
+            "radius": 15,
+            "units": "miles"
+        });
+        value[latitude] = json!(36.75);
+        value[longitude] = json!(119.7667);
+        let place = ap_model::Object::from_value(&value).unwrap();
+
+        assert_eq!(
+            geojson::to_point(&place),
+            Some(json!({"type": "Point", "coordinates": [119.7667, 36.75]}))
+        );
+        let written = place.to_value().unwrap();
+        assert_eq!(written["latitude"], json!(36.75));
+        assert_eq!(written["longitude"], json!(119.7667));
+        assert!(written.get("latitute").is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use activitist::graph;
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+fn create() -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/activities/1",
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "object": {
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "content": "Hello",
+            "tag": [{"type": "Hashtag", "name": "rust"}],
+            "replies": {
+                "id": "https://example.com/notes/1/replies",
+                "type": "Collection",
+                "totalItems": 0
+            }
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn flatten_embedded_objects() {
+    let documents: Vec<_> = graph::flatten(create())
+        .iter()
+        .map(|document| document.to_value().unwrap())
+        .collect();
+
+    assert_eq!(
+        documents,
+        vec![
+            json!({
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/activities/1",
+                "type": "Create",
+                "actor": "https://example.com/users/alice",
+                "object": "https://example.com/notes/1"
+            }),
+            json!({
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/notes/1",
+                "type": "Note",
+                "content": "Hello",
+                "tag": {"type": "Hashtag", "name": "rust"},
+                "replies": {"id": "https://example.com/notes/1/replies"}
+            }),
+            json!({
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/notes/1/replies",
+                "type": "Collection",
+                "totalItems": 0
+            }),
+        ],
+    );
+}
+
+#[test]
+fn inline_references() {
+    let mut store = ap_model::Map::new();
+    for document in graph::flatten(create()) {
+        store.insert(document.id.clone().unwrap(), document);
+    }
+    let alice = ap_model::Object::new(
+        Some("https://example.com/users/alice".to_string()),
+        vec!["Person".to_string()],
+    );
+    store.insert(alice.id.clone().unwrap(), alice);
+
+    let mut data = store["https://example.com/activities/1"].clone();
+    assert!(graph::inline(&mut data, &store, 1).is_empty());
+    let object = data.activity_items.object[0].as_object().unwrap();
+    assert_eq!(
+        data.activity_items.actor[0].as_object().unwrap().typ,
+        vec!["Person".to_string()]
+    );
+    assert_eq!(object.object_items.content, vec!["Hello".to_string()]);
+    assert_eq!(
+        object.object_items.replies.as_ref().unwrap().typ,
+        Vec::<String>::new()
+    );
+
+    let mut data = store["https://example.com/activities/1"].clone();
+    assert!(graph::inline(&mut data, &store, 2).is_empty());
+    let mut expected = create();
+    expected.activity_items.actor = vec![ap_model::ObjectOrLink::Object(
+        store["https://example.com/users/alice"].clone_without_schema_context(),
+    )];
+    assert_eq!(data.to_value().unwrap(), expected.to_value().unwrap());
+}
+
+#[test]
+fn keep_unresolvable_references() {
+    let mut store = ap_model::Map::new();
+    for document in graph::flatten(create()) {
+        store.insert(document.id.clone().unwrap(), document);
+    }
+
+    let mut data = store["https://example.com/activities/1"].clone();
+    let errors = graph::inline(&mut data, &store, 1);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(iri, _)| iri.as_str())
+            .collect::<Vec<_>>(),
+        vec!["https://example.com/users/alice"]
+    );
+    assert_eq!(
+        data.activity_items.actor[0].as_link().unwrap().href,
+        "https://example.com/users/alice"
+    );
+    let object = data.activity_items.object[0].as_object().unwrap();
+    assert_eq!(object.object_items.content, vec!["Hello".to_string()]);
+}
@@ -0,0 +1,52 @@
+use activitist::hashtag::{self, Hashtag};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::routes::Routes;
+use serde_json::json;
+
+#[test]
+fn scan_hashtags() {
+    assert_eq!(
+        hashtags_of("#Rust and #rust, #日本語 #हिन्दी #2024 #_x a#b"),
+        vec!["Rust", "日本語", "हिन्दी", "_x"]
+    );
+    assert_eq!(
+        hashtags_of("#தமிழ் #ಕನ್ನಡ #മലയാളം #ภาษาไทย"),
+        vec!["தமிழ்", "ಕನ್ನಡ", "മലയാളം", "ภาษาไทย"]
+    );
+    assert!(hashtags_of("https://example.com/#frag &#39; (#1) x=#y").is_empty());
+    assert_eq!(hashtag::normalize("#RuSt"), "rust");
+}
+
+fn hashtags_of(text: &str) -> Vec<&str> {
+    hashtag::hashtags(text)
+}
+
+#[test]
+fn compose_hashtag_tags() {
+    let routes = Routes::new("https://example.com/").unwrap();
+    let mut note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    note.object_items.content =
+        vec!["<p>Hi</p><p>#Rust <a href=\"#top\">#ActivityPub</a> #rust #Café</p>".to_string()];
+    note.object_items.tag = vec![ap_model::ObjectOrLink::Object(
+        Hashtag {
+            name: "ActivityPub".to_string(),
+            href: None,
+        }
+        .to_tag(),
+    )];
+    hashtag::add_hashtag_tags(&mut note, |name| routes.tag_url(name));
+
+    assert_eq!(
+        note.to_value().unwrap()["tag"],
+        json!([
+            {"type": "Hashtag", "name": "#ActivityPub"},
+            {"type": "Hashtag", "name": "#Rust", "href": "https://example.com/tags/rust"},
+            {"type": "Hashtag", "name": "#Café", "href": "https://example.com/tags/caf%C3%A9"}
+        ])
+    );
+    assert_eq!(
+        hashtag::tagged_hashtags(&note)[1].href.as_deref(),
+        Some("https://example.com/tags/rust")
+    );
+}
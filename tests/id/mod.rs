@@ -0,0 +1,71 @@
+use activitist::id::{IdMinter, SlugMinter, SnowflakeMinter};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+#[test]
+fn mint_snowflakes() {
+    let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let mut minter = SnowflakeMinter::new("https://example.com/objects/", 3);
+
+    let first = minter.next_id(now);
+    let second = minter.next_id(now);
+    assert_eq!(second, first + 1);
+    assert_eq!((first >> 12) & 0x3ff, 3);
+    // The clock going back does not reorder ids.
+    assert!(minter.next_id(now - Duration::seconds(1)) > second);
+    assert!(minter.next_id(now + Duration::milliseconds(1)) >> 22 > first >> 22);
+
+    let note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    assert!(minter
+        .mint(&note)
+        .starts_with("https://example.com/objects/"));
+}
+
+#[test]
+fn mint_slugs() {
+    let mut minter =
+        SlugMinter::new("https://example.com/articles/").with_used(["hello-world".to_string()]);
+    let article = ap_model::Object::from_value(&json!({
+        "type": "Article",
+        "name": "Hello, World!",
+        "content": "<p>Ignored</p>"
+    }))
+    .unwrap();
+    assert_eq!(
+        minter.mint(&article),
+        "https://example.com/articles/hello-world-2"
+    );
+    assert_eq!(
+        minter.mint(&article),
+        "https://example.com/articles/hello-world-3"
+    );
+
+    let note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "content": "<p>A <b>bold</b> move, with more words than fit a slug</p>"
+    }))
+    .unwrap();
+    let minter: &mut dyn IdMinter = &mut minter;
+    assert_eq!(
+        minter.mint(&note),
+        "https://example.com/articles/a-bold-move-with-more-words"
+    );
+    let anonymous = ap_model::Object::new(None, vec!["Like".to_string()]);
+    assert_eq!(minter.mint(&anonymous), "https://example.com/articles/like");
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn mint_uuids() {
+    let mut minter = activitist::id::UuidMinter {
+        prefix: "https://example.com/objects/".to_string(),
+    };
+    let note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    let id = minter.mint(&note);
+    assert_eq!(id.len(), "https://example.com/objects/".len() + 36);
+    assert_ne!(minter.mint(&note), id);
+}
@@ -0,0 +1,114 @@
+use activitist::instance::InstanceActor;
+use activitist::json::JsonSerde;
+use serde_json::json;
+
+const PUBLIC_KEY_PEM: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/keys/public.pem"
+));
+
+#[test]
+fn instance_actor_document() {
+    let instance = InstanceActor::new("https://example.com", PUBLIC_KEY_PEM).unwrap();
+
+    let actor = instance.to_object();
+    assert_eq!(actor.id.as_deref(), Some("https://example.com/actor"));
+    assert_eq!(actor.typ, vec!["Application".to_string()]);
+    let actor_items = actor.actor_items.as_ref().unwrap();
+    assert_eq!(
+        actor_items.preferred_username.as_deref(),
+        Some("example.com")
+    );
+    assert_eq!(
+        actor_items.endpoints.get("sharedInbox").map(String::as_str),
+        Some("https://example.com/inbox"),
+    );
+    let key = actor.security_items.public_key.as_ref().unwrap();
+    assert_eq!(key.id, "https://example.com/actor#main-key");
+
+    let round_trip =
+        activitist::model::Object::from_json_bytes(&actor.to_json_bytes().unwrap()).unwrap();
+    assert_eq!(round_trip, actor);
+}
+
+#[test]
+fn instance_actor_webfinger() {
+    let instance = InstanceActor::new("https://example.com/", PUBLIC_KEY_PEM)
+        .unwrap()
+        .with_username("relay");
+
+    assert!(instance.matches_resource("acct:relay@example.com"));
+    assert!(instance.matches_resource("https://example.com/actor"));
+    assert!(!instance.matches_resource("acct:alice@example.com"));
+    assert_eq!(
+        instance.webfinger(),
+        json!({
+            "subject": "acct:relay@example.com",
+            "aliases": ["https://example.com/actor"],
+            "links": [{
+                "rel": "self",
+                "type": "application/activity+json",
+                "href": "https://example.com/actor"
+            }]
+        }),
+    );
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn instance_actor_signer() {
+    let private_key_pem = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/keys/private.pem"
+    ));
+    let instance = InstanceActor::new("https://example.com", PUBLIC_KEY_PEM).unwrap();
+    assert!(instance.signer().is_none());
+
+    let instance = instance.with_private_key(private_key_pem).unwrap();
+    let signer = instance.signer().unwrap();
+    assert_eq!(signer.key_id, "https://example.com/actor#main-key");
+    assert!(!signer.sign(b"Subscribe").unwrap().is_empty());
+    assert!(!format!("{:?}", instance).contains("PRIVATE KEY"));
+
+    assert!(InstanceActor::new("https://example.com", PUBLIC_KEY_PEM)
+        .unwrap()
+        .with_private_key(PUBLIC_KEY_PEM)
+        .is_err());
+}
+
+#[cfg(feature = "signing")]
+#[test]
+fn instance_actor_key_mismatch() {
+    let private_key_pem = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/keys/private.pem"
+    ));
+    let other_public_key_pem = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/keys/other_public.pem"
+    ));
+    // The public key of `private.pem` in PKCS#1.
+    let rsa_public_key_pem = concat!(
+        "-----BEGIN RSA PUBLIC KEY-----\n",
+        "MIIBCgKCAQEAnrwMOEODdDZenTheyLf7KBTW8BkVlbRdfM6Kka3MFEQ/qCNfpxOQ\n",
+        "jfq2IIJqkkvxd4Jt69xCjSw4Bg5nzHkEGc4afRzpKU+I+/W/Z4NmBxbsVH/EU3iQ\n",
+        "L6d1GGjh3UV0bDP0zD+dc12QsdAGPHcsALbiXCk3tqGbE8XPHQ8bSZXJ9goqi3By\n",
+        "0636NT83Z1i32jMs8zbhhMGZpU8IObn4V6nEXYRUks1HIZ4WMSm8M6TLxgvzpWPc\n",
+        "mNz3CsImidm4qThnMxwgp+clHsu/C3mk8tIfmucZzeDsqT5brg+E6GjNeKOE4N1K\n",
+        "gSYepYnFeW5enUVD4CrIITTSzx4NbsZsRQIDAQAB\n",
+        "-----END RSA PUBLIC KEY-----\n",
+    );
+
+    assert!(
+        InstanceActor::new("https://example.com", rsa_public_key_pem)
+            .unwrap()
+            .with_private_key(private_key_pem)
+            .is_ok()
+    );
+    assert!(
+        InstanceActor::new("https://example.com", other_public_key_pem)
+            .unwrap()
+            .with_private_key(private_key_pem)
+            .is_err()
+    );
+}
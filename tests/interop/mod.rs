@@ -0,0 +1,127 @@
+#[cfg(feature = "activitypub_federation")]
+mod activitypub_federation {
+    use activitist::interop::activitypub_federation::{Activity, ActivityReceiver};
+    use activitist::model as ap_model;
+    use activitypub_federation::{config::Data, traits::ActivityHandler};
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+
+    #[derive(Clone)]
+    struct App;
+
+    #[async_trait::async_trait]
+    impl ActivityReceiver for App {
+        type Error = String;
+
+        async fn receive(_activity: ap_model::Object, _data: &Data<Self>) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    // The bounds activitypub_federation puts on activities given to
+    // `receive_activity`.
+    fn assert_receivable<A>()
+    where
+        A: ActivityHandler<DataType = App> + DeserializeOwned + Send + 'static,
+    {
+    }
+
+    #[test]
+    fn activity_handler() {
+        assert_receivable::<Activity<App>>();
+
+        let activity: Activity<App> = serde_json::from_value(json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/activities/1",
+            "type": "Like",
+            "actor": "https://example.com/users/sample",
+            "object": "https://remote.example/notes/1"
+        }))
+        .unwrap();
+        assert_eq!(activity.id().as_str(), "https://example.com/activities/1");
+        assert_eq!(
+            activity.actor().as_str(),
+            "https://example.com/users/sample"
+        );
+        assert_eq!(activity.object().typ, vec!["Like".to_string()]);
+
+        assert!(serde_json::from_value::<Activity<App>>(json!({
+            "id": "https://example.com/activities/1",
+            "type": "Like",
+            "object": "https://remote.example/notes/1"
+        }))
+        .is_err());
+    }
+}
+
+#[cfg(feature = "activitystreams")]
+mod activitystreams {
+    use activitist::model as ap_model;
+    use activitystreams::{
+        activity::Create,
+        base::AnyBase,
+        object::{ApObject, Note},
+        prelude::*,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn convert_note() {
+        let note: ApObject<Note> = serde_json::from_value(json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "content": "Hello",
+            "published": "2023-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        let data = ap_model::Object::try_from(note).unwrap();
+
+        assert_eq!(data.id, Some("https://example.com/notes/1".to_string()));
+        assert_eq!(data.typ, vec!["Note".to_string()]);
+        assert_eq!(data.object_items.content, vec!["Hello".to_string()]);
+
+        let note = ApObject::<Note>::try_from(&data).unwrap();
+        assert_eq!(
+            note.id_unchecked().map(|id| id.as_str()),
+            Some("https://example.com/notes/1"),
+        );
+        assert_eq!(
+            serde_json::to_value(note).unwrap()["content"],
+            json!("Hello")
+        );
+    }
+
+    #[test]
+    fn convert_activity() {
+        let mut activity = ap_model::Object::new_activity(
+            Some("https://example.com/activities/1".to_string()),
+            vec!["Create".to_string()],
+            vec![ap_model::ObjectOrLink::Link(
+                "https://example.com/users/sample".into(),
+            )],
+            vec![ap_model::ObjectOrLink::Link(
+                "https://example.com/notes/1".into(),
+            )],
+        );
+        activity.object_items.to = vec![ap_model::ObjectOrLink::Link(
+            "https://www.w3.org/ns/activitystreams#Public".into(),
+        )];
+
+        let create = Create::try_from(&activity).unwrap();
+        assert_eq!(
+            create.actor().unwrap().as_single_id().map(|id| id.as_str()),
+            Some("https://example.com/users/sample"),
+        );
+        assert_eq!(ap_model::Object::try_from(create).unwrap(), activity);
+
+        let reference = AnyBase::try_from(&ap_model::ObjectOrLink::Link(
+            "https://example.com/notes/1".into(),
+        ))
+        .unwrap();
+        assert_eq!(
+            reference.id().map(|id| id.as_str()),
+            Some("https://example.com/notes/1"),
+        );
+    }
+}
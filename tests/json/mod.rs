@@ -1,8 +1,19 @@
-use std::{collections::HashMap, str::FromStr};
+pub mod ndjson;
+#[cfg(feature = "testing")]
+pub mod size;
+#[cfg(feature = "tracing")]
+pub mod spans;
+pub mod stream;
 
-use activitist::json::JsonSerde;
+use std::str::FromStr;
+
+use activitist::json::{
+    DefaultContext, DeserializeOptions, JsonSerde, ParseMode, SerializeOptions,
+};
 use activitist::model as ap_model;
+use activitist::nodeinfo::NodeInfo;
 use chrono::DateTime;
+use serde_json::{json, Value};
 
 #[test]
 fn deserialize_context() {
@@ -21,6 +32,88 @@ fn deserialize_context() {
 }
 
 #[test]
+fn round_trip_context_keywords() {
+    let serialized_data = r#"[
+        "https://www.w3.org/ns/activitystreams",
+        {
+            "@language": "und",
+            "@vocab": null,
+            "toot": "http://joinmastodon.org/ns#"
+        }
+    ]"#;
+
+    let data = ap_model::Context::from_json_str(serialized_data).unwrap();
+
+    let ap_model::Context::Mix(items) = &data else {
+        panic!("unexpected context: {:?}", data);
+    };
+    assert_eq!(
+        items[1],
+        ap_model::Context::TermDefs(ap_model::ContextDefinition {
+            language: Some(Some("und".to_string())),
+            vocab: Some(None),
+            base: None,
+            protected: None,
+            terms: ap_model::Map::from_iter([(
+                "toot".to_string(),
+                ap_model::Iri::from("http://joinmastodon.org/ns#"),
+            )]),
+        })
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(&data.to_json_string().unwrap()).unwrap(),
+        serde_json::from_str::<Value>(serialized_data).unwrap()
+    );
+}
+
+#[test]
+fn round_trip_expanded_term_definitions() {
+    let serialized_data = r#"{
+        "@protected": true,
+        "focalPoint": {
+            "@container": "@list",
+            "@id": "toot:focalPoint"
+        },
+        "proof": {
+            "@id": "sec:proof",
+            "@type": "@id",
+            "@container": "@graph",
+            "@context": {
+                "@protected": true,
+                "created": "http://purl.org/dc/terms/created"
+            }
+        }
+    }"#;
+
+    let data = ap_model::Context::from_json_str(serialized_data).unwrap();
+
+    let ap_model::Context::TermDefs(defs) = &data else {
+        panic!("unexpected context: {:?}", data);
+    };
+    assert_eq!(defs.protected, Some(true));
+    assert_eq!(
+        defs.terms["focalPoint"],
+        ap_model::Iri::TypeCoercion {
+            id: "toot:focalPoint".to_string(),
+            typ: None,
+            container: Some("@list".to_string()),
+            protected: None,
+            context: None,
+        }
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(&data.to_json_string().unwrap()).unwrap(),
+        serde_json::from_str::<Value>(serialized_data).unwrap()
+    );
+}
+
+#[test]
+#[cfg(all(
+    feature = "mastodon",
+    feature = "place",
+    feature = "question",
+    feature = "security"
+))]
 fn serialize_object() {
     let data = ap_model::Object {
         schema_context: Some(ap_model::Context::object_default()),
@@ -45,17 +138,20 @@ fn serialize_object() {
             to: vec![],
             url: Some(ap_model::Link::from("https://example.com/@sample")),
             content: vec![],
-            content_map: HashMap::new(),
+            content_map: ap_model::Map::new(),
             name: vec!["Name".to_string()],
-            name_map: HashMap::new(),
+            name_map: ap_model::Map::new(),
             duration: None,
             media_type: vec![],
+            start_time: None,
             end_time: None,
             published: Some(DateTime::from_str("2023-04-15T11:22:33Z").unwrap()),
             summary: vec!["Summary".to_string()],
-            summary_map: HashMap::new(),
+            summary_map: ap_model::Map::new(),
             updated: None,
             describes: None,
+            likes: None,
+            shares: None,
         },
         actor_items: Some(ap_model::ActorItems {
             inbox: "https://example.com/users/sample/inbox".to_string(),
@@ -63,7 +159,7 @@ fn serialize_object() {
             following: "https://example.com/users/sample/following".to_string(),
             followers: "https://example.com/users/sample/followers".to_string(),
             preferred_username: Some("sample".to_string()),
-            endpoints: HashMap::from([(
+            endpoints: ap_model::Map::from([(
                 "sharedInbox".to_string(),
                 "https://example.com/inbox".to_string(),
             )]),
@@ -100,6 +196,8 @@ fn serialize_object() {
         property_items: ap_model::PropertyItems {
             value: None,
         },
+        schema_org_items: ap_model::SchemaOrgItems::empty(),
+        json_items: ap_model::JsonItems::empty(),
     };
     let serialized_data = data.to_value().unwrap();
     let expected_data = r#"{
@@ -175,6 +273,12 @@ fn serialize_object() {
 }
 
 #[test]
+#[cfg(all(
+    feature = "mastodon",
+    feature = "place",
+    feature = "question",
+    feature = "security"
+))]
 fn deserialize_object() {
     let serialized_data = r#"{
         "@context": [
@@ -256,13 +360,13 @@ fn deserialize_object() {
                 ap_model::Context::from("https://w3id.org/security/v1"),
                 ap_model::Context::from([
                     ("manuallyApprovesFollowers", ap_model::Iri::from("as:manuallyApprovesFollowers")),
-                    ("alsoKnownAs", ap_model::Iri::TypeCoercion { id: "as:alsoKnownAs".to_string(), typ: Some("@id".to_string()) }),
-                    ("movedTo", ap_model::Iri::TypeCoercion { id: "as:movedTo".to_string(), typ: Some("@id".to_string()) })
+                    ("alsoKnownAs", ap_model::Iri::TypeCoercion { id: "as:alsoKnownAs".to_string(), typ: Some("@id".to_string()), container: None, protected: None, context: None }),
+                    ("movedTo", ap_model::Iri::TypeCoercion { id: "as:movedTo".to_string(), typ: Some("@id".to_string()), container: None, protected: None, context: None })
                 ]),
                 ap_model::Context::from([
-                    ("featuredTags", ap_model::Iri::TypeCoercion { id: "toot:featuredTags".to_string(), typ: Some("@id".to_string()) }),
-                    ("devices", ap_model::Iri::TypeCoercion { id: "toot:devices".to_string(), typ: Some("@id".to_string()) }),
-                    ("featured", ap_model::Iri::TypeCoercion { id: "toot:featured".to_string(), typ: Some("@id".to_string()) }),
+                    ("featuredTags", ap_model::Iri::TypeCoercion { id: "toot:featuredTags".to_string(), typ: Some("@id".to_string()), container: None, protected: None, context: None }),
+                    ("devices", ap_model::Iri::TypeCoercion { id: "toot:devices".to_string(), typ: Some("@id".to_string()), container: None, protected: None, context: None }),
+                    ("featured", ap_model::Iri::TypeCoercion { id: "toot:featured".to_string(), typ: Some("@id".to_string()), container: None, protected: None, context: None }),
                     ("toot", ap_model::Iri::from("http://joinmastodon.org/ns#")),
                     ("discoverable", ap_model::Iri::from("toot:discoverable")),
                     ("suspended", ap_model::Iri::from("toot:suspended")),
@@ -294,17 +398,20 @@ fn deserialize_object() {
                 to: vec![],
                 url: Some(ap_model::Link::from("https://example.com/@sample")),
                 content: vec![],
-                content_map: HashMap::new(),
+                content_map: ap_model::Map::new(),
                 name: vec!["Name".to_string()],
-                name_map: HashMap::new(),
+                name_map: ap_model::Map::new(),
                 duration: None,
                 media_type: vec![],
+                start_time: None,
                 end_time: None,
                 published: Some(DateTime::from_str("2023-04-15T11:22:33Z").unwrap()),
                 summary: vec!["Summary".to_string()],
-                summary_map: HashMap::new(),
+                summary_map: ap_model::Map::new(),
                 updated: None,
                 describes: None,
+                likes: None,
+                shares: None,
             },
             actor_items: Some(ap_model::ActorItems {
                 inbox: "https://example.com/users/sample/inbox".to_string(),
@@ -312,7 +419,7 @@ fn deserialize_object() {
                 following: "https://example.com/users/sample/following".to_string(),
                 followers: "https://example.com/users/sample/followers".to_string(),
                 preferred_username: Some("sample".to_string()),
-                endpoints: HashMap::from([
+                endpoints: ap_model::Map::from([
                     ("sharedInbox".to_string(), "https://example.com/inbox".to_string()),
                 ]),
             }),
@@ -348,6 +455,8 @@ fn deserialize_object() {
             property_items: ap_model::PropertyItems {
                 value: None,
             },
+            schema_org_items: ap_model::SchemaOrgItems::empty(),
+            json_items: ap_model::JsonItems::empty(),
         },
     );
 }
@@ -378,3 +487,800 @@ fn deserialize_collection() {
         ),
     );
 }
+
+#[test]
+fn round_trip_likes_and_shares() {
+    let serialized_data = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/notes/1",
+        "type": "Note",
+        "likes": {
+            "id": "https://example.com/notes/1/likes",
+            "type": "Collection",
+            "totalItems": 3
+        },
+        "shares": "https://example.com/notes/1/shares"
+    }"#;
+
+    let data = ap_model::Object::from_json_str(serialized_data).unwrap();
+
+    let Some(ap_model::ObjectOrLink::Object(likes)) = data.object_items.likes.as_deref() else {
+        panic!("unexpected likes: {:?}", data.object_items.likes);
+    };
+    assert_eq!(likes.collection_items.total_items, Some(3));
+    assert_eq!(
+        data.object_items
+            .shares
+            .as_deref()
+            .and_then(|shares| shares.id()),
+        Some("https://example.com/notes/1/shares")
+    );
+    assert_eq!(
+        serde_json::from_str::<Value>(&data.to_json_string().unwrap()).unwrap(),
+        serde_json::from_str::<Value>(serialized_data).unwrap()
+    );
+}
+
+#[test]
+fn deserialize_attributed_to_with_legacy_name() {
+    for key in ["attributedTo", "attributeTo"] {
+        let data = ap_model::Object::from_value(&json!({
+            "type": "Note",
+            key: "https://example.com/users/sample"
+        }))
+        .unwrap();
+        assert_eq!(
+            data.object_items.attributed_to[0].id(),
+            Some("https://example.com/users/sample")
+        );
+        assert_eq!(
+            data.to_value().unwrap(),
+            json!({
+                "type": "Note",
+                "attributedTo": "https://example.com/users/sample"
+            })
+        );
+    }
+}
+
+#[test]
+fn round_trip_schema_org_terms() {
+    let video = ap_model::Object::from_value(&json!({
+        "type": "Video",
+        "category": {"identifier": "15", "name": "Science & Technology"},
+        "licence": {"identifier": "1", "name": "Attribution"}
+    }))
+    .unwrap();
+    assert_eq!(
+        video.schema_org_items.category,
+        vec![ap_model::SchemaValue::Thing {
+            identifier: Some("15".to_string()),
+            name: Some("Science & Technology".to_string()),
+        }]
+    );
+    assert!(video.schema_org_items.license.is_some());
+
+    let mut note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    note.schema_org_items.license = Some(ap_model::SchemaValue::Text(
+        "https://creativecommons.org/licenses/by/4.0/".to_string(),
+    ));
+    note.schema_org_items.identifier = Some("note-1".to_string());
+    assert_eq!(
+        note.to_value().unwrap(),
+        json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                {
+                    "schema": "http://schema.org#",
+                    "license": "schema:license",
+                    "identifier": "schema:identifier"
+                }
+            ],
+            "type": "Note",
+            "license": "https://creativecommons.org/licenses/by/4.0/",
+            "identifier": "note-1"
+        })
+    );
+
+    // Values of other shapes are kept as they are.
+    let other = ap_model::Object::from_value(&json!({"type": "Note", "category": 3})).unwrap();
+    assert!(other.schema_org_items.category.is_empty());
+    assert_eq!(other.to_value().unwrap()["category"], json!(3));
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn preserve_property_order() {
+    let serialized_data = r#"{"type":"Note","id":"https://example.com/notes/1","contentMap":{"ja":"こんにちは","en":"Hello"},"@context":"https://www.w3.org/ns/activitystreams","replies":{"type":"Collection","id":"https://example.com/notes/1/replies"}}"#;
+
+    let data: ap_model::Object = ap_model::Object::from_json_str(serialized_data).unwrap();
+
+    assert_eq!(data.to_json_string().unwrap(), serialized_data);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn async_read_write_object() {
+    let serialized_data = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/users/sample/outbox",
+        "type": "OrderedCollection",
+        "totalItems": 0
+    }"#;
+
+    let data = ap_model::Object::io_read_json_async(serialized_data.as_bytes(), 1024)
+        .await
+        .unwrap();
+    assert_eq!(
+        data,
+        ap_model::Object::new_collection(
+            Some("https://example.com/users/sample/outbox".to_string()),
+            vec!["OrderedCollection".to_string()],
+            Some(0),
+            None,
+            None,
+            None,
+            vec![],
+            vec![],
+        ),
+    );
+
+    let mut writer = vec![];
+    data.io_write_json_async(&mut writer).await.unwrap();
+    assert_eq!(writer, data.to_json_bytes().unwrap());
+
+    assert!(
+        ap_model::Object::io_read_json_async(serialized_data.as_bytes(), 64)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn deserialize_object_array() {
+    let serialized_data = r#"[
+        {
+            "id": "https://example.com/activities/1",
+            "type": "Like",
+            "actor": "https://example.com/users/sample",
+            "object": "https://example.com/notes/1"
+        },
+        "https://example.com/activities/2"
+    ]"#;
+
+    let data: Vec<ap_model::ObjectOrLink> = Vec::from_json_str(serialized_data).unwrap();
+    assert_eq!(
+        data,
+        vec![
+            ap_model::ObjectOrLink::Object(
+                ap_model::Object::new_activity(
+                    Some("https://example.com/activities/1".to_string()),
+                    vec!["Like".to_string()],
+                    vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+                        "https://example.com/users/sample"
+                    ))],
+                    vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+                        "https://example.com/notes/1"
+                    ))],
+                )
+                .clone_without_schema_context()
+            ),
+            ap_model::ObjectOrLink::Link(ap_model::Link::from("https://example.com/activities/2")),
+        ],
+    );
+
+    assert!(Vec::<ap_model::Object>::from_json_str(serialized_data).is_err());
+
+    let objects: Vec<ap_model::Object> = Vec::from_json_str(r#"[{"type": "Note"}]"#).unwrap();
+    assert_eq!(objects.to_json_string().unwrap(), r#"[{"type":"Note"}]"#);
+}
+
+#[test]
+fn embed_in_serde_types() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Envelope {
+        received: String,
+        activity: ap_model::Object,
+    }
+
+    let envelope: Envelope = serde_json::from_str(
+        r#"{
+            "received": "2023-01-01T00:00:00Z",
+            "activity": {
+                "@context": "https://www.w3.org/ns/activitystreams",
+                "id": "https://example.com/activities/1",
+                "type": "Like",
+                "actor": "https://example.com/users/sample",
+                "object": "https://example.com/notes/1"
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        envelope.activity,
+        ap_model::Object::new_activity(
+            Some("https://example.com/activities/1".to_string()),
+            vec!["Like".to_string()],
+            vec![ap_model::ObjectOrLink::Link(
+                "https://example.com/users/sample".into()
+            )],
+            vec![ap_model::ObjectOrLink::Link(
+                "https://example.com/notes/1".into()
+            )],
+        ),
+    );
+    assert_eq!(
+        serde_json::to_value(&envelope).unwrap()["activity"],
+        envelope.activity.to_value().unwrap(),
+    );
+}
+
+#[test]
+fn deserialize_string_encoded_numbers() {
+    let serialized = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/users/sample/followers",
+        "type": "OrderedCollection",
+        "totalItems": "5",
+        "first": {
+            "type": "Link",
+            "href": "https://example.com/users/sample/followers?page=1",
+            "width": "10"
+        }
+    }"#;
+
+    let data = ap_model::Object::from_json_str(serialized).unwrap();
+    assert_eq!(data.collection_items.total_items, Some(5));
+    assert_eq!(
+        data.collection_items.first,
+        Some(Box::new(ap_model::ObjectOrLink::Link(ap_model::Link {
+            typ: vec!["Link".to_string()],
+            width: Some(10),
+            ..ap_model::Link::from("https://example.com/users/sample/followers?page=1")
+        }))),
+    );
+
+    assert!(ap_model::Object::from_json_str(&serialized.replace("\"5\"", "\"five\"")).is_err());
+    assert!(
+        ap_model::Object::from_json_bytes_with_mode(serialized.as_bytes(), ParseMode::Lax).is_ok()
+    );
+    assert!(
+        ap_model::Object::from_json_bytes_with_mode(serialized.as_bytes(), ParseMode::Strict)
+            .unwrap_err()
+            .to_string()
+            .ends_with("must be a number")
+    );
+}
+
+#[test]
+#[cfg(feature = "mastodon")]
+fn deserialize_non_standard_booleans() {
+    let serialized = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/users/sample",
+        "type": "Person",
+        "manuallyApprovesFollowers": "true",
+        "discoverable": 0,
+        "suspended": 1
+    }"#;
+
+    let data = ap_model::Object::from_json_str(serialized).unwrap();
+    assert_eq!(
+        data.activity_streams_ext_items.manually_approves_followers,
+        Some(true),
+    );
+    assert_eq!(data.mastodon_ext_items.discoverable, Some(false));
+    assert_eq!(data.mastodon_ext_items.suspended, Some(true));
+
+    assert!(ap_model::Object::from_json_str(&serialized.replace("\"true\"", "\"yes\"")).is_err());
+    assert!(ap_model::Object::from_json_str(&serialized.replace(": 1", ": 2")).is_err());
+    assert!(
+        ap_model::Object::from_json_bytes_with_mode(serialized.as_bytes(), ParseMode::Strict)
+            .unwrap_err()
+            .to_string()
+            .ends_with("must be a boolean")
+    );
+}
+
+#[test]
+fn keep_explicit_nulls() {
+    let data = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/users/sample",
+        "type": "Person",
+        "summary": null
+    }))
+    .unwrap();
+
+    assert_eq!(data.object_items.summary, Vec::<String>::new());
+    assert_eq!(data.json_items.null_properties, vec!["summary".to_string()]);
+    assert_eq!(data.to_value().unwrap()["summary"], Value::Null);
+
+    let mut cleared = ap_model::Object::new(
+        Some("https://example.com/users/sample".to_string()),
+        vec!["Person".to_string()],
+    );
+    cleared.json_items.null_properties = vec!["summary".to_string()];
+    assert_eq!(data, cleared);
+    assert_ne!(
+        data,
+        ap_model::Object::new(
+            Some("https://example.com/users/sample".to_string()),
+            vec!["Person".to_string()],
+        ),
+    );
+}
+
+#[test]
+fn detect_duplicate_keys() {
+    let serialized = br#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/activities/1",
+        "type": "Create",
+        "actor": "https://example.com/users/sample",
+        "object": [{"id": "https://example.com/notes/1", "a/b": 1, "a/b": 2}],
+        "actor": "https://attacker.example/users/sample"
+    }"#;
+
+    assert_eq!(
+        activitist::json::find_duplicate_keys(serialized).unwrap(),
+        vec!["/object/0/a~1b".to_string(), "/actor".to_string()],
+    );
+    assert!(ap_model::Object::from_json_bytes_with_mode(serialized, ParseMode::Lax).is_ok());
+    assert_eq!(
+        ap_model::Object::from_json_bytes_with_mode(serialized, ParseMode::Strict)
+            .unwrap_err()
+            .to_string(),
+        "duplicate key: /object/0/a~1b",
+    );
+}
+
+#[test]
+fn deserialize_large_counts() {
+    let data = ap_model::Object::from_value(&json!({
+        "type": "OrderedCollectionPage",
+        "totalItems": 5_000_000_000_u64,
+        "startIndex": 4_294_967_296_u64
+    }))
+    .unwrap();
+
+    assert_eq!(data.collection_items.total_items, Some(5_000_000_000));
+    assert_eq!(
+        data.ordered_collection_page_items.start_index,
+        Some(4_294_967_296),
+    );
+}
+
+#[test]
+fn serialize_with_always_arrays() {
+    let data = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Create",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "object": {
+            "type": "Note",
+            "summary": null,
+            "cc": "https://example.com/users/sample/followers",
+            "url": "https://example.com/notes/1",
+            "ext:meta": {"name": "extension", "tag": "kept"}
+        }
+    }))
+    .unwrap();
+    let options = SerializeOptions {
+        always_arrays: true,
+        default_context: DefaultContext::Omit,
+        ..SerializeOptions::default()
+    };
+    let serialized: Value =
+        serde_json::from_str(&data.to_json_string_with(&options).unwrap()).unwrap();
+
+    assert_eq!(
+        serialized,
+        json!({
+            "type": ["Create"],
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "object": [{
+                "type": ["Note"],
+                "summary": null,
+                "cc": ["https://example.com/users/sample/followers"],
+                "url": "https://example.com/notes/1",
+                "ext:meta": {"name": "extension", "tag": "kept"}
+            }]
+        }),
+    );
+}
+
+#[test]
+fn serialize_with_sorted_keys() {
+    let data = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "content": "Hello"
+    }))
+    .unwrap();
+    let options = SerializeOptions {
+        default_context: DefaultContext::Include,
+        sort_keys: true,
+        ..SerializeOptions::default()
+    };
+
+    assert_eq!(
+        data.to_json_string_with(&options).unwrap(),
+        r#"{"@context":"https://www.w3.org/ns/activitystreams","content":"Hello","id":"https://example.com/notes/1","type":"Note"}"#,
+    );
+}
+
+#[test]
+fn serialize_with_peer_quirks() {
+    let data = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Note",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "tag": {
+            "type": "Hashtag",
+            "name": "#rust"
+        }
+    }))
+    .unwrap();
+    let peer = |name: &str| NodeInfo {
+        software_name: name.to_string(),
+        software_version: None,
+        protocols: vec!["activitypub".to_string()],
+    };
+    let serialize = |options: &SerializeOptions| -> Value {
+        serde_json::from_str(&data.to_json_string_with(options).unwrap()).unwrap()
+    };
+
+    let serialized = serialize(&SerializeOptions::for_peer(&peer("mastodon")));
+    assert_eq!(
+        serialized["to"],
+        json!(["https://www.w3.org/ns/activitystreams#Public"])
+    );
+    assert_eq!(serialized["tag"][0]["name"], "#rust");
+    assert_eq!(
+        serialized["@context"],
+        "https://www.w3.org/ns/activitystreams"
+    );
+
+    let serialized = serialize(&SerializeOptions::for_peer(&peer("akkoma")));
+    assert_eq!(
+        serialized["@context"],
+        json!([
+            "https://www.w3.org/ns/activitystreams",
+            { "Hashtag": "as:Hashtag", "sensitive": "as:sensitive" }
+        ])
+    );
+    assert_eq!(serialized["tag"]["name"], "#rust");
+
+    assert_eq!(
+        SerializeOptions::for_peer(&peer("misskey")),
+        SerializeOptions::default()
+    );
+}
+
+#[test]
+#[cfg(feature = "mastodon")]
+fn deserialize_with_unknown_property_policy() {
+    use activitist::json::UnknownProperties;
+
+    let serialized = br#"{
+        "type": "Create",
+        "object": {"type": "Note", "_misskey_quote": "https://example.com/notes/0"},
+        "featured": "https://example.com/featured"
+    }"#;
+
+    let options = DeserializeOptions {
+        unknown_properties: UnknownProperties::Drop,
+        ..DeserializeOptions::default()
+    };
+    let data = ap_model::Object::from_json_with(serialized, &options).unwrap();
+    let object = match &data.activity_items.object[0] {
+        ap_model::ObjectOrLink::Object(object) => object,
+        _ => panic!("object must be embedded"),
+    };
+    assert!(object.json_items.unknown_properties.is_empty());
+    assert_eq!(
+        data.mastodon_ext_items.featured.as_deref(),
+        Some("https://example.com/featured"),
+    );
+
+    let options = DeserializeOptions {
+        unknown_properties: UnknownProperties::Reject,
+        ..DeserializeOptions::default()
+    };
+    assert_eq!(
+        ap_model::Object::from_json_with(serialized, &options)
+            .unwrap_err()
+            .to_string(),
+        "unknown property: /object/_misskey_quote",
+    );
+}
+
+#[test]
+fn deserialize_with_depth_limit() {
+    let serialized = br#"{"type": "Note", "tag": [{"type": "Hashtag", "name": "rust"}]}"#;
+
+    let options = DeserializeOptions {
+        max_depth: Some(3),
+        ..DeserializeOptions::default()
+    };
+    assert!(ap_model::Object::from_json_with(serialized, &options).is_ok());
+
+    let options = DeserializeOptions {
+        max_depth: Some(2),
+        ..DeserializeOptions::default()
+    };
+    assert!(ap_model::Object::from_json_with(serialized, &options)
+        .unwrap_err()
+        .to_string()
+        .starts_with("nested deeper than 2"),);
+
+    // Rejected while parsing, before serde_json's recursion limit.
+    let deep = format!(r#"{{"type": "Note", "ext:deep": {}"#, "[".repeat(100_000));
+    assert!(ap_model::Object::from_json_with(deep.as_bytes(), &options)
+        .unwrap_err()
+        .to_string()
+        .starts_with("nested deeper than 2"),);
+}
+
+#[test]
+fn deserialize_multiple_functional_values() {
+    let value = json!({
+        "type": "Note",
+        "published": ["2023-01-01T00:00:00Z", "2023-01-02T00:00:00Z"],
+        "movedTo": []
+    });
+
+    let data = ap_model::Object::from_value_with_mode(&value, ParseMode::Lax).unwrap();
+    assert_eq!(
+        data.object_items.published,
+        Some(DateTime::from_str("2023-01-01T00:00:00Z").unwrap()),
+    );
+    assert_eq!(data.activity_streams_ext_items.moved_to, None);
+
+    assert_eq!(
+        ap_model::Object::from_value_with_mode(&value, ParseMode::Strict)
+            .unwrap_err()
+            .to_string(),
+        "published must have a single value",
+    );
+}
+
+#[test]
+fn reject_invalid_rels() {
+    let value = json!({
+        "type": "Note",
+        "tag": [{"type": "Mention", "href": "https://example.com/@alice", "rel": ["me", "nofollow noopener"]}]
+    });
+
+    assert!(ap_model::Object::from_value_with_mode(&value, ParseMode::Lax).is_ok());
+    assert_eq!(
+        ap_model::Object::from_value_with_mode(&value, ParseMode::Strict)
+            .unwrap_err()
+            .to_string(),
+        "rel must not contain spaces or commas",
+    );
+}
+
+#[test]
+fn keep_extension_values_as_they_are() {
+    let value = json!({
+        "type": "Note",
+        "published": "2023-01-01T00:00:00Z",
+        "ext:shape": {"width": "10", "radius": ["1", "2"], "closed": "yes"}
+    });
+
+    let data = ap_model::Object::from_value_with_mode(&value, ParseMode::Strict).unwrap();
+    assert_eq!(
+        data.json_items.unknown_properties["ext:shape"],
+        json!({"width": "10", "radius": ["1", "2"], "closed": "yes"}),
+    );
+}
+
+#[test]
+fn deserialize_relative_ids() {
+    let serialized = br#"{
+        "type": "Create",
+        "id": "/activities/1",
+        "actor": "/users/alice",
+        "to": ["followers", "https://www.w3.org/ns/activitystreams#Public"],
+        "object": {"type": "Note", "id": "notes/1", "inReplyTo": "/notes/0"}
+    }"#;
+
+    let options = DeserializeOptions {
+        base_url: Some("https://example.com/users/alice/".to_string()),
+        ..DeserializeOptions::default()
+    };
+    let data = ap_model::Object::from_json_with(serialized, &options).unwrap();
+    assert_eq!(data.id.as_deref(), Some("https://example.com/activities/1"));
+    assert_eq!(
+        data.activity_items.object[0].id(),
+        Some("https://example.com/users/alice/notes/1"),
+    );
+    assert_eq!(
+        data.activity_items.actor[0].id(),
+        Some("https://example.com/users/alice"),
+    );
+    assert_eq!(
+        data.object_items
+            .to
+            .iter()
+            .map(|to| to.id().unwrap())
+            .collect::<Vec<_>>(),
+        vec![
+            "https://example.com/users/alice/followers",
+            "https://www.w3.org/ns/activitystreams#Public",
+        ],
+    );
+    assert_eq!(
+        data.activity_items.object[0]
+            .as_object()
+            .unwrap()
+            .object_items
+            .in_reply_to[0]
+            .id(),
+        Some("https://example.com/notes/0"),
+    );
+
+    let serialized = br#"{
+        "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            {"@base": "https://other.example/"}
+        ],
+        "type": "Note",
+        "id": "notes/1"
+    }"#;
+    let data = ap_model::Object::from_json_with(serialized, &options).unwrap();
+    assert_eq!(data.id.as_deref(), Some("https://other.example/notes/1"));
+}
+
+#[test]
+fn serialize_relative_ids() {
+    let data = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "to": [
+            "https://example.com/users/alice/followers",
+            "https://other.example/users/bob"
+        ],
+        "object": {"type": "Note", "id": "https://other.example/notes/1"}
+    }))
+    .unwrap();
+    let options = SerializeOptions {
+        relative_to: Some("https://example.com/".to_string()),
+        ..SerializeOptions::default()
+    };
+    let serialized: Value =
+        serde_json::from_str(&data.to_json_string_with(&options).unwrap()).unwrap();
+
+    assert_eq!(serialized["id"], json!("activities/1"));
+    assert_eq!(serialized["actor"], json!("users/alice"));
+    assert_eq!(
+        serialized["to"],
+        json!(["users/alice/followers", "https://other.example/users/bob"])
+    );
+    assert_eq!(
+        serialized["object"]["id"],
+        json!("https://other.example/notes/1")
+    );
+
+    let options = SerializeOptions {
+        relative_to: Some("example.com".to_string()),
+        ..SerializeOptions::default()
+    };
+    assert!(data.to_json_string_with(&options).is_err());
+}
+
+#[cfg(feature = "nfc")]
+#[test]
+fn normalize_strings_to_nfc() {
+    // "Café" with a combining acute accent.
+    let decomposed = "Cafe\u{301}";
+    let url = format!("https://example.com/{}", decomposed);
+    let serialized = json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "name": decomposed,
+        "url": url,
+        "tag": {"type": "Hashtag", "href": url, "name": format!("#{}", decomposed)}
+    })
+    .to_string();
+    let options = DeserializeOptions {
+        normalize_nfc: true,
+        ..DeserializeOptions::default()
+    };
+
+    let data = ap_model::Object::from_json_with(serialized.as_bytes(), &options).unwrap();
+    assert_eq!(data.object_items.name, vec!["Caf\u{e9}".to_string()]);
+    let tag = data.object_items.tag[0].as_link().unwrap();
+    assert_eq!(tag.name, vec!["#Caf\u{e9}".to_string()]);
+    assert_eq!(tag.href, url);
+    assert_eq!(data.object_items.url.as_ref().unwrap().href, url);
+
+    let mut data = data;
+    data.object_items.content = vec![decomposed.to_string()];
+    let options = SerializeOptions {
+        normalize_nfc: true,
+        ..SerializeOptions::default()
+    };
+    let serialized: Value =
+        serde_json::from_str(&data.to_json_string_with(&options).unwrap()).unwrap();
+    assert_eq!(serialized["content"], json!("Caf\u{e9}"));
+}
+
+#[test]
+fn deserialize_batch() {
+    let serialized = br#"[
+        {"type": "Create", "id": "https://example.com/activities/1"},
+        {"type": "Announce", "id": "https://example.com/activities/2", "id": "x"},
+        "not an object",
+        {"type": "Like", "id": "https://example.com/activities/3"}
+    ]"#;
+
+    let items =
+        ap_model::Object::from_json_batch(serialized, &DeserializeOptions::from(ParseMode::Strict))
+            .unwrap();
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(
+        items[0].as_ref().unwrap().id.as_deref(),
+        Some("https://example.com/activities/1"),
+    );
+    assert_eq!(
+        items[1].as_ref().unwrap_err().to_string(),
+        "item 1: duplicate key: /id",
+    );
+    assert!(items[2].is_err());
+    assert_eq!(items[3].as_ref().unwrap().typ, vec!["Like".to_string()]);
+
+    let items =
+        ap_model::Object::from_json_batch(br#"{"type": "Note"}"#, &DeserializeOptions::default())
+            .unwrap();
+    assert_eq!(items.len(), 1);
+}
+
+#[test]
+#[cfg(not(feature = "mastodon"))]
+fn keep_properties_of_disabled_groups() {
+    let serialized = r#"{"id":"https://example.com/users/sample","type":"Person","featured":"https://example.com/users/sample/featured","discoverable":true}"#;
+
+    let data = ap_model::Object::from_json_str(serialized).unwrap();
+    assert_eq!(
+        data.json_items.unknown_properties.get("discoverable"),
+        Some(&serde_json::Value::Bool(true)),
+    );
+    assert_eq!(
+        data.to_value().unwrap(),
+        serde_json::from_str::<Value>(serialized).unwrap(),
+    );
+}
+
+#[test]
+fn estimate_json_size() {
+    let serialized = r##"{
+        "@context": ["https://www.w3.org/ns/activitystreams", {"Hashtag": "as:Hashtag"}],
+        "id": "https://example.com/notes/1",
+        "type": "Note",
+        "attributedTo": "https://example.com/users/alice",
+        "to": ["https://www.w3.org/ns/activitystreams#Public"],
+        "cc": ["https://example.com/users/alice/followers", "https://example.com/users/bob"],
+        "content": "<p>\"Hello\",\n\tworld</p>",
+        "contentMap": {"en": "<p>Hello</p>"},
+        "published": "2023-04-15T11:22:33Z",
+        "tag": [{"type": "Hashtag", "name": "#hello"}],
+        "replies": {"type": "Collection", "totalItems": 12, "items": []},
+        "sensitive": false,
+        "_misskey_content": "Hello 🌏"
+    }"##;
+    let note = ap_model::Object::from_json_str(serialized).unwrap();
+
+    assert_eq!(
+        note.estimated_json_size(),
+        note.to_json_bytes().unwrap().len()
+    );
+    let mut bigger = note.clone();
+    bigger.object_items.content = vec!["a".repeat(1000)];
+    assert!(bigger.estimated_json_size() > note.estimated_json_size() + 900);
+}
@@ -0,0 +1,55 @@
+use activitist::json::ndjson::{NdjsonReader, NdjsonWriter};
+use activitist::model as ap_model;
+
+#[test]
+fn read_ndjson_stream() {
+    let serialized_data = concat!(
+        r#"{"id":"https://example.com/activities/1","type":"Create"}"#,
+        "\n\n",
+        r#"{"id":"https://example.com/activities/2","type":"Like"}"#,
+        "\n",
+        r#"{"id": 3}"#,
+        "\n",
+    );
+
+    let mut reader: NdjsonReader<_, ap_model::Object> =
+        NdjsonReader::new(serialized_data.as_bytes());
+    let first = reader.next().unwrap().unwrap();
+    assert_eq!(first.typ, vec!["Create".to_string()]);
+    let second = reader.next().unwrap().unwrap();
+    assert_eq!(second.typ, vec!["Like".to_string()]);
+    let err = reader.next().unwrap().unwrap_err();
+    assert!(err.to_string().starts_with("line 4:"));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn write_ndjson_stream() {
+    let mut writer = NdjsonWriter::new(vec![]);
+    writer
+        .write(&ap_model::Object::new_activity(
+            Some("https://example.com/activities/1".to_string()),
+            vec!["Like".to_string()],
+            vec![],
+            vec![],
+        ))
+        .unwrap();
+    writer
+        .write(&ap_model::Link::from("https://example.com/notes/1"))
+        .unwrap();
+
+    let written = String::from_utf8(writer.into_inner()).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 2);
+
+    let reader: NdjsonReader<_, ap_model::Object> = NdjsonReader::new(lines[0].as_bytes());
+    assert_eq!(
+        reader.map(Result::unwrap).collect::<Vec<_>>(),
+        vec![ap_model::Object::new_activity(
+            Some("https://example.com/activities/1".to_string()),
+            vec!["Like".to_string()],
+            vec![],
+            vec![],
+        )],
+    );
+}
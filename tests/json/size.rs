@@ -0,0 +1,13 @@
+use activitist::json::JsonSerde;
+use activitist::testing;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn estimate_json_size(object in testing::object()) {
+        prop_assert_eq!(
+            object.estimated_json_size(),
+            object.to_json_bytes().unwrap().len()
+        );
+    }
+}
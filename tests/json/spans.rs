@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex};
+
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+// Records the spans and events with their fields, as `name field=value`.
+#[derive(Clone, Default)]
+struct RecordingSubscriber {
+    records: Arc<Mutex<Vec<String>>>,
+}
+
+struct FieldsVisitor(String);
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push_str(&format!(" {}={:?}", field.name(), value));
+    }
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut visitor = FieldsVisitor(span.metadata().name().to_string());
+        span.record(&mut visitor);
+        let mut records = self.records.lock().unwrap();
+        records.push(visitor.0);
+        Id::from_u64(records.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldsVisitor(format!("event {}", event.metadata().level()));
+        event.record(&mut visitor);
+        self.records.lock().unwrap().push(visitor.0);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn trace_parsing() {
+    let subscriber = RecordingSubscriber::default();
+    let serialized = r#"{"type":"Note","id":"https://example.com/notes/1"}"#;
+
+    tracing::subscriber::with_default(subscriber.clone(), || {
+        ap_model::Object::from_json_bytes(serialized.as_bytes()).unwrap();
+        assert!(ap_model::Object::from_json_bytes(b"{").is_err());
+    });
+
+    let records = subscriber.records.lock().unwrap();
+    assert_eq!(
+        records[0],
+        format!("from_json_bytes size={}", serialized.len())
+    );
+    assert!(records.contains(&"from_json_bytes size=1".to_string()));
+    assert!(records
+        .iter()
+        .any(|record| record.starts_with("event ERROR error=")));
+}
@@ -0,0 +1,48 @@
+use activitist::json::stream::{self, OrderedCollectionWriter};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::{json, Value};
+
+fn items() -> Vec<ap_model::ObjectOrLink> {
+    vec![
+        ap_model::ObjectOrLink::Link(ap_model::Link::from("https://example.com/activities/2")),
+        ap_model::ObjectOrLink::Object(
+            ap_model::Object::from_value(&json!({
+                "id": "https://example.com/activities/1",
+                "type": "Create",
+                "object": "https://example.com/notes/1"
+            }))
+            .unwrap(),
+        ),
+    ]
+}
+
+#[test]
+fn stream_ordered_collection() {
+    let envelope = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/users/alice/outbox",
+        "type": "OrderedCollection",
+        "totalItems": 2
+    }))
+    .unwrap();
+
+    let written =
+        stream::write_ordered_collection(Vec::new(), &envelope, items().into_iter().map(Ok))
+            .unwrap();
+    let mut collection = envelope.clone();
+    collection.ordered_collection_items.ordered_items = items();
+    assert_eq!(
+        serde_json::from_slice::<Value>(&written).unwrap(),
+        collection.to_value().unwrap()
+    );
+
+    let empty = ap_model::Object::new(None, vec![]);
+    let mut writer = OrderedCollectionWriter::new(Vec::new(), &empty).unwrap();
+    writer.write_item(&items()[0]).unwrap();
+    assert_eq!(writer.count(), 1);
+    assert_eq!(
+        String::from_utf8(writer.finish().unwrap()).unwrap(),
+        r#"{"@context":"https://www.w3.org/ns/activitystreams","orderedItems":["https://example.com/activities/2"]}"#
+    );
+}
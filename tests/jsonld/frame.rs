@@ -0,0 +1,95 @@
+use activitist::jsonld::frame::{self, Frame};
+use activitist::model as ap_model;
+use serde_json::json;
+
+#[test]
+fn frame_node_in_graph() {
+    let document = json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "@graph": [
+            {
+                "id": "https://example.com/activities/1",
+                "type": "Create",
+                "actor": "https://example.com/users/alice",
+                "object": "https://example.com/notes/1"
+            },
+            {
+                "id": "https://example.com/users/alice",
+                "type": "Person",
+                "preferredUsername": "alice",
+                "inbox": "https://example.com/users/alice/inbox",
+                "outbox": "https://example.com/users/alice/outbox",
+                "following": "https://example.com/users/alice/following",
+                "followers": "https://example.com/users/alice/followers"
+            },
+            {
+                "id": "https://example.com/notes/1",
+                "type": "Note",
+                "content": "Hello"
+            }
+        ]
+    });
+
+    let actor = frame::frame(
+        &document,
+        &Frame {
+            typ: Some("Person".to_string()),
+            ..Frame::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(actor.id.as_deref(), Some("https://example.com/users/alice"));
+    assert_eq!(
+        actor.actor_items.unwrap().inbox,
+        "https://example.com/users/alice/inbox"
+    );
+
+    let create = frame::frame(
+        &document,
+        &Frame {
+            typ: Some("https://www.w3.org/ns/activitystreams#Create".to_string()),
+            ..Frame::default()
+        },
+    )
+    .unwrap();
+    let object = create.activity_items.object[0].as_object().unwrap();
+    assert_eq!(object.object_items.content, vec!["Hello".to_string()]);
+    let actor = create.activity_items.actor[0].as_object().unwrap();
+    assert_eq!(actor.typ, vec!["Person".to_string()]);
+}
+
+#[test]
+fn frame_expanded_document() {
+    let document = json!([{
+        "@id": "https://example.com/activities/2",
+        "@type": ["https://www.w3.org/ns/activitystreams#Like"],
+        "https://www.w3.org/ns/activitystreams#actor": [{"@id": "https://example.com/users/alice"}],
+        "https://www.w3.org/ns/activitystreams#object": [{"@id": "https://remote.example/notes/1"}],
+        "https://www.w3.org/ns/activitystreams#published": [{
+            "@type": "http://www.w3.org/2001/XMLSchema#dateTime",
+            "@value": "2023-01-01T00:00:00Z"
+        }]
+    }]);
+
+    let like = frame::frame(&document, &Frame::default()).unwrap();
+    assert_eq!(like.typ, vec!["Like".to_string()]);
+    assert_eq!(
+        like.activity_items.object,
+        vec![ap_model::ObjectOrLink::Link(ap_model::Link::simple(
+            "https://remote.example/notes/1"
+        ))],
+    );
+    assert!(like.object_items.published.is_some());
+    assert_eq!(
+        frame::frame(
+            &document,
+            &Frame {
+                typ: Some("Follow".to_string()),
+                ..Frame::default()
+            },
+        )
+        .unwrap_err()
+        .to_string(),
+        "no node matches the frame",
+    );
+}
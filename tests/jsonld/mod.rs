@@ -0,0 +1,112 @@
+pub mod frame;
+
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::{json, Value};
+
+const SERIALIZED_ACTOR: &str = r#"{
+    "@context": [
+        "https://www.w3.org/ns/activitystreams",
+        "https://w3id.org/security/v1",
+        {
+            "toot": "http://joinmastodon.org/ns#",
+            "discoverable": "toot:discoverable",
+            "indexable": "toot:indexable",
+            "memorial": {
+                "@id": "toot:memorial",
+                "@type": "xsd:boolean"
+            }
+        }
+    ],
+    "id": "https://example.com/users/sample",
+    "type": "Person",
+    "name": "Name",
+    "discoverable": true,
+    "indexable": false,
+    "toot:memorial": false,
+    "https://example.com/ns#custom": "custom",
+    "publicKey": {
+        "id": "https://example.com/users/sample#main-key",
+        "owner": "https://example.com/users/sample"
+    }
+}"#;
+
+#[test]
+fn get_property_by_expanded_iri() {
+    let data = ap_model::Object::from_json_str(SERIALIZED_ACTOR).unwrap();
+
+    assert_eq!(
+        data.get_property("https://www.w3.org/ns/activitystreams#name")
+            .unwrap(),
+        Some(Value::String("Name".to_string())),
+    );
+    assert_eq!(
+        data.get_property("http://joinmastodon.org/ns#discoverable")
+            .unwrap(),
+        Some(Value::Bool(true)),
+    );
+    assert_eq!(
+        data.get_property("http://joinmastodon.org/ns#indexable")
+            .unwrap(),
+        Some(Value::Bool(false)),
+    );
+    assert_eq!(
+        data.get_property("http://joinmastodon.org/ns#memorial")
+            .unwrap(),
+        Some(Value::Bool(false)),
+    );
+    assert_eq!(
+        data.get_property("https://example.com/ns#custom").unwrap(),
+        Some(Value::String("custom".to_string())),
+    );
+    assert_eq!(
+        data.get_property("https://w3id.org/security#publicKey")
+            .unwrap(),
+        Some(json!({
+            "id": "https://example.com/users/sample#main-key",
+            "owner": "https://example.com/users/sample"
+        })),
+    );
+    assert_eq!(
+        data.get_property("http://joinmastodon.org/ns#suspended")
+            .unwrap(),
+        None,
+    );
+}
+
+#[test]
+fn keep_unknown_properties() {
+    let data = ap_model::Object::from_json_str(SERIALIZED_ACTOR).unwrap();
+
+    assert_eq!(
+        data.json_items.unknown_properties.get("indexable"),
+        Some(&Value::Bool(false)),
+    );
+    assert_eq!(
+        data.to_value().unwrap(),
+        serde_json::from_str::<Value>(SERIALIZED_ACTOR).unwrap(),
+    );
+}
+
+#[test]
+fn expand_with_vocab() {
+    let context = ap_model::Context::from_json_str(
+        r#"[
+            "https://www.w3.org/ns/activitystreams",
+            { "@vocab": "https://example.com/ns#", "@language": "ja" }
+        ]"#,
+    )
+    .unwrap();
+
+    let active_context = activitist::jsonld::ActiveContext::new(&context);
+
+    assert_eq!(
+        active_context.expand_iri("custom").as_deref(),
+        Some("https://example.com/ns#custom"),
+    );
+    assert_eq!(
+        active_context.expand_iri("name").as_deref(),
+        Some("https://www.w3.org/ns/activitystreams#name"),
+    );
+    assert_eq!(active_context.language(), Some("ja"));
+}
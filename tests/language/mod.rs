@@ -0,0 +1,51 @@
+use activitist::json::JsonSerde;
+use activitist::language::{preferred_languages, select};
+use activitist::model::{Map, Object};
+use serde_json::json;
+
+#[test]
+fn parse_accept_language() {
+    assert_eq!(
+        preferred_languages("fr;q=0.5, ja-JP, en;q=0.8, de;q=0, *;q=0.1"),
+        vec!["ja-JP", "en", "fr", "*"],
+    );
+    assert_eq!(preferred_languages(""), Vec::<&str>::new());
+}
+
+#[test]
+fn select_from_language_map() {
+    let mut map = Map::new();
+    map.insert("en-US".to_string(), "Hello".to_string());
+    map.insert("ja".to_string(), "こんにちは".to_string());
+
+    assert_eq!(select("ja-JP, en", None, None, &map), Some("こんにちは"));
+    assert_eq!(select("en-GB;q=0.9, fr", None, None, &map), Some("Hello"));
+    assert_eq!(select("de", Some("Hi"), None, &map), Some("Hi"));
+    assert_eq!(select("de", Some("Hi"), Some("en"), &map), Some("Hi"));
+    assert_eq!(select("en", Some("Hi"), Some("en"), &map), Some("Hi"));
+}
+
+#[test]
+fn localized_content_of_object() {
+    let note = Object::from_value(&json!({
+        "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            {"@language": "en"}
+        ],
+        "id": "https://example.com/notes/1",
+        "type": "Note",
+        "name": "Greeting",
+        "content": "<p>Hello</p>",
+        "contentMap": {
+            "ja": "<p>こんにちは</p>",
+            "fr": "<p>Bonjour</p>"
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(note.content_for("fr-CA, ja;q=0.5"), Some("<p>Bonjour</p>"));
+    assert_eq!(note.content_for("en-US, ja;q=0.5"), Some("<p>Hello</p>"));
+    assert_eq!(note.content_for("de"), Some("<p>Hello</p>"));
+    assert_eq!(note.name_for("ja"), Some("Greeting"));
+    assert_eq!(note.summary_for("ja"), None);
+}
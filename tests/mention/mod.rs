@@ -0,0 +1,71 @@
+use std::error::Error;
+
+use activitist::json::JsonSerde;
+use activitist::mention::{self, Acct};
+use activitist::model as ap_model;
+use serde_json::json;
+
+fn lookup(user: &str, host: &str) -> Result<String, Box<dyn Error>> {
+    match (user, host) {
+        ("alice", "example.com") => Ok("https://example.com/users/alice".to_string()),
+        ("bob", "other.example") => Ok("https://other.example/u/bob".to_string()),
+        _ => Err(format!("{}@{} is not found", user, host).into()),
+    }
+}
+
+#[test]
+fn scan_mentions() {
+    assert_eq!(
+        mention::mentions("@alice, @Bob@Other.example. @bob@other.example mail@example.com"),
+        vec![
+            Acct {
+                user: "alice",
+                host: None
+            },
+            Acct {
+                user: "Bob",
+                host: Some("Other.example")
+            },
+        ]
+    );
+    assert!(mention::mentions("https://example.com/@alice a@b").is_empty());
+}
+
+#[test]
+fn compose_mention_tags_and_addressing() {
+    let public = "https://www.w3.org/ns/activitystreams#Public";
+    let mut note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    note.object_items.content =
+        vec!["<p><span>@alice</span> @bob@other.example @carol@nowhere.example</p>".to_string()];
+    note.object_items.to = vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(public))];
+    note.object_items.cc = vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+        "https://other.example/u/bob",
+    ))];
+    let unresolved = mention::add_mention_tags(&mut note, "example.com", &lookup);
+
+    assert_eq!(unresolved, vec!["carol@nowhere.example"]);
+    let value = note.to_value().unwrap();
+    assert_eq!(value["to"], json!(public));
+    assert_eq!(
+        value["cc"],
+        json!([
+            "https://other.example/u/bob",
+            "https://example.com/users/alice"
+        ])
+    );
+    assert_eq!(
+        value["tag"],
+        json!([
+            {"type": "Mention", "name": "@alice@example.com", "href": "https://example.com/users/alice"},
+            {"type": "Mention", "name": "@bob@other.example", "href": "https://other.example/u/bob"}
+        ])
+    );
+
+    let mut direct = ap_model::Object::new(None, vec!["Note".to_string()]);
+    direct.object_items.content = vec!["@alice@example.com hi".to_string()];
+    mention::add_mention_tags(&mut direct, "example.com", &lookup);
+    assert_eq!(
+        direct.to_value().unwrap()["to"],
+        json!("https://example.com/users/alice")
+    );
+}
@@ -0,0 +1,87 @@
+use activitist::json::{JsonSerde, SerdeJsonValue};
+use activitist::mf2::{self, Mf2Item, Mf2Value};
+use activitist::model as ap_model;
+
+#[test]
+fn note_to_h_entry() {
+    let mut data = ap_model::Object::from_json_str(
+        r##"{
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "content": "<p>Hello &amp; welcome</p>",
+            "published": "2023-01-01T00:00:00Z",
+            "tag": [{"type": "Hashtag", "name": "#rust"}],
+            "attachment": [{
+                "type": "Document",
+                "mediaType": "image/png",
+                "url": "https://example.com/media/1.png"
+            }]
+        }"##,
+    )
+    .unwrap();
+    let mut author = ap_model::Object::new(
+        Some("https://example.com/users/sample".to_string()),
+        vec!["Person".to_string()],
+    );
+    author.object_items.name = vec!["Sample".to_string()];
+    data.object_items.attributed_to = vec![ap_model::ObjectOrLink::Object(author)];
+    let entry = mf2::to_h_entry(&data);
+
+    assert_eq!(entry.typ, vec!["h-entry".to_string()]);
+    assert_eq!(entry.texts("url"), vec!["https://example.com/notes/1"]);
+    assert_eq!(
+        entry.properties.get("content"),
+        Some(&vec![Mf2Value::Html {
+            html: "<p>Hello &amp; welcome</p>".to_string(),
+            value: "Hello & welcome".to_string(),
+        }]),
+    );
+    assert_eq!(entry.texts("published"), vec!["2023-01-01T00:00:00+00:00"]);
+    assert_eq!(
+        entry.texts("author"),
+        vec!["https://example.com/users/sample"]
+    );
+    assert_eq!(entry.texts("category"), vec!["rust"]);
+    assert_eq!(
+        entry.texts("photo"),
+        vec!["https://example.com/media/1.png"]
+    );
+}
+
+#[test]
+fn h_entry_to_article() {
+    let entry = SerdeJsonValue::<Mf2Item>::from_json_str(
+        r#"{
+            "type": ["h-entry"],
+            "properties": {
+                "name": ["Title"],
+                "content": [{"html": "<p>Body</p>", "value": "Body"}],
+                "url": ["https://example.com/posts/1"],
+                "published": ["2023-01-01T09:00:00+09:00"],
+                "author": [{
+                    "type": ["h-card"],
+                    "properties": {"url": ["https://example.com/"], "name": ["Sample"]}
+                }],
+                "category": ["indieweb"]
+            }
+        }"#,
+    )
+    .unwrap()
+    .value;
+    let data = mf2::from_h_entry(&entry).unwrap();
+
+    assert_eq!(data.typ, vec!["Article".to_string()]);
+    assert_eq!(data.id, Some("https://example.com/posts/1".to_string()));
+    assert_eq!(data.object_items.name, vec!["Title".to_string()]);
+    assert_eq!(data.object_items.content, vec!["<p>Body</p>".to_string()]);
+    assert_eq!(
+        data.object_items.published.map(|date| date.to_rfc3339()),
+        Some("2023-01-01T00:00:00+00:00".to_string()),
+    );
+    assert_eq!(
+        data.object_items.attributed_to,
+        vec![ap_model::ObjectOrLink::Link("https://example.com/".into())],
+    );
+    assert_eq!(data.object_items.tag.len(), 1);
+}
@@ -0,0 +1,107 @@
+use activitist::json::JsonSerde;
+use activitist::migration;
+use activitist::model as ap_model;
+
+fn actor(id: &str) -> ap_model::Object {
+    let mut actor = ap_model::Object::new(Some(id.to_string()), vec!["Person".to_string()]);
+    actor.actor_items = Some(ap_model::ActorItems {
+        inbox: format!("{}/inbox", id),
+        outbox: format!("{}/outbox", id),
+        following: format!("{}/following", id),
+        followers: format!("{}/followers", id),
+        preferred_username: None,
+        endpoints: ap_model::Map::new(),
+    });
+    actor
+}
+
+#[test]
+fn export_followers() {
+    let serialized_data = r#"{
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://old.example/users/alice/followers",
+        "type": "OrderedCollection",
+        "totalItems": 3,
+        "first": {
+            "type": "OrderedCollectionPage",
+            "orderedItems": [
+                "https://a.example/users/bob",
+                "https://b.example/users/carol"
+            ],
+            "next": {
+                "type": "OrderedCollectionPage",
+                "orderedItems": "https://c.example/users/dave"
+            }
+        }
+    }"#;
+    let data = ap_model::Object::from_json_str(serialized_data).unwrap();
+
+    assert_eq!(
+        migration::export_account_iris(&data),
+        vec![
+            "https://a.example/users/bob".to_string(),
+            "https://b.example/users/carol".to_string(),
+            "https://c.example/users/dave".to_string(),
+        ],
+    );
+}
+
+#[test]
+fn move_and_refollow() {
+    let mut old_actor = actor("https://old.example/users/alice");
+    let mut new_actor = actor("https://new.example/users/alice");
+
+    assert!(migration::move_activity(None, &old_actor, &new_actor).is_err());
+
+    migration::add_also_known_as(&mut new_actor, "https://old.example/users/alice");
+    migration::set_moved_to(&mut old_actor, "https://new.example/users/alice");
+    assert_eq!(
+        old_actor.activity_streams_ext_items.moved_to,
+        Some("https://new.example/users/alice".to_string()),
+    );
+
+    let activity = migration::move_activity(
+        Some("https://old.example/users/alice#moves/1".to_string()),
+        &old_actor,
+        &new_actor,
+    )
+    .unwrap();
+    assert_eq!(
+        activity.to_value().unwrap(),
+        serde_json::json!({
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://old.example/users/alice#moves/1",
+            "type": "Move",
+            "actor": "https://old.example/users/alice",
+            "object": "https://old.example/users/alice",
+            "target": "https://new.example/users/alice",
+            "to": "https://old.example/users/alice/followers",
+        }),
+    );
+
+    let mut count = 0;
+    let follows = migration::refollow_activities(
+        &activity,
+        &new_actor,
+        &["https://local.example/users/bob".to_string()],
+        || {
+            count += 1;
+            format!("https://local.example/follows/{}", count)
+        },
+    )
+    .unwrap();
+    assert_eq!(follows.len(), 1);
+    assert_eq!(
+        follows[0].id,
+        Some("https://local.example/follows/1".to_string())
+    );
+    assert_eq!(
+        follows[0].activity_items.object,
+        vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+            "https://new.example/users/alice"
+        ))],
+    );
+
+    let impostor = actor("https://new.example/users/mallory");
+    assert!(migration::refollow_activities(&activity, &impostor, &[], String::new).is_err());
+}
@@ -0,0 +1,214 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use serde_json::json;
+
+#[test]
+fn build_link() {
+    let link = ap_model::Link::builder("https://example.com/media/1.png")
+        .rel("preview")
+        .media_type("image/png")
+        .dimensions(640, 480)
+        .name("A preview")
+        .build();
+
+    let value = link.to_value().unwrap();
+    assert_eq!(value["href"], json!("https://example.com/media/1.png"));
+    assert_eq!(value["name"], json!("A preview"));
+    assert_eq!(value["rel"], json!("preview"));
+    assert_eq!(
+        (value["width"].clone(), value["height"].clone()),
+        (json!(640), json!(480))
+    );
+    assert_eq!(
+        ap_model::Link::from_value(&link.to_value().unwrap()).unwrap(),
+        link,
+    );
+}
+
+#[test]
+fn simple_link_is_bare_iri() {
+    let mut note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    note.object_items.to = vec![ap_model::ObjectOrLink::Link(ap_model::Link::simple(
+        "https://www.w3.org/ns/activitystreams#Public",
+    ))];
+
+    assert_eq!(
+        note.to_value().unwrap()["to"],
+        json!("https://www.w3.org/ns/activitystreams#Public"),
+    );
+}
+
+#[test]
+fn typed_rels() {
+    let link = ap_model::Link::builder("https://example.com/@alice")
+        .rel(ap_model::Rel::Me)
+        .rel("canonical")
+        .rel("x-custom")
+        .build();
+
+    assert_eq!(
+        link.rels(),
+        vec![
+            ap_model::Rel::Me,
+            ap_model::Rel::Canonical,
+            ap_model::Rel::Other("x-custom".to_string()),
+        ],
+    );
+    assert_eq!(ap_model::Rel::Self_.to_string(), "self");
+    assert!(ap_model::Rel::is_valid("nofollow"));
+    assert!(!ap_model::Rel::is_valid("nofollow noopener"));
+    assert!(!ap_model::Rel::is_valid("a,b"));
+}
+
+#[test]
+fn access_object_or_link() {
+    let mut note = ap_model::Object::new(
+        Some("https://example.com/notes/1".to_string()),
+        vec!["Note".to_string()],
+    );
+    note.object_items.url = Some(ap_model::Link::simple("https://example.com/@alice/1"));
+    let embedded = ap_model::ObjectOrLink::Object(note.clone());
+    let reference =
+        ap_model::ObjectOrLink::Link(ap_model::Link::simple("https://example.com/notes/1"));
+
+    assert_eq!(embedded.id(), Some("https://example.com/notes/1"));
+    assert_eq!(embedded.href(), Some("https://example.com/@alice/1"));
+    assert_eq!(embedded.as_object(), Some(&note));
+    assert_eq!(embedded.as_link(), None);
+    assert_eq!(reference.id(), Some("https://example.com/notes/1"));
+    assert_eq!(reference.href(), Some("https://example.com/notes/1"));
+    assert_eq!(reference.as_object(), None);
+    assert_eq!(reference.into_object(), None);
+    assert_eq!(embedded.into_object(), Some(note));
+}
+
+#[test]
+fn list_referenced_iris() {
+    let data = ap_model::Object::from_value(&json!({
+        "id": "https://example.com/activities/1",
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "to": ["https://www.w3.org/ns/activitystreams#Public"],
+        "object": {
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "inReplyTo": "https://remote.example/notes/9",
+            "to": ["https://www.w3.org/ns/activitystreams#Public"],
+            "url": "https://example.com/@alice/1",
+            "tag": [{"type": "Mention", "href": "https://remote.example/users/bob"}],
+            "replies": {
+                "id": "https://example.com/notes/1/replies",
+                "type": "Collection",
+                "first": "https://example.com/notes/1/replies?page=1"
+            }
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        data.referenced_iris().collect::<Vec<_>>(),
+        vec![
+            "https://www.w3.org/ns/activitystreams#Public",
+            "https://example.com/users/alice",
+            "https://example.com/notes/1",
+            "https://remote.example/notes/9",
+            "https://remote.example/users/bob",
+            "https://example.com/notes/1/replies",
+            "https://example.com/notes/1/replies?page=1",
+        ],
+    );
+}
+
+#[test]
+fn actor_type() {
+    let actor = ap_model::Object::new_actor(
+        "https://example.com/c/rust".to_string(),
+        ap_model::ActorType::Group,
+        ap_model::ActorItems {
+            inbox: "https://example.com/c/rust/inbox".to_string(),
+            outbox: "https://example.com/c/rust/outbox".to_string(),
+            following: "https://example.com/c/rust/following".to_string(),
+            followers: "https://example.com/c/rust/followers".to_string(),
+            preferred_username: Some("rust".to_string()),
+            endpoints: ap_model::Map::new(),
+        },
+    );
+    assert_eq!(actor.typ, vec!["Group".to_string()]);
+    assert_eq!(actor.actor_type(), Some(ap_model::ActorType::Group));
+
+    let note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    assert_eq!(note.actor_type(), None);
+    assert_eq!(
+        ap_model::ActorType::from_type("Service"),
+        Some(ap_model::ActorType::Service)
+    );
+}
+
+#[test]
+fn validate_username() {
+    for username in ["alice", "Alice_01", "a.b-c"] {
+        assert!(
+            ap_model::ActorItems::validate_username(username).is_ok(),
+            "{}",
+            username
+        );
+    }
+    for username in [
+        "",
+        "al ice",
+        "alice@example.com",
+        ".alice",
+        "alice-",
+        "ålice",
+    ] {
+        assert!(
+            ap_model::ActorItems::validate_username(username).is_err(),
+            "{}",
+            username
+        );
+    }
+    assert!(ap_model::ActorItems::validate_username(&"a".repeat(31)).is_err());
+
+    assert_eq!(
+        ap_model::ActorItems::normalize_username(" @Alice "),
+        "alice"
+    );
+}
+
+#[test]
+fn bump_updated_on_modification() {
+    let mut note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "content": "Hello",
+        "published": "2024-01-01T00:00:00Z"
+    }))
+    .unwrap();
+    let now = chrono::DateTime::parse_from_rfc3339("2024-01-02T00:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+
+    assert!(!note.modify(now, |_| {}));
+    assert_eq!(note.object_items.updated, None);
+    assert!(note.modify(now, |note| {
+        note.object_items.content = vec!["Hello, world".to_string()];
+    }));
+    assert_eq!(note.object_items.updated, Some(now));
+
+    let update = ap_model::Object::new_update(
+        Some("https://example.com/activities/1".to_string()),
+        "https://example.com/users/alice",
+        &note,
+    )
+    .unwrap();
+    assert_eq!(update.object_items.published, Some(now));
+    assert_eq!(update.object_items.to, note.object_items.to);
+
+    note.object_items.updated = Some(now - chrono::Duration::days(2));
+    assert_eq!(
+        note.validate_timestamps().unwrap_err().to_string(),
+        "updated 2023-12-31T00:00:00+00:00 is before published 2024-01-01T00:00:00+00:00"
+    );
+    assert!(ap_model::Object::new_update(None, "https://example.com/users/alice", &note).is_err());
+}
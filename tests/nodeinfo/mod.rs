@@ -0,0 +1,119 @@
+use std::cell::Cell;
+use std::error::Error;
+
+use activitist::nodeinfo::{self, NodeInfo, PeerCache};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+#[test]
+fn read_nodeinfo() {
+    let discovery = json!({
+        "links": [
+            {
+                "rel": "http://nodeinfo.diaspora.software/ns/schema/2.0",
+                "href": "https://remote.example/nodeinfo/2.0"
+            },
+            {
+                "rel": "http://nodeinfo.diaspora.software/ns/schema/2.1",
+                "href": "https://remote.example/nodeinfo/2.1"
+            },
+            {
+                "rel": "https://example.com/ns/other",
+                "href": "https://remote.example/other"
+            }
+        ]
+    });
+    assert_eq!(
+        nodeinfo::document_url(&discovery).as_deref(),
+        Some("https://remote.example/nodeinfo/2.1")
+    );
+
+    let node_info = NodeInfo::from_json(&json!({
+        "version": "2.1",
+        "software": { "name": "Mastodon", "version": "4.2.10+glitch" },
+        "protocols": ["activitypub"],
+        "usage": { "users": {} },
+        "openRegistrations": false
+    }))
+    .unwrap();
+    assert!(node_info.is("mastodon"));
+    assert!(node_info.speaks_activity_pub());
+    assert!(node_info.version_at_least(&[4, 2]));
+    assert!(!node_info.version_at_least(&[4, 3]));
+    assert!(NodeInfo::from_json(&json!({ "version": "2.0" })).is_err());
+}
+
+#[test]
+fn cache_peers() {
+    let lookups = Cell::new(0);
+    let lookup = |host: &str| -> Result<NodeInfo, Box<dyn Error>> {
+        lookups.set(lookups.get() + 1);
+        match host {
+            "remote.example" => Ok(NodeInfo {
+                software_name: "pleroma".to_string(),
+                software_version: Some("2.6.0".to_string()),
+                protocols: vec!["activitypub".to_string()],
+            }),
+            _ => Err("not found".into()),
+        }
+    };
+    let cache = PeerCache::new(lookup);
+
+    assert!(cache.get("Remote.example", now()).unwrap().is("pleroma"));
+    assert!(cache.get("remote.example", now()).is_some());
+    assert!(cache.get("static.example", now()).is_none());
+    assert!(cache.get("static.example", now()).is_none());
+    assert_eq!(lookups.get(), 2);
+
+    assert!(cache
+        .get("remote.example", now() + Duration::days(2))
+        .is_some());
+    assert_eq!(lookups.get(), 3);
+    cache.invalidate("remote.example");
+    assert!(cache.cached("remote.example").is_none());
+
+    // Failed lookups are retried sooner.
+    assert!(cache
+        .get("static.example", now() + Duration::hours(2))
+        .is_none());
+    assert_eq!(lookups.get(), 4);
+}
+
+#[test]
+fn limit_peer_cache_size() {
+    let mut cache =
+        PeerCache::new(|_: &str| -> Result<NodeInfo, Box<dyn Error>> { Err("not found".into()) });
+    cache.max_entries = 2;
+    let node_info = |name: &str| {
+        NodeInfo::from_json(&json!({
+            "version": "2.1",
+            "software": { "name": name, "version": "1.0" },
+            "protocols": ["activitypub"]
+        }))
+        .unwrap()
+    };
+
+    cache.insert("a.example", Some(node_info("mastodon")), now());
+    cache.insert("b.example", None, now());
+    // The failed lookup of b.example has expired, so it is dropped first.
+    cache.insert(
+        "c.example",
+        Some(node_info("misskey")),
+        now() + Duration::hours(2),
+    );
+    assert!(cache.cached("a.example").is_some());
+    assert!(cache.cached("c.example").is_some());
+
+    cache.insert(
+        "d.example",
+        Some(node_info("pleroma")),
+        now() + Duration::hours(3),
+    );
+    assert!(cache.cached("a.example").is_none());
+    assert!(cache.cached("c.example").is_some());
+    assert!(cache.cached("d.example").is_some());
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use activitist::cache::CachingResolver;
+use activitist::delivery::queue::{DeliveryError, DeliveryQueue, MemoryJobStore};
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::observe::Observer;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+#[derive(Clone, Default)]
+struct RecordingObserver {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingObserver {
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Observer for RecordingObserver {
+    fn delivery_attempted(&self, inbox: &str) {
+        self.record(format!("attempted {}", inbox));
+    }
+
+    fn delivery_succeeded(&self, inbox: &str) {
+        self.record(format!("succeeded {}", inbox));
+    }
+
+    fn delivery_failed(&self, inbox: &str, error: &str) {
+        self.record(format!("failed {}: {}", inbox, error));
+    }
+
+    fn delivery_retry_after(&self, inbox: &str, seconds: i64) {
+        self.record(format!("retry {} after {}", inbox, seconds));
+    }
+
+    fn fetch_cache_hit(&self, iri: &str) {
+        self.record(format!("hit {}", iri));
+    }
+
+    fn fetch_cache_miss(&self, iri: &str) {
+        self.record(format!("miss {}", iri));
+    }
+}
+
+#[test]
+fn observe_deliveries() {
+    let observer = RecordingObserver::default();
+    let mut queue = DeliveryQueue::new(MemoryJobStore::new());
+    queue.observer = Box::new(observer.clone());
+    let activity = ap_model::Object::from_value(&json!({
+        "type": "Like",
+        "id": "https://example.com/activities/1",
+        "actor": "https://example.com/users/alice",
+        "object": "https://remote.example/notes/1"
+    }))
+    .unwrap();
+    queue
+        .enqueue(
+            &activity,
+            &[
+                "https://remote.example/inbox".to_string(),
+                "https://other.example/inbox".to_string(),
+            ],
+            now(),
+        )
+        .unwrap();
+
+    queue
+        .run_once(now(), 10, |job| -> Result<(), Box<dyn Error>> {
+            if job.inbox.starts_with("https://other.example/") {
+                Err(Box::new(DeliveryError {
+                    status: 503,
+                    retry_after: Some("120".to_string()),
+                }))
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+
+    assert_eq!(
+        observer.events(),
+        vec![
+            "attempted https://remote.example/inbox",
+            "succeeded https://remote.example/inbox",
+            "attempted https://other.example/inbox",
+            "failed https://other.example/inbox: delivery failed with status 503, retry after 120",
+            "retry https://other.example/inbox after 120",
+        ]
+    );
+}
+
+#[test]
+fn observe_cache_lookups() {
+    let observer = RecordingObserver::default();
+    let resolver = |iri: &str| -> Result<ap_model::Object, Box<dyn Error>> {
+        ap_model::Object::from_value(&json!({"type": "Note", "id": iri}))
+    };
+    let mut cache = CachingResolver::new(resolver);
+    cache.observer = Box::new(observer.clone());
+
+    cache
+        .resolve_at("https://remote.example/notes/1", now())
+        .unwrap();
+    cache
+        .resolve_at("https://remote.example/notes/1", now())
+        .unwrap();
+
+    assert_eq!(
+        observer.events(),
+        vec![
+            "miss https://remote.example/notes/1",
+            "hit https://remote.example/notes/1",
+        ]
+    );
+}
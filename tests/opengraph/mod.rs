@@ -0,0 +1,61 @@
+use activitist::model as ap_model;
+use activitist::opengraph;
+
+#[test]
+fn preview_from_opengraph() {
+    let html = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Fallback title</title>
+  <meta property="og:title" content="Example &amp; Co.">
+  <meta property='og:description' content='An "example" page'>
+  <meta property="og:image" content="/images/card.png" />
+  <meta property="og:image:type" content="image/png">
+  <meta property="og:image:width" content="1200">
+  <meta property="og:image:height" content="630">
+  <link rel="canonical" href="https://example.com/articles/1">
+</head>
+<body><meta property="og:title" content="Ignored"></body>
+</html>"#;
+    let preview = opengraph::preview_from_html("https://example.com/articles/1?ref=feed", html);
+
+    assert_eq!(preview.typ, vec!["Page".to_string()]);
+    assert_eq!(
+        preview.object_items.url.map(|url| url.href),
+        Some("https://example.com/articles/1".to_string()),
+    );
+    assert_eq!(preview.object_items.name, vec!["Example & Co.".to_string()]);
+    assert_eq!(
+        preview.object_items.summary,
+        vec!["An &quot;example&quot; page".to_string()],
+    );
+    assert_eq!(
+        preview.object_items.image,
+        vec![ap_model::ObjectOrLink::Link(ap_model::Link {
+            media_type: vec!["image/png".to_string()],
+            width: Some(1200),
+            height: Some(630),
+            ..ap_model::Link::from("https://example.com/images/card.png")
+        })],
+    );
+}
+
+#[test]
+fn preview_from_plain_meta() {
+    let html = r#"<html><head>
+<TITLE>Plain page</TITLE>
+<meta name=description content=Description>
+</head></html>"#;
+    let preview = opengraph::preview_from_html("https://example.com/plain", html);
+
+    assert_eq!(
+        preview.object_items.url.map(|url| url.href),
+        Some("https://example.com/plain".to_string()),
+    );
+    assert_eq!(preview.object_items.name, vec!["Plain page".to_string()]);
+    assert_eq!(
+        preview.object_items.summary,
+        vec!["Description".to_string()]
+    );
+    assert!(preview.object_items.image.is_empty());
+}
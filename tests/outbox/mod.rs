@@ -0,0 +1,142 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::outbox::{self, OutboxResponse};
+use activitist::store::{MemoryStore, ObjectStore};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+fn alice() -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "inbox": "https://example.com/users/alice/inbox",
+        "outbox": "https://example.com/users/alice/outbox",
+        "following": "https://example.com/users/alice/following",
+        "followers": "https://example.com/users/alice/followers"
+    }))
+    .unwrap()
+}
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn public() -> Vec<ap_model::ObjectOrLink> {
+    vec![ap_model::ObjectOrLink::Link(ap_model::Link::from(
+        "https://www.w3.org/ns/activitystreams#Public",
+    ))]
+}
+
+#[test]
+fn negotiate_content_type() {
+    assert!(outbox::is_activity_streams_type(
+        "application/activity+json"
+    ));
+    assert!(outbox::is_activity_streams_type(
+        r#"application/ld+json; profile="https://www.w3.org/ns/activitystreams""#
+    ));
+    assert!(!outbox::is_activity_streams_type("application/ld+json"));
+    assert!(!outbox::is_activity_streams_type("application/json"));
+}
+
+#[test]
+fn post_note_to_outbox() {
+    let store = MemoryStore::new();
+    store
+        .put(
+            ap_model::Object::from_value(&json!({
+                "type": "OrderedCollection",
+                "id": "https://example.com/users/alice/outbox",
+                "totalItems": 0,
+                "orderedItems": []
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+    let body = json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Note",
+        "id": "https://evil.example/notes/1",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "content": "Hello"
+    })
+    .to_string();
+    let mut ids = (1..).map(|n| format!("https://example.com/objects/{}", n));
+
+    let response = outbox::post_to_outbox(
+        &alice(),
+        Some("application/activity+json"),
+        body.as_bytes(),
+        &store,
+        || ids.next().unwrap(),
+        now(),
+    )
+    .unwrap();
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.location(), Some("https://example.com/objects/1"));
+
+    let activity = store.get("https://example.com/objects/1").unwrap().unwrap();
+    assert_eq!(activity.typ, vec!["Create".to_string()]);
+    assert_eq!(activity.object_items.to, public());
+    let note = store.get("https://example.com/objects/2").unwrap().unwrap();
+    assert_eq!(note.object_items.published, Some(now()));
+    assert_eq!(
+        note.object_items.attributed_to[0].id(),
+        Some("https://example.com/users/alice")
+    );
+    assert!(store.get("https://evil.example/notes/1").unwrap().is_none());
+    let collection = store
+        .get("https://example.com/users/alice/outbox")
+        .unwrap()
+        .unwrap();
+    assert_eq!(collection.collection_items.total_items, Some(1));
+
+    let impersonation = json!({
+        "type": "Like",
+        "actor": "https://example.com/users/bob",
+        "object": "https://remote.example/notes/1"
+    })
+    .to_string();
+    let response = outbox::post_to_outbox(
+        &alice(),
+        Some("application/activity+json"),
+        impersonation.as_bytes(),
+        &store,
+        || ids.next().unwrap(),
+        now(),
+    )
+    .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let misattribution = json!({
+        "type": "Note",
+        "attributedTo": [
+            "https://example.com/users/alice",
+            "https://example.com/users/bob"
+        ],
+        "content": "Hello"
+    })
+    .to_string();
+    let response = outbox::post_to_outbox(
+        &alice(),
+        Some("application/activity+json"),
+        misattribution.as_bytes(),
+        &store,
+        || ids.next().unwrap(),
+        now(),
+    )
+    .unwrap();
+    assert_eq!(
+        response,
+        OutboxResponse::BadRequest(
+            "object must be attributed to https://example.com/users/alice".to_string()
+        )
+    );
+    assert_eq!(
+        outbox::post_to_outbox(&alice(), None, b"{}", &store, || ids.next().unwrap(), now())
+            .unwrap(),
+        OutboxResponse::UnsupportedMediaType
+    );
+}
@@ -0,0 +1,91 @@
+use activitist::model as ap_model;
+use activitist::paging::{self, Direction, PageQuery};
+use url::Url;
+
+const OUTBOX: &str = "https://example.com/users/alice/outbox";
+
+// Notes 5 to 1, newest first.
+fn items() -> Vec<ap_model::ObjectOrLink> {
+    (1..=5)
+        .rev()
+        .map(|i| {
+            ap_model::ObjectOrLink::Link(ap_model::Link::from(
+                format!("https://example.com/notes/{}", i).as_str(),
+            ))
+        })
+        .collect()
+}
+
+fn ids(items: &[ap_model::ObjectOrLink]) -> Vec<&str> {
+    items
+        .iter()
+        .map(|item| item.id().unwrap().rsplit('/').next().unwrap())
+        .collect()
+}
+
+fn query(name: &str, note: u32) -> PageQuery {
+    let url = format!("{}?{}=https://example.com/notes/{}", OUTBOX, name, note);
+    PageQuery::from_url(&Url::parse(&url).unwrap())
+}
+
+#[test]
+fn window_items() {
+    let items = items();
+    assert_eq!(ids(PageQuery::default().window(&items, 2)), vec!["5", "4"]);
+    assert_eq!(ids(query("max_id", 4).window(&items, 2)), vec!["3", "2"]);
+    assert_eq!(ids(query("since_id", 2).window(&items, 2)), vec!["5", "4"]);
+    assert_eq!(ids(query("min_id", 2).window(&items, 2)), vec!["4", "3"]);
+
+    let unknown = PageQuery {
+        min_id: Some("0".to_string()),
+        ..PageQuery::default()
+    };
+    assert_eq!(ids(unknown.window(&items, 2)), vec!["2", "1"]);
+    let unknown = PageQuery {
+        max_id: Some("0".to_string()),
+        ..PageQuery::default()
+    };
+    assert!(unknown.window(&items, 2).is_empty());
+}
+
+#[test]
+fn link_pages() {
+    let items = items();
+    let page = paging::ordered_page(OUTBOX, &items, &query("max_id", 4), 2).unwrap();
+    assert_eq!(
+        page.id.as_deref(),
+        Some("https://example.com/users/alice/outbox?page=true&max_id=https%3A%2F%2Fexample.com%2Fnotes%2F4")
+    );
+    assert_eq!(
+        ids(&page.ordered_collection_items.ordered_items),
+        vec!["3", "2"]
+    );
+    let next = paging::page_link(&page, Direction::Older).unwrap();
+    assert_eq!(
+        PageQuery::from_url(&Url::parse(next.id().unwrap()).unwrap()),
+        query("max_id", 2)
+    );
+    let prev = paging::page_link(&page, Direction::Newer).unwrap();
+    assert_eq!(
+        PageQuery::from_url(&Url::parse(prev.id().unwrap()).unwrap()),
+        query("min_id", 3)
+    );
+
+    let first = paging::ordered_page(OUTBOX, &items, &PageQuery::default(), 2).unwrap();
+    assert!(paging::page_link(&first, Direction::Newer).is_none());
+}
+
+#[test]
+fn collect_items_in_both_directions() {
+    let items = items();
+    let collection = paging::ordered_collection(OUTBOX, Some(5)).unwrap();
+    let resolver = |iri: &str| -> Result<ap_model::Object, Box<dyn std::error::Error>> {
+        let query = PageQuery::from_url(&Url::parse(iri)?);
+        paging::ordered_page(OUTBOX, &items, &query, 2)
+    };
+
+    let older = paging::collect_items(&collection, &resolver, Direction::Older, 10).unwrap();
+    assert_eq!(ids(&older), vec!["5", "4", "3", "2", "1"]);
+    let newer = paging::collect_items(&collection, &resolver, Direction::Newer, 3).unwrap();
+    assert_eq!(ids(&newer), vec!["1", "2", "3"]);
+}
@@ -0,0 +1,52 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::preflight::{self, AddressingProblem, SigningProblem};
+use serde_json::json;
+
+#[test]
+fn pass_ready_activity() {
+    let activity = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "id": "https://example.com/activities/1",
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "to": "https://www.w3.org/ns/activitystreams#Public",
+        "cc": "https://example.com/users/alice/followers",
+        "object": {
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "contentMap": {"en": "Hello"}
+        }
+    }))
+    .unwrap();
+    let report = preflight::preflight(&activity);
+    assert!(report.is_ok(), "{:?}", report);
+}
+
+#[test]
+fn report_problems_by_check() {
+    let activity = ap_model::Object::from_value(&json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "bcc": ["as:Public", "acct:bob@example.com"],
+        "object": {
+            "type": "Note",
+            "quoteUrl": "https://example.com/notes/0"
+        }
+    }))
+    .unwrap();
+    let report = preflight::preflight(&activity);
+
+    assert!(report.conformance.is_empty());
+    assert_eq!(report.undefined_terms, vec!["quoteUrl"]);
+    assert_eq!(
+        report.addressing,
+        vec![
+            AddressingProblem::InvalidAddressee("acct:bob@example.com".to_string()),
+            AddressingProblem::PublicInBlind,
+        ]
+    );
+    assert_eq!(report.signing, vec![SigningProblem::MissingId]);
+    assert!(!report.is_ok());
+}
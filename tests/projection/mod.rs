@@ -0,0 +1,47 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::projection::Projection;
+use serde_json::json;
+
+#[test]
+fn project_public_actor() {
+    let actor = ap_model::Object::from_value(&json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "inbox": "https://example.com/users/alice/inbox",
+        "outbox": "https://example.com/users/alice/outbox",
+        "following": "https://example.com/users/alice/following",
+        "followers": "https://example.com/users/alice/followers",
+        "endpoints": {
+            "sharedInbox": "https://example.com/inbox",
+            "adminApi": "https://internal.example.com/admin"
+        },
+        "indexable": true,
+        "_internalScore": 42,
+        "attachment": [{
+            "type": "PropertyValue",
+            "name": "Site",
+            "value": "https://alice.example",
+            "_verifiedAt": "2023-01-01T00:00:00Z"
+        }]
+    }))
+    .unwrap();
+
+    let projection = Projection {
+        extension_properties: vec!["indexable".to_string()],
+        ..Projection::default()
+    };
+    let public = projection.project(&actor).to_value().unwrap();
+
+    assert_eq!(
+        public["endpoints"],
+        json!({"sharedInbox": "https://example.com/inbox"})
+    );
+    assert_eq!(public["indexable"], json!(true));
+    assert!(public.get("_internalScore").is_none());
+    assert!(public["attachment"].get("_verifiedAt").is_none());
+    assert_eq!(
+        public["attachment"]["value"],
+        json!("https://alice.example")
+    );
+}
@@ -0,0 +1,101 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::quote::QuoteStyle;
+use serde_json::json;
+
+#[test]
+fn read_quotes_of_each_software() {
+    for value in [
+        json!({"type": "Note", "quoteUrl": "https://example.com/notes/1"}),
+        json!({"type": "Note", "_misskey_quote": "https://example.com/notes/1"}),
+        json!({"type": "Note", "quoteUri": "https://example.com/notes/1"}),
+        json!({"type": "Note", "tag": [{
+            "type": "Link",
+            "mediaType": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+            "href": "https://example.com/notes/1",
+            "rel": "https://misskey-hub.net/ns#_misskey_quote",
+            "name": "RE: https://example.com/notes/1"
+        }]}),
+    ] {
+        let note = ap_model::Object::from_value(&value).unwrap();
+        assert_eq!(note.quoted_object(), Some("https://example.com/notes/1"));
+    }
+    let note = ap_model::Object::from_value(&json!({"type": "Note"})).unwrap();
+    assert_eq!(note.quoted_object(), None);
+}
+
+#[test]
+fn keep_other_object_links() {
+    let mut note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "tag": [{
+            "type": "Link",
+            "mediaType": "application/activity+json",
+            "href": "https://example.com/notes/1",
+            "name": "https://example.com/notes/1"
+        }, {
+            "type": "Link",
+            "mediaType": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+            "href": "https://example.com/notes/2",
+            "name": "RE: https://example.com/notes/2"
+        }]
+    }))
+    .unwrap();
+    assert_eq!(note.quoted_object(), Some("https://example.com/notes/2"));
+
+    note.remove_quoted_object();
+    assert_eq!(note.quoted_object(), None);
+    assert_eq!(note.object_items.tag.len(), 1);
+    assert_eq!(
+        note.object_items.tag[0].as_link().unwrap().href,
+        "https://example.com/notes/1"
+    );
+}
+
+#[test]
+fn write_configured_quote_representations() {
+    let mut note = ap_model::Object::new(None, vec!["Note".to_string()]);
+    note.set_quoted_object("https://example.com/notes/1", &QuoteStyle::default());
+    note.set_quoted_object(
+        "https://example.com/notes/2",
+        &QuoteStyle {
+            quote_uri: false,
+            ..QuoteStyle::default()
+        },
+    );
+    let value = note.to_value().unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "@context": [
+                "https://www.w3.org/ns/activitystreams",
+                {
+                    "fedibird": "http://fedibird.com/ns#",
+                    "quoteUri": "fedibird:quoteUri",
+                    "quoteUrl": "as:quoteUrl",
+                    "misskey": "https://misskey-hub.net/ns#",
+                    "_misskey_quote": "misskey:_misskey_quote"
+                }
+            ],
+            "type": "Note",
+            "quoteUrl": "https://example.com/notes/2",
+            "_misskey_quote": "https://example.com/notes/2",
+            "tag": {
+                "type": "Link",
+                "mediaType": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"",
+                "href": "https://example.com/notes/2",
+                "rel": "https://misskey-hub.net/ns#_misskey_quote",
+                "name": "RE: https://example.com/notes/2"
+            }
+        })
+    );
+    let parsed = ap_model::Object::from_value(&value).unwrap();
+    assert_eq!(parsed.quoted_object(), Some("https://example.com/notes/2"));
+    let link = parsed.object_items.tag[0].as_link().unwrap();
+    assert_eq!(link.typ, vec!["Link".to_string()]);
+    assert_eq!(
+        link.media_type,
+        vec![activitist::quote::OBJECT_LINK_MEDIA_TYPE.to_string()]
+    );
+    assert_eq!(parsed, note);
+}
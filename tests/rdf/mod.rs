@@ -0,0 +1,93 @@
+pub mod turtle;
+
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::rdf::{self, Term, Triple};
+
+const AS: &str = "https://www.w3.org/ns/activitystreams#";
+
+fn triple(subject: Term, predicate: &str, object: Term) -> Triple {
+    Triple {
+        subject,
+        predicate: predicate.to_string(),
+        object,
+    }
+}
+
+#[test]
+fn object_to_triples() {
+    let data = ap_model::Object::from_json_str(
+        r##"{
+            "@context": "https://www.w3.org/ns/activitystreams",
+            "id": "https://example.com/notes/1",
+            "type": "Note",
+            "attributedTo": "https://example.com/users/sample",
+            "contentMap": {"en": "Hello"},
+            "published": "2023-01-01T00:00:00Z",
+            "tag": {"type": "Hashtag", "name": "#rust"}
+        }"##,
+    )
+    .unwrap();
+    let triples = rdf::to_triples(&data).unwrap();
+    let note = Term::Iri("https://example.com/notes/1".to_string());
+
+    for expected in [
+        triple(
+            note.clone(),
+            rdf::RDF_TYPE,
+            Term::Iri(format!("{}Note", AS)),
+        ),
+        triple(
+            note.clone(),
+            &format!("{}attributedTo", AS),
+            Term::Iri("https://example.com/users/sample".to_string()),
+        ),
+        triple(
+            note.clone(),
+            &format!("{}content", AS),
+            Term::Literal {
+                value: "Hello".to_string(),
+                datatype: Some(rdf::RDF_LANG_STRING.to_string()),
+                language: Some("en".to_string()),
+            },
+        ),
+        triple(
+            note.clone(),
+            &format!("{}published", AS),
+            Term::typed_literal(
+                "2023-01-01T00:00:00Z",
+                "http://www.w3.org/2001/XMLSchema#dateTime",
+            ),
+        ),
+        triple(
+            note.clone(),
+            &format!("{}tag", AS),
+            Term::BlankNode("b0".to_string()),
+        ),
+        triple(
+            Term::BlankNode("b0".to_string()),
+            &format!("{}name", AS),
+            Term::literal("#rust"),
+        ),
+    ] {
+        assert!(triples.contains(&expected), "missing {:?}", expected);
+    }
+}
+
+#[test]
+fn write_n_quads() {
+    let triples = vec![triple(
+        Term::Iri("https://example.com/notes/1".to_string()),
+        &format!("{}content", AS),
+        Term::literal("say \"hi\"\n"),
+    )];
+
+    assert_eq!(
+        rdf::to_n_triples(&triples),
+        "<https://example.com/notes/1> <https://www.w3.org/ns/activitystreams#content> \"say \\\"hi\\\"\\n\" .\n",
+    );
+    assert_eq!(
+        rdf::to_n_quads(&triples, Some("https://example.com/graph")),
+        "<https://example.com/notes/1> <https://www.w3.org/ns/activitystreams#content> \"say \\\"hi\\\"\\n\" <https://example.com/graph> .\n",
+    );
+}
@@ -0,0 +1,58 @@
+use activitist::rdf::{self, turtle, Term, Triple};
+
+const SERIALIZED_NOTE: &str = r#"@prefix as: <https://www.w3.org/ns/activitystreams#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+@base <https://example.com/> .
+
+<notes/1> a as:Note ;
+    as:attributedTo <users/sample> ;
+    as:content "Hello"@en, """multi
+line""" ;
+    as:tag [ a as:Mention ; as:href <users/other> ] ;
+    as:published "2023-01-01T00:00:00Z"^^xsd:dateTime .
+"#;
+
+#[test]
+fn parse_turtle() {
+    let triples = turtle::from_turtle(SERIALIZED_NOTE).unwrap();
+    let note = Term::Iri("https://example.com/notes/1".to_string());
+
+    assert_eq!(triples.len(), 8);
+    assert_eq!(
+        triples[0],
+        Triple {
+            subject: note.clone(),
+            predicate: rdf::RDF_TYPE.to_string(),
+            object: Term::Iri("https://www.w3.org/ns/activitystreams#Note".to_string()),
+        },
+    );
+    assert_eq!(triples[3].object, Term::literal("multi\nline"),);
+
+    let data = rdf::from_triples(&triples, &note).unwrap();
+    assert_eq!(data.id, Some("https://example.com/notes/1".to_string()));
+    assert_eq!(data.typ, vec!["Note".to_string()]);
+    assert_eq!(
+        data.object_items.content_map.get("en"),
+        Some(&"Hello".to_string()),
+    );
+    assert_eq!(data.object_items.tag.len(), 1);
+}
+
+#[test]
+fn turtle_round_trip() {
+    let triples = turtle::from_turtle(SERIALIZED_NOTE).unwrap();
+    let serialized = turtle::to_turtle(
+        &triples,
+        &[
+            ("as", "https://www.w3.org/ns/activitystreams#"),
+            ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+        ],
+    );
+
+    assert!(serialized.contains("<https://example.com/notes/1>\n    a as:Note ;"));
+    let parsed = turtle::from_turtle(&serialized).unwrap();
+    assert_eq!(parsed.len(), triples.len());
+    for triple in triples.iter() {
+        assert!(parsed.contains(triple), "missing {:?}", triple);
+    }
+}
@@ -0,0 +1,124 @@
+use std::error::Error;
+
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::relay::{unwrap_relayed, Relay, RelayKind};
+use serde_json::{json, Value};
+
+fn relay(kind: RelayKind) -> Relay {
+    Relay {
+        actor: "https://relay.example/actor".to_string(),
+        inbox: "https://relay.example/inbox".to_string(),
+        kind,
+    }
+}
+
+fn remote(iri: &str) -> Result<ap_model::Object, Box<dyn Error>> {
+    match iri {
+        "https://remote.example/notes/1" => ap_model::Object::from_value(&json!({
+            "type": "Note",
+            "id": iri,
+            "attributedTo": "https://remote.example/users/bob",
+            "content": "Hello"
+        })),
+        "https://remote.example/activities/1" => ap_model::Object::from_value(&json!({
+            "type": "Create",
+            "id": iri,
+            "actor": "https://remote.example/users/bob",
+            "object": "https://remote.example/notes/1"
+        })),
+        "https://remote.example/notes/forged" => ap_model::Object::from_value(&json!({
+            "type": "Note",
+            "id": iri,
+            "attributedTo": "https://other.example/users/carol",
+            "content": "Hello"
+        })),
+        _ => Err(format!("{} is not found", iri).into()),
+    }
+}
+
+#[test]
+fn subscribe() {
+    let instance = "https://local.example/actor";
+    let mastodon = relay(RelayKind::Mastodon).new_subscribe(
+        Some("https://local.example/follows/1".to_string()),
+        instance,
+    );
+    assert_eq!(
+        mastodon.activity_items.object[0].id(),
+        Some("https://www.w3.org/ns/activitystreams#Public")
+    );
+    let relay = relay(RelayKind::LitePub);
+    let litepub = relay.new_subscribe(None, instance);
+    assert_eq!(
+        litepub.activity_items.object[0].id(),
+        Some("https://relay.example/actor")
+    );
+
+    let undo = relay.new_unsubscribe(None, &litepub).unwrap();
+    assert_eq!(undo.typ, vec!["Undo"]);
+    assert_eq!(undo.activity_items.actor[0].id(), Some(instance));
+    assert!(relay.new_unsubscribe(None, &undo).is_err());
+
+    let accept = ap_model::Object::from_value(&json!({
+        "type": "Accept",
+        "actor": "https://relay.example/actor",
+        "object": "https://local.example/follows/1"
+    }))
+    .unwrap();
+    assert!(relay.is_accept(&accept));
+}
+
+#[test]
+fn unwrap_relayed_activities() {
+    let relays = [relay(RelayKind::LitePub)];
+    let signer = "https://relay.example/actor";
+
+    // The embedded copy is replaced with the one of its origin.
+    let announce = ap_model::Object::from_value(&json!({
+        "type": "Announce",
+        "actor": signer,
+        "object": {
+            "type": "Note",
+            "id": "https://remote.example/notes/1",
+            "content": "Tampered"
+        }
+    }))
+    .unwrap();
+    let note = unwrap_relayed(&relays, signer, &announce, &remote)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        note.to_value().unwrap()["content"],
+        Value::String("Hello".to_string())
+    );
+
+    let forwarded = ap_model::Object::from_value(&json!({
+        "type": "Create",
+        "id": "https://remote.example/activities/1",
+        "actor": "https://remote.example/users/bob",
+        "object": "https://remote.example/notes/1"
+    }))
+    .unwrap();
+    let create = unwrap_relayed(&relays, signer, &forwarded, &remote)
+        .unwrap()
+        .unwrap();
+    assert_eq!(create.typ, vec!["Create"]);
+    assert!(unwrap_relayed(
+        &relays,
+        "https://remote.example/users/bob",
+        &forwarded,
+        &remote
+    )
+    .is_none());
+
+    let forged = ap_model::Object::from_value(&json!({
+        "type": "Announce",
+        "actor": signer,
+        "object": "https://remote.example/notes/forged"
+    }))
+    .unwrap();
+    assert!(unwrap_relayed(&relays, signer, &forged, &remote)
+        .unwrap()
+        .is_err());
+}
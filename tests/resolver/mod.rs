@@ -0,0 +1,44 @@
+use activitist::model::{self as ap_model, Map};
+use activitist::resolver::Resolver;
+
+fn store() -> Map<String, ap_model::Object> {
+    let mut store = Map::new();
+    store.insert(
+        "https://example.com/notes/1".to_string(),
+        ap_model::Object::new(
+            Some("https://example.com/notes/1".to_string()),
+            vec!["Note".to_string()],
+        ),
+    );
+    store
+}
+
+#[test]
+fn fetch_linked_object() {
+    let store = store();
+    let reference =
+        ap_model::ObjectOrLink::Link(ap_model::Link::simple("https://example.com/notes/1"));
+
+    let object = reference.fetch_object(&store).unwrap();
+    assert_eq!(object.id.as_deref(), Some("https://example.com/notes/1"));
+
+    let missing =
+        ap_model::ObjectOrLink::Link(ap_model::Link::simple("https://example.com/notes/2"));
+    assert_eq!(
+        missing.fetch_object(&store).unwrap_err().to_string(),
+        "https://example.com/notes/2 is not found",
+    );
+}
+
+#[test]
+fn replace_link_in_place() {
+    let resolver = |iri: &str| store().resolve(iri);
+    let mut reference =
+        ap_model::ObjectOrLink::Link(ap_model::Link::simple("https://example.com/notes/1"));
+
+    reference.fetch_object_in_place(&resolver).unwrap();
+    assert!(matches!(
+        reference,
+        ap_model::ObjectOrLink::Object(ref object) if object.typ == vec!["Note".to_string()]
+    ));
+}
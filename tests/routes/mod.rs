@@ -0,0 +1,80 @@
+use activitist::id::{IdMinter, RoutedMinter, SlugMinter};
+use activitist::model as ap_model;
+use activitist::routes::{Locality, Route, Routes, Tenants};
+
+#[test]
+fn generate_and_parse_routes() {
+    let routes = Routes::new("https://example.com/social/").unwrap();
+
+    assert_eq!(
+        routes.actor_id("alice"),
+        "https://example.com/social/users/alice"
+    );
+    let actor = routes.new_actor("alice", ap_model::ActorType::Person);
+    let actor_items = actor.actor_items.as_ref().unwrap();
+    assert_eq!(
+        actor_items.inbox,
+        "https://example.com/social/users/alice/inbox"
+    );
+    assert_eq!(
+        actor_items.endpoints["sharedInbox"],
+        "https://example.com/social/inbox"
+    );
+
+    for (iri, route) in [
+        (
+            "https://example.com/social/users/alice",
+            Some(Route::Actor("alice".to_string())),
+        ),
+        (
+            "https://example.com/social/users/alice/outbox",
+            Some(Route::Outbox("alice".to_string())),
+        ),
+        (
+            "https://example.com/social/objects/1",
+            Some(Route::Object("1".to_string())),
+        ),
+        ("https://example.com/social/inbox", Some(Route::SharedInbox)),
+        ("https://example.com/users/alice", None),
+        ("https://other.example/social/users/alice", None),
+        ("https://example.com/social/users/alice/statuses/1", None),
+    ] {
+        assert_eq!(routes.parse(iri), route, "{}", iri);
+    }
+}
+
+#[test]
+fn mint_routed_ids() {
+    let mut minter = RoutedMinter {
+        routes: Routes::new("https://example.com").unwrap(),
+        tokens: SlugMinter::new(""),
+    };
+    let like = ap_model::Object::new(None, vec!["Like".to_string()]);
+    let note = ap_model::Object::new(None, vec!["Note".to_string()]);
+
+    assert_eq!(minter.mint(&like), "https://example.com/activities/like");
+    assert_eq!(minter.mint(&note), "https://example.com/objects/note");
+}
+
+#[test]
+fn resolve_tenants() {
+    let tenants = Tenants::new(vec![
+        Routes::new("https://example.com/").unwrap(),
+        Routes::new("https://example.com/b/").unwrap(),
+        Routes::new("https://other.example/").unwrap(),
+    ]);
+
+    assert!(tenants.is_local("https://other.example/objects/1"));
+    assert!(!tenants.is_local("https://remote.example/objects/1"));
+    assert_eq!(
+        tenants
+            .routes_of("https://example.com/b/users/bob")
+            .map(|routes| routes.base_url.as_str()),
+        Some("https://example.com/b/")
+    );
+    assert_eq!(
+        tenants.parse("https://example.com/b/users/bob"),
+        Some(Route::Actor("bob".to_string()))
+    );
+    assert_eq!(tenants.parse("https://example.com/bob/users/bob"), None);
+}
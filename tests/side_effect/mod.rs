@@ -0,0 +1,89 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::side_effect::{self, RepliesUpdate};
+use activitist::store::{MemoryStore, ObjectStore};
+use serde_json::json;
+use url::Url;
+
+fn reply(id: &str, in_reply_to: &str) -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": id,
+        "inReplyTo": in_reply_to
+    }))
+    .unwrap()
+}
+
+#[test]
+fn add_replies_to_local_objects() {
+    let base = Url::parse("https://example.com/").unwrap();
+    let store = MemoryStore::new();
+    for value in [
+        json!({
+            "type": "Note",
+            "id": "https://example.com/notes/1"
+        }),
+        json!({
+            "type": "Note",
+            "id": "https://example.com/notes/2",
+            "replies": { "id": "https://example.com/notes/2/replies" }
+        }),
+        json!({
+            "type": "OrderedCollection",
+            "id": "https://example.com/notes/2/replies",
+            "totalItems": 1,
+            "orderedItems": ["https://other.example/notes/1"]
+        }),
+    ] {
+        store
+            .put(ap_model::Object::from_value(&value).unwrap())
+            .unwrap();
+    }
+
+    let first = reply(
+        "https://remote.example/notes/1",
+        "https://example.com/notes/1",
+    );
+    let update = vec![RepliesUpdate {
+        object: "https://example.com/notes/1".to_string(),
+        total_items: 1,
+    }];
+    assert_eq!(
+        side_effect::add_reply(&store, &base, &first).unwrap(),
+        update
+    );
+    assert!(side_effect::add_reply(&store, &base, &first)
+        .unwrap()
+        .is_empty());
+    let note = store.get("https://example.com/notes/1").unwrap().unwrap();
+    assert_eq!(
+        note.to_value().unwrap()["replies"],
+        json!({
+            "type": "Collection",
+            "totalItems": 1,
+            "items": "https://remote.example/notes/1"
+        })
+    );
+
+    let second = reply(
+        "https://remote.example/notes/2",
+        "https://example.com/notes/2",
+    );
+    assert_eq!(
+        side_effect::add_reply(&store, &base, &second).unwrap()[0].total_items,
+        2
+    );
+    let replies = store
+        .get("https://example.com/notes/2/replies")
+        .unwrap()
+        .unwrap();
+    assert_eq!(replies.ordered_collection_items.ordered_items.len(), 2);
+
+    let remote = reply(
+        "https://remote.example/notes/3",
+        "https://other.example/notes/1",
+    );
+    assert!(side_effect::add_reply(&store, &base, &remote)
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,54 @@
+#[cfg(feature = "signing")]
+pub mod request;
+
+use activitist::signature::{SignatureAlgorithm, SignatureConfig};
+
+#[test]
+fn downgrade_rejected_signatures() {
+    let mut config = SignatureConfig::new(vec![
+        SignatureAlgorithm::Rfc9421,
+        SignatureAlgorithm::Hs2019,
+        SignatureAlgorithm::RsaSha256,
+    ]);
+    config.host_overrides.insert(
+        "legacy.example".to_string(),
+        vec![SignatureAlgorithm::RsaSha256],
+    );
+
+    // The peer verifies only the draft signatures with `rsa-sha256`.
+    let mut tried = vec![];
+    let status = config
+        .negotiate("remote.example", |algorithm| {
+            tried.push(algorithm.identifier());
+            Ok(if algorithm == SignatureAlgorithm::RsaSha256 {
+                202
+            } else {
+                401
+            })
+        })
+        .unwrap();
+    assert_eq!(status, 202);
+    assert_eq!(tried, vec!["rsa-v1_5-sha256", "hs2019", "rsa-sha256"]);
+    assert_eq!(
+        config.algorithms_for("remote.example"),
+        vec![
+            SignatureAlgorithm::RsaSha256,
+            SignatureAlgorithm::Rfc9421,
+            SignatureAlgorithm::Hs2019
+        ]
+    );
+    assert_eq!(
+        config.algorithms_for("legacy.example"),
+        vec![SignatureAlgorithm::RsaSha256]
+    );
+
+    // Other failures are not retried.
+    let mut attempts = 0;
+    let status = config
+        .negotiate("down.example", |_| {
+            attempts += 1;
+            Ok(503)
+        })
+        .unwrap();
+    assert_eq!((status, attempts), (503, 1));
+}
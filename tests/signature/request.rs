@@ -0,0 +1,71 @@
+use activitist::signature::request::{self, Signer};
+use activitist::signature::SignatureAlgorithm;
+use chrono::{DateTime, Utc};
+
+const PRIVATE_KEY_PEM: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/keys/private.pem"
+));
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+fn signer() -> Signer {
+    Signer::from_pem("https://example.com/users/alice#main-key", PRIVATE_KEY_PEM).unwrap()
+}
+
+#[test]
+fn sign_draft_signature() {
+    let signed = request::signed_post(
+        &signer(),
+        SignatureAlgorithm::RsaSha256,
+        "https://remote.example/users/bob/inbox",
+        br#"{"type":"Like"}"#.to_vec(),
+        &now(),
+    )
+    .unwrap();
+
+    assert_eq!(signed.method, "POST");
+    assert_eq!(signed.header("host"), Some("remote.example"));
+    assert_eq!(signed.header("date"), Some("Mon, 01 Jan 2024 00:00:00 GMT"));
+    assert_eq!(
+        signed.header("digest"),
+        Some("SHA-256=fQLcyuiC5b53id2wfhThCI+LFjFoCGXZfRwgMiR7Fp0=")
+    );
+    // Signed by `openssl dgst -sha256 -sign` with the same key.
+    assert_eq!(
+        signed.header("signature"),
+        Some(concat!(
+            r#"keyId="https://example.com/users/alice#main-key",algorithm="rsa-sha256","#,
+            r#"headers="(request-target) host date digest","#,
+            r#"signature="IyPwrHukpS3rwvkDr1z5vWDtjGHHbGpfd/vHGgwJwL4bBjm4PYMm79QFUAWk8Ehr8A7KpbjBlGJoScDU/7iS9HlikiLXlc/udF/FzPDA54N2JeCjLbSBEpTO/97FSuFJvsBomfJzs/H67Yb57my8wH2H+tNyHQkO9Zo0tUBWVC0O1uzMkJR29L1z00JnRHnRxNhRAihwcrwhfAaSfHhbFIOYbu/v9uf4Jx/Kv+bXfEE7MR8l31oJbm+z43KA13cTJFrOotTashuhz7+/XoRx26VfMkwv6GeaB84dhjPgCIzWBuFQSOaj45g5JAlgWFIuDT0IxcFd1Z5ou5iDqVPM6w==""#
+        ))
+    );
+}
+
+#[test]
+fn sign_message_signature() {
+    let signed = request::signed_post(
+        &signer(),
+        SignatureAlgorithm::Rfc9421,
+        "https://remote.example/users/bob/inbox",
+        br#"{"type":"Like"}"#.to_vec(),
+        &now(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        signed.header("content-digest"),
+        Some("sha-256=:fQLcyuiC5b53id2wfhThCI+LFjFoCGXZfRwgMiR7Fp0=:")
+    );
+    assert_eq!(
+        signed.header("signature-input"),
+        Some(concat!(
+            r#"sig1=("@method" "@target-uri" "content-digest" "content-type");"#,
+            r#"created=1704067200;keyid="https://example.com/users/alice#main-key";alg="rsa-v1_5-sha256""#
+        ))
+    );
+    assert!(signed.header("signature").unwrap().starts_with("sig1=:"));
+    assert!(!format!("{:?}", signer()).contains("PRIVATE"));
+}
@@ -0,0 +1,202 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::side_effect;
+use activitist::store::history::{Edit, MemoryHistory, VersionedStore};
+use activitist::store::{MemoryStore, ObjectStore};
+use serde_json::{json, Value};
+use url::Url;
+
+fn update(note: Value) -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "type": "Update",
+        "actor": "https://example.com/users/alice",
+        "object": note
+    }))
+    .unwrap()
+}
+
+#[test]
+fn keep_revisions_of_updates() {
+    let store = VersionedStore::new(MemoryStore::new(), MemoryHistory::new());
+    store
+        .put(
+            ap_model::Object::from_value(&json!({
+                "type": "Note",
+                "id": "https://example.com/notes/1",
+                "content": "Helo",
+                "published": "2024-01-01T00:00:00Z"
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+    assert!(store
+        .edits("https://example.com/notes/1")
+        .unwrap()
+        .is_empty());
+
+    store
+        .apply_update(&update(json!({
+            "type": "Note",
+            "id": "https://example.com/notes/1",
+            "content": "Hello",
+            "sensitive": true,
+            "published": "2024-01-01T00:00:00Z",
+            "updated": "2024-01-02T00:00:00Z"
+        })))
+        .unwrap();
+
+    let history = store.history("https://example.com/notes/1").unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        store.edits("https://example.com/notes/1").unwrap(),
+        vec![
+            Edit {
+                content: Some("Helo".to_string()),
+                summary: None,
+                sensitive: false,
+                created_at: history[0].object_items.published,
+            },
+            Edit {
+                content: Some("Hello".to_string()),
+                summary: None,
+                sensitive: true,
+                created_at: history[1].object_items.updated,
+            },
+        ]
+    );
+
+    assert!(store
+        .apply_update(&update(json!("https://example.com/notes/1")))
+        .is_err());
+
+    store.delete("https://example.com/notes/1").unwrap();
+    assert!(store
+        .history("https://example.com/notes/1")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn keep_replies_out_of_edits() {
+    let store = VersionedStore::new(MemoryStore::new(), MemoryHistory::new());
+    let base = Url::parse("https://example.com/").unwrap();
+    let reply = |n: u32| {
+        ap_model::Object::from_value(&json!({
+            "type": "Note",
+            "id": format!("https://remote.example/notes/{}", n),
+            "inReplyTo": "https://example.com/notes/1"
+        }))
+        .unwrap()
+    };
+    store
+        .put(
+            ap_model::Object::from_value(&json!({
+                "type": "Note",
+                "id": "https://example.com/notes/1",
+                "content": "Helo",
+                "published": "2024-01-01T00:00:00Z"
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+    side_effect::add_reply(&store, &base, &reply(1)).unwrap();
+    assert!(store
+        .edits("https://example.com/notes/1")
+        .unwrap()
+        .is_empty());
+
+    let mut edited = store.get("https://example.com/notes/1").unwrap().unwrap();
+    edited.object_items.content = vec!["Hello".to_string()];
+    edited.object_items.updated = "2024-01-02T00:00:00Z".parse().ok();
+    store
+        .apply_update(&update(edited.to_value().unwrap()))
+        .unwrap();
+    side_effect::add_reply(&store, &base, &reply(2)).unwrap();
+
+    let edits = store.edits("https://example.com/notes/1").unwrap();
+    assert_eq!(
+        edits
+            .iter()
+            .map(|edit| edit.content.as_deref().unwrap())
+            .collect::<Vec<_>>(),
+        vec!["Helo", "Hello"]
+    );
+    let current = store.get("https://example.com/notes/1").unwrap().unwrap();
+    assert_eq!(
+        current
+            .object_items
+            .replies
+            .unwrap()
+            .collection_items
+            .total_items,
+        Some(2)
+    );
+}
+
+#[test]
+fn keep_revisions_of_other_edits() {
+    let note = json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "content": "Hello",
+        "contentMap": {"en": "Hello"},
+        "published": "2024-01-01T00:00:00Z"
+    });
+    let revisions = |edited: Value| {
+        let store = VersionedStore::new(MemoryStore::new(), MemoryHistory::new());
+        store
+            .put(ap_model::Object::from_value(&note).unwrap())
+            .unwrap();
+        store.apply_update(&update(edited)).unwrap();
+        store.history("https://example.com/notes/1").unwrap().len()
+    };
+
+    let mut attached = note.clone();
+    attached["attachment"] = json!({
+        "type": "Document",
+        "mediaType": "image/png",
+        "url": "https://example.com/media/1.png"
+    });
+    assert_eq!(revisions(attached), 2);
+
+    let mut translated = note.clone();
+    translated["contentMap"] = json!({"en": "Hello", "ja": "こんにちは"});
+    assert_eq!(revisions(translated), 2);
+
+    let mut touched = note.clone();
+    touched["updated"] = json!("2024-01-02T00:00:00Z");
+    assert_eq!(revisions(touched), 1);
+}
+
+#[cfg(feature = "question")]
+#[test]
+fn keep_votes_out_of_edits() {
+    let poll = |option: &str, votes: u32| {
+        json!({
+            "type": "Question",
+            "id": "https://example.com/notes/1",
+            "content": "Which?",
+            "oneOf": [{
+                "type": "Note",
+                "name": option,
+                "replies": {"type": "Collection", "totalItems": votes}
+            }]
+        })
+    };
+    let store = VersionedStore::new(MemoryStore::new(), MemoryHistory::new());
+    store
+        .put(ap_model::Object::from_value(&poll("Tea", 0)).unwrap())
+        .unwrap();
+
+    store.apply_update(&update(poll("Tea", 3))).unwrap();
+    assert_eq!(
+        store.history("https://example.com/notes/1").unwrap().len(),
+        1
+    );
+    store.apply_update(&update(poll("Coffee", 3))).unwrap();
+    assert_eq!(
+        store.history("https://example.com/notes/1").unwrap().len(),
+        2
+    );
+}
@@ -0,0 +1,32 @@
+pub mod history;
+
+use activitist::model as ap_model;
+use activitist::store::{MemoryStore, ObjectStore};
+
+#[test]
+fn memory_store() {
+    let store = MemoryStore::new();
+    let note = ap_model::Object::new(
+        Some("https://example.com/notes/1".to_string()),
+        vec!["Note".to_string()],
+    );
+
+    store.put(note.clone()).unwrap();
+    assert_eq!(
+        store.get("https://example.com/notes/1").unwrap(),
+        Some(note.clone())
+    );
+    assert_eq!(store.len(), 1);
+
+    assert_eq!(
+        store.delete("https://example.com/notes/1").unwrap(),
+        Some(note)
+    );
+    assert_eq!(store.get("https://example.com/notes/1").unwrap(), None);
+
+    let anonymous = ap_model::Object::new(None, vec!["Note".to_string()]);
+    assert_eq!(
+        store.put(anonymous).unwrap_err().to_string(),
+        "object has no id"
+    );
+}
@@ -0,0 +1,145 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::store::{MemoryStore, ObjectStore};
+use activitist::suspension::{self, ActorRemoval};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+fn now() -> DateTime<Utc> {
+    "2024-01-01T00:00:00Z".parse().unwrap()
+}
+
+fn remote_store() -> MemoryStore {
+    let store = MemoryStore::new();
+    for value in [
+        json!({
+            "type": "Person",
+            "id": "https://remote.example/users/bob",
+            "inbox": "https://remote.example/users/bob/inbox",
+            "outbox": "https://remote.example/users/bob/outbox",
+            "following": "https://remote.example/users/bob/following",
+            "followers": "https://remote.example/users/bob/followers"
+        }),
+        json!({
+            "type": "Note",
+            "id": "https://remote.example/notes/1",
+            "attributedTo": "https://remote.example/users/bob",
+            "content": "Hello"
+        }),
+        json!({
+            "type": "Note",
+            "id": "https://other.example/notes/1",
+            "attributedTo": "https://other.example/users/carol",
+            "content": "Hi"
+        }),
+    ] {
+        store
+            .put(ap_model::Object::from_value(&value).unwrap())
+            .unwrap();
+    }
+    store
+}
+
+#[test]
+#[cfg(feature = "mastodon")]
+fn federate_suspension_of_local_actor() {
+    let mut actor = ap_model::Object::from_value(&json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "inbox": "https://example.com/users/alice/inbox",
+        "outbox": "https://example.com/users/alice/outbox",
+        "following": "https://example.com/users/alice/following",
+        "followers": "https://example.com/users/alice/followers"
+    }))
+    .unwrap();
+
+    assert!(suspension::suspend(&mut actor, now()));
+    assert!(!suspension::suspend(&mut actor, now()));
+    assert!(suspension::is_suspended(&actor));
+
+    let update = suspension::new_suspension_update(None, &actor).unwrap();
+    assert_eq!(
+        suspension::actor_removal(&update),
+        Some(("https://example.com/users/alice", ActorRemoval::Suspended)),
+    );
+    let value = update.to_value().unwrap();
+    assert_eq!(value["to"], "https://www.w3.org/ns/activitystreams#Public");
+    assert_eq!(value["object"]["suspended"], true);
+
+    assert!(suspension::unsuspend(&mut actor, now()));
+    assert!(!suspension::is_suspended(&actor));
+}
+
+#[test]
+fn tombstone_objects_of_deleted_actor() {
+    let store = remote_store();
+    let delete = suspension::new_actor_delete(None, "https://remote.example/users/bob");
+    assert_eq!(
+        suspension::actor_removal(&delete),
+        Some(("https://remote.example/users/bob", ActorRemoval::Deleted)),
+    );
+
+    let mut tombstoned = suspension::apply_actor_removal(&store, &delete, now()).unwrap();
+    tombstoned.sort();
+    assert_eq!(
+        tombstoned,
+        vec![
+            "https://remote.example/notes/1".to_string(),
+            "https://remote.example/users/bob".to_string(),
+        ],
+    );
+    let note = store
+        .get("https://remote.example/notes/1")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        note.to_value().unwrap(),
+        json!({
+            "type": "Tombstone",
+            "id": "https://remote.example/notes/1",
+            "deleted": "2024-01-01T00:00:00Z"
+        }),
+    );
+    let other = store.get("https://other.example/notes/1").unwrap().unwrap();
+    assert_eq!(other.typ, vec!["Note".to_string()]);
+    assert!(suspension::apply_actor_removal(&store, &delete, now())
+        .unwrap()
+        .is_empty());
+
+    let forged = ap_model::Object::from_value(&json!({
+        "type": "Delete",
+        "actor": "https://other.example/users/carol",
+        "object": "https://other.example/notes/1"
+    }))
+    .unwrap();
+    assert_eq!(suspension::actor_removal(&forged), None);
+}
+
+#[test]
+fn tombstone_objects_of_suspended_actor() {
+    let store = remote_store();
+    let update = ap_model::Object::from_value(&json!({
+        "type": "Update",
+        "actor": "https://remote.example/users/bob",
+        "object": {
+            "type": "Person",
+            "id": "https://remote.example/users/bob",
+            "inbox": "https://remote.example/users/bob/inbox",
+            "outbox": "https://remote.example/users/bob/outbox",
+            "following": "https://remote.example/users/bob/following",
+            "followers": "https://remote.example/users/bob/followers",
+            "suspended": true
+        }
+    }))
+    .unwrap();
+
+    assert_eq!(
+        suspension::apply_actor_removal(&store, &update, now()).unwrap(),
+        vec!["https://remote.example/notes/1".to_string()],
+    );
+    let actor = store
+        .get("https://remote.example/users/bob")
+        .unwrap()
+        .unwrap();
+    assert!(suspension::is_suspended(&actor));
+}
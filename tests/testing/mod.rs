@@ -0,0 +1,23 @@
+#[cfg(feature = "testing")]
+mod strategies {
+    use activitist::json::JsonSerde;
+    use activitist::model as ap_model;
+    use activitist::testing;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn round_trip_actors(actor in testing::actor()) {
+            let serialized = actor.to_json_bytes().unwrap();
+            prop_assert_eq!(ap_model::Object::from_json_bytes(&serialized).unwrap(), actor);
+        }
+
+        #[test]
+        fn round_trip_activities(
+            activity in prop_oneof![testing::activity(), testing::collection()],
+        ) {
+            let serialized = activity.to_json_bytes().unwrap();
+            prop_assert_eq!(ap_model::Object::from_json_bytes(&serialized).unwrap(), activity);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::visit::{self, Visitor, VisitorMut};
+use serde_json::json;
+
+fn create() -> ap_model::Object {
+    ap_model::Object::from_value(&json!({
+        "id": "https://old.example/activities/1",
+        "type": "Create",
+        "actor": "https://old.example/users/alice",
+        "object": {
+            "id": "https://old.example/notes/1",
+            "type": "Note",
+            "attachment": [
+                {"type": "Document", "mediaType": "image/png", "url": "https://old.example/media/1.png"}
+            ],
+            "tag": [{"type": "Mention", "href": "https://remote.example/users/bob"}]
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn collect_media() {
+    struct Media(Vec<String>);
+
+    impl Visitor for Media {
+        fn visit_object(&mut self, object: &ap_model::Object) {
+            if object.typ.iter().any(|typ| typ == "Document") {
+                if let Some(url) = &object.object_items.url {
+                    self.0.push(url.href.clone());
+                }
+            }
+        }
+    }
+
+    let mut media = Media(vec![]);
+    visit::walk(&create(), &mut media);
+    assert_eq!(media.0, vec!["https://old.example/media/1.png".to_string()]);
+}
+
+#[test]
+fn rewrite_iris() {
+    struct Rewrite;
+
+    impl VisitorMut for Rewrite {
+        fn visit_iri(&mut self, iri: &mut String) {
+            if let Some(path) = iri.strip_prefix("https://old.example/") {
+                *iri = format!("https://new.example/{}", path);
+            }
+        }
+    }
+
+    let mut data = create();
+    visit::walk_mut(&mut data, &mut Rewrite);
+
+    let object = data.activity_items.object[0].as_object().unwrap();
+    assert_eq!(data.id.as_deref(), Some("https://new.example/activities/1"));
+    assert_eq!(
+        data.activity_items.actor[0].id(),
+        Some("https://new.example/users/alice")
+    );
+    assert_eq!(object.id.as_deref(), Some("https://new.example/notes/1"));
+    assert_eq!(
+        object.object_items.attachment[0].href(),
+        Some("https://new.example/media/1.png")
+    );
+    assert_eq!(
+        object.object_items.tag[0].id(),
+        Some("https://remote.example/users/bob")
+    );
+}
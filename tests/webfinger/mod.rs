@@ -0,0 +1,34 @@
+#[cfg(feature = "security")]
+use activitist::instance::InstanceActor;
+use activitist::webfinger;
+use serde_json::json;
+
+#[test]
+fn find_actor_in_jrd() {
+    assert_eq!(
+        webfinger::query_url("alice", "example.com")
+            .unwrap()
+            .as_str(),
+        "https://example.com/.well-known/webfinger?resource=acct%3Aalice%40example.com"
+    );
+
+    #[cfg(feature = "security")]
+    {
+        let instance = InstanceActor::new("https://example.com/", "PEM").unwrap();
+        assert_eq!(
+            webfinger::actor_of(&instance.webfinger()),
+            Some(instance.id())
+        );
+    }
+    let jrd = json!({
+        "links": [
+            {"rel": "http://webfinger.net/rel/profile-page", "type": "text/html", "href": "https://example.com/@alice"},
+            {"rel": "self", "type": "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\"", "href": "https://example.com/users/alice"}
+        ]
+    });
+    assert_eq!(
+        webfinger::actor_of(&jrd).as_deref(),
+        Some("https://example.com/users/alice")
+    );
+    assert_eq!(webfinger::actor_of(&json!({"links": []})), None);
+}
@@ -0,0 +1,57 @@
+use activitist::json::JsonSerde;
+use activitist::model as ap_model;
+use activitist::webmention;
+use serde_json::json;
+use url::Url;
+
+#[test]
+fn find_targets() {
+    let note = ap_model::Object::from_value(&json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "inReplyTo": "https://blog.example/posts/hello",
+        "content": concat!(
+            "<p><span class=\"h-card\"><a href=\"https://remote.example/@bob\" class=\"u-url mention\">@bob</a></span> ",
+            "<a href=\"https://example.com/tags/rust\" class=\"mention hashtag\" rel=\"tag\">#rust</a> ",
+            "see <a href=\"https://blog.example/posts/hello\">this</a> ",
+            "and <a href=\"https://other.example/about\">that</a></p>"
+        )
+    }))
+    .unwrap();
+    assert_eq!(
+        webmention::targets(&note),
+        vec![
+            "https://blog.example/posts/hello",
+            "https://other.example/about"
+        ]
+    );
+}
+
+#[test]
+fn discover_endpoints() {
+    let target = Url::parse("https://blog.example/posts/hello").unwrap();
+    assert_eq!(
+        webmention::endpoint_from_link_headers(
+            &target,
+            ["<https://blog.example/feed>; rel=\"alternate\", </webmention>; rel=\"webmention\""]
+        ),
+        Some("https://blog.example/webmention".to_string())
+    );
+    assert_eq!(
+        webmention::endpoint_from_link_headers(&target, ["<https://blog.example/>; rel=home"]),
+        None
+    );
+
+    let html = r#"<html><head>
+        <link rel="stylesheet" href="/style.css">
+        <link rel="webmention" href="">
+        </head><body><a rel="webmention" href="/other">x</a></body></html>"#;
+    assert_eq!(
+        webmention::endpoint_from_html(&target, html),
+        Some("https://blog.example/posts/hello".to_string())
+    );
+    assert!(!webmention::has_activity_pub_alternate(html));
+    assert!(webmention::has_activity_pub_alternate(
+        r#"<link rel="alternate" type="application/activity+json" href="https://blog.example/ap/hello">"#
+    ));
+}